@@ -0,0 +1,58 @@
+//! Translating the 1000 cells of a table on each of 4 threads sharing the global translator: one
+//! `i18n!` per cell takes the lock 1000 times, `translate_many` once.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use easy_i18n::{i18n, translate_many, I18n, I18N};
+use std::time::{Duration, Instant};
+
+const CELLS: usize = 1000;
+const THREADS: usize = 4;
+
+/// Runs `render` on every thread `iters` times, returning the slowest thread's time.
+fn contended(iters: u64, render: fn(&[String])) -> Duration {
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            std::thread::spawn(move || {
+                let cells: Vec<String> = (0..CELLS).map(|i| format!("状态{}", i % 50)).collect();
+                let start = Instant::now();
+                for _ in 0..iters {
+                    render(&cells);
+                }
+                start.elapsed()
+            })
+        })
+        .collect();
+    threads
+        .into_iter()
+        .map(|t| t.join().unwrap())
+        .max()
+        .unwrap()
+}
+
+fn batch(c: &mut Criterion) {
+    let mut i18n = I18n::new("en");
+    for i in 0..50 {
+        i18n.set_override("en", "common", &format!("状态{i}"), &format!("Status {i}"));
+    }
+    *I18N.lock().unwrap() = i18n;
+    let mut group = c.benchmark_group("1000 cells, 4 threads");
+    group.bench_function("i18n! per cell", |b| {
+        b.iter_custom(|iters| {
+            contended(iters, |cells| {
+                for cell in cells {
+                    black_box(i18n!(cell));
+                }
+            })
+        })
+    });
+    group.bench_function("translate_many", |b| {
+        b.iter_custom(|iters| {
+            contended(iters, |cells| {
+                black_box(translate_many(cells.iter().map(String::as_str), None));
+            })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, batch);
+criterion_main!(benches);
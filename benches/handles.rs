@@ -0,0 +1,42 @@
+//! Looking up one of 10k keys every frame: a string lookup hashes the key through the namespace
+//! chain and allocates the result, a handle indexes the table resolved after the last change.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use easy_i18n::I18n;
+
+const KEYS: usize = 10_000;
+
+fn lookup(c: &mut Criterion) {
+    let mut i18n = I18n::new("en");
+    let keys: Vec<String> = (0..KEYS).map(|i| format!("hud.label.{i}")).collect();
+    for (i, key) in keys.iter().enumerate() {
+        i18n.set_override("en", "common", key, &format!("Label {i}"));
+    }
+    let handles: Vec<_> = keys.iter().map(|key| i18n.key_handle(key, None)).collect();
+    let table = i18n.msg_table();
+    let mut group = c.benchmark_group("10k keys");
+    group.bench_function("translate", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 7919) % KEYS;
+            i18n.translate(black_box(&keys[i]), None)
+        })
+    });
+    group.bench_function("translate_handle", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 7919) % KEYS;
+            i18n.translate_handle(black_box(handles[i])).len()
+        })
+    });
+    group.bench_function("table snapshot", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 7919) % KEYS;
+            table.get(black_box(handles[i])).len()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
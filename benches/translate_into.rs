@@ -0,0 +1,67 @@
+//! Appending 10k translated fragments to one document: `translate`/`trans_with_inter` allocate a
+//! `String` per fragment, `translate_into`/`trans_with_inter_into` write into the buffer.
+use criterion::{criterion_group, criterion_main, Criterion};
+use easy_i18n::I18n;
+use std::fmt::Write;
+use std::path::Path;
+
+const FRAGMENTS: usize = 10_000;
+
+fn i18n() -> I18n {
+    let mut i18n = I18n::new("en");
+    i18n.set_source(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/source")));
+    i18n
+}
+
+fn append(c: &mut Criterion) {
+    let i18n = i18n();
+    let mut group = c.benchmark_group("append 10k fragments");
+    group.bench_function("translate", |b| {
+        b.iter(|| {
+            let mut doc = String::new();
+            for _ in 0..FRAGMENTS {
+                doc.push_str(&i18n.translate("这是一个测试", None));
+            }
+            doc
+        })
+    });
+    group.bench_function("translate_into", |b| {
+        b.iter(|| {
+            let mut doc = String::new();
+            for _ in 0..FRAGMENTS {
+                i18n.translate_into(&mut doc, "这是一个测试", None).unwrap();
+            }
+            doc
+        })
+    });
+    group.bench_function("trans_with_inter", |b| {
+        b.iter(|| {
+            let mut doc = String::new();
+            for i in 0..FRAGMENTS {
+                let vals = vec![i.to_string(), "100".to_string()];
+                doc.push_str(&i18n.trans_with_inter("他的成绩是，语文：%1, 数学：%2", vals, None));
+            }
+            doc
+        })
+    });
+    group.bench_function("trans_with_inter_into", |b| {
+        b.iter(|| {
+            let mut doc = String::new();
+            for i in 0..FRAGMENTS {
+                i18n.trans_with_inter_into(
+                    &mut doc,
+                    "他的成绩是，语文：%1, 数学：%2",
+                    &[&i, &100],
+                    None,
+                )
+                .unwrap();
+                doc.write_char('\n').unwrap();
+            }
+            doc
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, append);
+criterion_main!(benches);
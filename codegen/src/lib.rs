@@ -0,0 +1,306 @@
+//! Typed keys for [easy_i18n](https://docs.rs/easy_i18n), generated from the language files by a
+//! build script, so that a key removed from the catalogs is a compile error instead of an
+//! untranslated label.
+//! ### Example
+//! ``` rust,no_run
+//! // build.rs
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("keys.rs");
+//! easy_i18n_codegen::generate("./locales", &out).unwrap();
+//! ```
+//! ``` rust,ignore
+//! // src/main.rs
+//! mod keys {
+//!     include!(concat!(env!("OUT_DIR"), "/keys.rs"));
+//! }
+//! i18n!(keys::checkout::CONFIRM_ORDER);
+//! i18n!(keys::common::K_E2B9C0F7, 88, 100); // 他的成绩是，语文：%1, 数学：%2
+//! ```
+//! Every namespace becomes a module (`checkout.mobile` is `checkout::mobile`) holding one
+//! `&str` constant per key, whose value is the key text. Namespaces that would share a module
+//! name, like `checkout_mobile` and `Checkout-Mobile`, are told apart with a `_2` suffix. ASCII keys are named after their words,
+//! `confirm order` or `confirmOrder` → `CONFIRM_ORDER`; other keys, like Chinese sentences, are
+//! named after a hash of their text, `K_E2B9C0F7`, with the text in the doc comment.
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Name of the reserved metadata section of a language file.
+const META: &str = "_meta";
+
+/// Writes the keys of every `.json` language file in `source_dir` to `out_file`. A key that is
+/// in any catalog gets a constant, so the code only breaks when a key leaves them all.
+///
+/// The file is only rewritten when its content changes, and cargo is told to rerun the build
+/// script when `source_dir` changes.
+pub fn generate(source_dir: impl AsRef<Path>, out_file: impl AsRef<Path>) -> Result<()> {
+    generate_from(source_dir.as_ref(), None, out_file.as_ref())
+}
+
+/// Like [`generate`], with the keys of the reference language `lang` only, e.g. the one the
+/// translators start from.
+pub fn generate_lang(
+    source_dir: impl AsRef<Path>,
+    lang: &str,
+    out_file: impl AsRef<Path>,
+) -> Result<()> {
+    generate_from(source_dir.as_ref(), Some(lang), out_file.as_ref())
+}
+
+fn generate_from(source_dir: &Path, lang: Option<&str>, out_file: &Path) -> Result<()> {
+    println!("cargo:rerun-if-changed={}", source_dir.display());
+    let mut keys = Tree::default();
+    let mut found = false;
+    let dir = fs::read_dir(source_dir)
+        .with_context(|| format!("[codegen error]: can't read {}.", source_dir.display()))?;
+    for file in dir {
+        let path = file?.path();
+        let (Some(stem), Some("json")) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.extension().and_then(|e| e.to_str()),
+        ) else {
+            continue;
+        };
+        if lang.is_some_and(|lang| !lang.eq_ignore_ascii_case(stem)) {
+            continue;
+        }
+        found = true;
+        let json = fs::read_to_string(&path)?;
+        let catalog: BTreeMap<String, Value> = serde_json::from_str(&json).with_context(|| {
+            format!(
+                "[codegen error]: {} is not a language file.",
+                path.display()
+            )
+        })?;
+        for (ns, entries) in catalog {
+            let Value::Object(entries) = entries else {
+                continue;
+            };
+            if ns == META {
+                continue;
+            }
+            let tree = ns.split('.').fold(&mut keys, |tree, part| {
+                tree.children.entry(part.to_string()).or_default()
+            });
+            tree.keys.extend(entries.keys().cloned());
+        }
+    }
+    if let Some(lang) = lang.filter(|_| !found) {
+        anyhow::bail!(
+            "[codegen error]: no {lang}.json in {}.",
+            source_dir.display()
+        );
+    }
+
+    let mut code = format!(
+        "// @generated by easy_i18n_codegen from `{}`, do not edit.\n",
+        source_dir.display()
+    );
+    for (ident, name, tree) in modules(&keys) {
+        code.push('\n');
+        write_module(&mut code, &ident, name, tree, 0);
+    }
+    if fs::read_to_string(out_file).ok().as_deref() != Some(code.as_str()) {
+        fs::write(out_file, code)
+            .with_context(|| format!("[codegen error]: can't write {}.", out_file.display()))?;
+    }
+    Ok(())
+}
+
+/// The keys of a namespace and its child namespaces, sorted so that the output is stable.
+#[derive(Debug, Default)]
+struct Tree {
+    keys: BTreeSet<String>,
+    children: BTreeMap<String, Tree>,
+}
+
+fn write_module(code: &mut String, ident: &str, ns: &str, tree: &Tree, depth: usize) {
+    let indent = "    ".repeat(depth);
+    // writing into a String never fails
+    let _ = writeln!(code, "{indent}/// Keys of namespace `{ns}`.");
+    if depth == 0 {
+        let _ = writeln!(code, "{indent}#[allow(dead_code)]");
+    }
+    let _ = writeln!(code, "{indent}pub mod {ident} {{");
+    let mut used = BTreeSet::new();
+    for key in &tree.keys {
+        let mut ident = const_ident(key);
+        let base = ident.clone();
+        let mut n = 1;
+        while !used.insert(ident.clone()) {
+            n += 1;
+            ident = format!("{base}_{n}");
+        }
+        let doc = key.replace(['\n', '\r'], " ");
+        let _ = writeln!(code, "{indent}    /// `{doc}`");
+        let _ = writeln!(code, "{indent}    pub const {ident}: &str = {key:?};");
+    }
+    for (ident, child, tree) in modules(tree) {
+        write_module(code, &ident, &format!("{ns}.{child}"), tree, depth + 1);
+    }
+    let _ = writeln!(code, "{indent}}}");
+}
+
+/// The child namespaces of `tree` with their module names. Namespaces giving the same name,
+/// like `checkout_mobile` and `Checkout-Mobile`, are told apart by a suffix: the one named as
+/// its module keeps the name, the others get `_2`, `_3`, ... in the order of their names.
+fn modules(tree: &Tree) -> Vec<(String, &str, &Tree)> {
+    let mut children: Vec<_> = tree.children.iter().collect();
+    children.sort_by_key(|(name, _)| module_ident(name) != **name);
+    let mut used = BTreeSet::new();
+    let mut modules = vec![];
+    for (name, child) in children {
+        let mut ident = module_ident(name);
+        let base = ident.trim_start_matches("r#").to_string();
+        let mut n = 1;
+        while !used.insert(ident.clone()) {
+            n += 1;
+            ident = format!("{base}_{n}");
+        }
+        modules.push((ident, name.as_str(), child));
+    }
+    modules.sort_by_key(|(_, name, _)| *name);
+    modules
+}
+
+/// Words of an ASCII text, `confirmOrder` and `confirm order` alike giving `confirm`, `order`.
+fn words(text: &str) -> Option<Vec<String>> {
+    if !text.is_ascii() {
+        return None;
+    }
+    let mut words: Vec<String> = vec![];
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if !c.is_ascii_alphanumeric() {
+            prev_lower = false;
+            words.push(String::new());
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower {
+            words.push(String::new());
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        match words.last_mut() {
+            Some(word) => word.push(c),
+            None => words.push(c.to_string()),
+        }
+    }
+    words.retain(|word| !word.is_empty());
+    (!words.is_empty()).then_some(words)
+}
+
+/// FNV-1a, which is stable across platforms and releases unlike `DefaultHasher`.
+fn hash(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+fn const_ident(key: &str) -> String {
+    match words(key) {
+        Some(words) => {
+            let ident = words.join("_").to_ascii_uppercase();
+            match ident.starts_with(|c: char| c.is_ascii_digit()) {
+                true => format!("K_{ident}"),
+                false => ident,
+            }
+        }
+        None => format!("K_{:08X}", hash(key)),
+    }
+}
+
+fn module_ident(ns: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+        "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override",
+        "priv", "try", "typeof", "unsized", "virtual", "yield",
+    ];
+    let ident = match words(ns) {
+        Some(words) => words.join("_").to_ascii_lowercase(),
+        None => return format!("ns_{:08x}", hash(ns)),
+    };
+    // `_list` and the like keep their leading underscore
+    let ident = match ns.starts_with('_') {
+        true => format!("_{ident}"),
+        false => ident,
+    };
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("ns_{ident}")
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else {
+        ident
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(const_ident("confirmOrder"), "CONFIRM_ORDER");
+        assert_eq!(const_ident("confirm order!"), "CONFIRM_ORDER");
+        assert_eq!(const_ident("3 items"), "K_3_ITEMS");
+        assert_eq!(
+            const_ident("这是一个测试"),
+            format!("K_{:08X}", hash("这是一个测试"))
+        );
+        assert_eq!(module_ident("checkout"), "checkout");
+        assert_eq!(module_ident("type"), "r#type");
+        assert_eq!(module_ident("_list"), "_list");
+        assert!(module_ident("结账").starts_with("ns_"));
+    }
+
+    #[test]
+    fn generate_module() {
+        let dir = std::env::temp_dir().join(format!("easy_i18n_codegen_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("en.json"),
+            r#"{
+                "_meta": {"display_name": "English"},
+                "common": {"这是一个测试": "This is a test", "ok": "OK"},
+                "checkout": {"confirmOrder": "Confirm", "confirm order": "Confirm"},
+                "checkout.mobile": {"tap": "Tap"},
+                "checkout_mobile": {"tap": "Tap"},
+                "checkout-mobile": {"tap": "Tap"},
+                "Checkout": {"back": "Back"}
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("fr.json"),
+            r#"{"common": {"only_fr": "Seulement"}}"#,
+        )
+        .unwrap();
+        let out = dir.join("keys.rs");
+
+        generate_lang(&dir, "en", &out).unwrap();
+        let code = fs::read_to_string(&out).unwrap();
+        let test_key = format!("K_{:08X}", hash("这是一个测试"));
+        for line in [
+            "pub mod checkout {",
+            "    pub const CONFIRM_ORDER: &str = \"confirm order\";",
+            "    pub const CONFIRM_ORDER_2: &str = \"confirmOrder\";",
+            "    pub mod mobile {",
+            "        pub const TAP: &str = \"tap\";",
+            "/// Keys of namespace `checkout`.\n#[allow(dead_code)]\npub mod checkout {",
+            "/// Keys of namespace `Checkout`.\n#[allow(dead_code)]\npub mod checkout_2 {",
+            "/// Keys of namespace `checkout_mobile`.\n#[allow(dead_code)]\npub mod checkout_mobile {",
+            "/// Keys of namespace `checkout-mobile`.\n#[allow(dead_code)]\npub mod checkout_mobile_2 {",
+            &format!("    pub const {test_key}: &str = \"这是一个测试\";"),
+        ] {
+            assert!(code.contains(line), "{line} not in\n{code}");
+        }
+        assert!(!code.contains("_meta") && !code.contains("ONLY_FR"));
+
+        generate(&dir, &out).unwrap();
+        assert!(fs::read_to_string(&out).unwrap().contains("ONLY_FR"));
+        assert!(generate_lang(&dir, "de", &out).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! `#[derive(I18nLabel)]` for [easy_i18n](https://docs.rs/easy_i18n): gives an enum a
+//! `fn label(&self) -> String` translating each variant with `i18n!`.
+//! ### Example
+//! ``` rust
+//! use easy_i18n_derive::I18nLabel;
+//!
+//! #[derive(I18nLabel)]
+//! #[i18n(ns = "order")]
+//! enum OrderStatus {
+//!     Pending,
+//!     #[i18n(key = "已发货")]
+//!     Shipped { carrier: String },
+//!     #[i18n(key = "已取消", ns = "common")]
+//!     Cancelled(u32),
+//! }
+//! let lang = easy_i18n::current_lang();
+//! easy_i18n::I18N
+//!     .write()
+//!     .unwrap()
+//!     .set_override(&lang, "order", "Pending", "Awaiting payment");
+//! // i18n!("Pending", ns = "order")
+//! assert_eq!(OrderStatus::Pending.label(), "Awaiting payment");
+//! // i18n!("已发货", ns = "order"), untranslated
+//! let carrier = "DHL".to_string();
+//! assert_eq!(OrderStatus::Shipped { carrier }.label(), "已发货");
+//! // i18n!("已取消", ns = "common")
+//! assert_eq!(OrderStatus::Cancelled(3).label(), "已取消");
+//! ```
+//! The key of a variant is its name unless `#[i18n(key = "...")]` says otherwise, and its
+//! namespace the one of the enum, `#[i18n(ns = "...")]`, or the default namespace of `i18n!`.
+//! The fields of a variant are not used.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, LitStr, Result};
+
+/// Derives `fn label(&self) -> String`, see the [crate documentation](crate).
+#[proc_macro_derive(I18nLabel, attributes(i18n))]
+pub fn derive_i18n_label(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The `key` and `ns` of an `#[i18n(...)]` attribute.
+#[derive(Default)]
+struct Label {
+    key: Option<LitStr>,
+    ns: Option<LitStr>,
+}
+
+impl Label {
+    fn parse(attrs: &[Attribute], allow_key: bool) -> Result<Label> {
+        let mut label = Label::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("i18n")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("key") && allow_key {
+                    label.key = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("ns") {
+                    label.ns = Some(meta.value()?.parse()?);
+                } else if allow_key {
+                    return Err(meta.error("expected `key` or `ns`"));
+                } else {
+                    return Err(meta.error("expected `ns`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(label)
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "I18nLabel can only be derived for enums",
+        ));
+    };
+    let enum_label = Label::parse(&input.attrs, false)?;
+    let mut arms = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        let label = Label::parse(&variant.attrs, true)?;
+        let ident = &variant.ident;
+        let key = label
+            .key
+            .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+        let translate = match label.ns.or_else(|| enum_label.ns.clone()) {
+            Some(ns) => quote!(::easy_i18n::i18n!(#key, ns = #ns)),
+            None => quote!(::easy_i18n::i18n!(#key)),
+        };
+        arms.push(quote!(Self::#ident { .. } => #translate,));
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The translation of this variant in the current language.
+            pub fn label(&self) -> ::std::string::String {
+                use ::easy_i18n::I18N;
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
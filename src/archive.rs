@@ -0,0 +1,94 @@
+//! Catalogs shipped as one `.zip` archive, behind the `zip` feature.
+use crate::{I18n, LoadWarning, Source};
+use ::zip::ZipArchive;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+impl I18n {
+    /// Like [`I18n::set_source`], reading the language files from the zip archive at `path`
+    /// instead of a directory. Every entry with the extension of a registered loader is parsed
+    /// and keyed by its file stem, wherever it is in the archive; entries of the same language
+    /// are merged and the ones that can't be parsed are skipped.
+    ///
+    /// Fails, keeping the current sources, when the archive itself can't be read.
+    pub fn set_source_from_zip(&mut self, path: &Path) -> Result<()> {
+        let file = File::open(path)
+            .with_context(|| format!("[source error]: can't open {}.", path.display()))?;
+        let mut archive = ZipArchive::new(BufReader::new(file))
+            .with_context(|| format!("[source error]: {} is not a zip archive.", path.display()))?;
+        let mut sources: HashMap<String, Source> = HashMap::new();
+        let mut warnings: Vec<LoadWarning> = vec![];
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let Some(name) = entry.enclosed_name().filter(|_| entry.is_file()) else {
+                continue;
+            };
+            let file = path.join(&name);
+            let (Some(loader), Some(lang)) = (
+                self.loaders.for_path(&name),
+                name.file_stem().and_then(|stem| stem.to_str()),
+            ) else {
+                continue;
+            };
+            let mut bytes = vec![];
+            entry.read_to_end(&mut bytes)?;
+            match loader.load(&bytes) {
+                Ok(mut source) => {
+                    let lang = lang.to_uppercase();
+                    warnings.extend(source.file_warnings(&lang, &file, &self.load_options));
+                    sources.entry(lang).or_default().merge(source);
+                }
+                Err(_err) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("skipped {}: {_err:#}", file.display());
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(path = %file.display(), error = %format!("{_err:#}"), "skipped a language file");
+                }
+            }
+        }
+        self.replace_sources(Some(path), sources, warnings);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::zip::write::SimpleFileOptions;
+    use ::zip::ZipWriter;
+    use std::io::Write;
+
+    #[test]
+    fn load_from_archive() {
+        let path = crate::tests::temp_path("easy_i18n_catalogs.zip");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        let entries = [
+            ("en.json", r#"{"common": {"保存": "Save"}}"#),
+            ("fr/fr.json", r#"{"common": {"保存": "Enregistrer"}}"#),
+            ("fr/dialog/fr.json", r#"{"dialog": {"取消": "Annuler"}}"#),
+            ("de.json", "{ broken"),
+            ("README.txt", "not a catalog"),
+        ];
+        for (name, content) in entries {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let mut i18n = I18n::new("fr");
+        i18n.set_source_from_zip(&path).unwrap();
+        assert_eq!(i18n.translate("保存", None), "Enregistrer");
+        assert_eq!(i18n.translate("取消", Some("dialog".into())), "Annuler");
+        assert_eq!(i18n.languages_with_key("保存", None), ["EN", "FR"]);
+        assert_eq!(i18n.source_path(), Some(path.as_path()));
+
+        let err = i18n
+            .set_source_from_zip(Path::new("./source/en.json"))
+            .unwrap_err();
+        assert!(err.to_string().contains("is not a zip archive"), "{err}");
+        assert_eq!(i18n.translate("保存", None), "Enregistrer");
+    }
+}
@@ -0,0 +1,123 @@
+//! A shared translator for async code.
+use crate::{load_source, I18n, Namespace};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// An [`I18n`] behind an `Arc<tokio::sync::RwLock<_>>`, the async counterpart of
+/// [`I18nHandle`](crate::I18nHandle): waiting for the lock yields to the executor instead of
+/// blocking the thread, and the guards may be held across an `.await`.
+///
+/// The translating methods are `async` and release the lock before returning, so
+/// [`i18n_in!`](crate::i18n_in) works with a `.await` after it.
+/// ```
+/// use easy_i18n::{i18n_in, AsyncI18nHandle};
+/// async fn greet(i18n: AsyncI18nHandle) -> String {
+///     i18n.set_lang("en").await;
+///     i18n_in!(i18n, "他的成绩是，语文：%1, 数学：%2", 88, 100).await
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AsyncI18nHandle(Arc<RwLock<I18n>>);
+
+impl From<I18n> for AsyncI18nHandle {
+    fn from(i18n: I18n) -> Self {
+        AsyncI18nHandle(Arc::new(RwLock::new(i18n)))
+    }
+}
+
+impl AsyncI18nHandle {
+    /// A handle to a new translator for `lang`, see [`I18n::new`].
+    pub fn new(lang: &str) -> AsyncI18nHandle {
+        AsyncI18nHandle::from(I18n::new(lang))
+    }
+
+    /// Borrows the translator for reading.
+    pub async fn read(&self) -> RwLockReadGuard<'_, I18n> {
+        self.0.read().await
+    }
+
+    /// Borrows the translator for changing its settings.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, I18n> {
+        self.0.write().await
+    }
+
+    pub async fn set_lang(&self, lang: &str) {
+        self.write().await.set_lang(lang);
+    }
+
+    pub async fn current_lang(&self) -> String {
+        self.read().await.current_lang().to_string()
+    }
+
+    /// [`I18n::set_source`], reading the files before taking the write lock. The files are read
+    /// with blocking calls on the current task; load large catalogs from `spawn_blocking`.
+    pub async fn set_source(&self, path: &Path) {
+        let (options, loaders) = {
+            let i18n = self.read().await;
+            (i18n.load_options.clone(), i18n.loaders.clone())
+        };
+        let (sources, warnings) = load_source(path, &options, &loaders);
+        self.write()
+            .await
+            .replace_sources(Some(path), sources, warnings);
+    }
+
+    pub async fn translate(&self, text: &str, ns: Option<Namespace>) -> String {
+        self.read().await.translate(text, ns)
+    }
+
+    pub async fn trans_with_inter(
+        &self,
+        text: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        self.read().await.trans_with_inter(text, vals, ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    /// Runs `future` to completion on this thread; the tokio lock is uncontended here, so every
+    /// poll makes progress.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn translate_across_awaits() {
+        let i18n = AsyncI18nHandle::new("cn");
+        let shared = i18n.clone();
+        block_on(async {
+            shared.set_source(Path::new("./source")).await;
+            shared.set_lang("en").await;
+            assert_eq!(i18n.current_lang().await, "EN");
+            assert_eq!(
+                crate::i18n_in!(i18n, "这是一个测试", ns = "namespace1").await,
+                "This is a test, but it is different"
+            );
+            // the read guard is held across an await without blocking other readers
+            let guard = i18n.read().await;
+            let other = shared.translate("这是一个测试", None).await;
+            assert_eq!(guard.translate("这是一个测试", None), other);
+            drop(guard);
+            shared.write().await.set_lang("de");
+            assert_eq!(
+                crate::i18n_in!(i18n, "这是一个测试").await,
+                "Das ist ein Test"
+            );
+        });
+    }
+}
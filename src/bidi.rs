@@ -0,0 +1,118 @@
+//! Bidi isolation of interpolated values, so that a Latin name in an Arabic sentence, or the
+//! reverse, doesn't reorder the punctuation and numbers around it.
+use crate::{opt, Direction, I18n};
+use core::fmt::Display;
+use serde::Deserialize;
+
+/// FIRST STRONG ISOLATE, opening an isolated run whose direction is the one of its content.
+pub(crate) const FSI: char = '\u{2068}';
+/// POP DIRECTIONAL ISOLATE, closing the run opened by [`FSI`].
+pub(crate) const PDI: char = '\u{2069}';
+
+/// When the values of an interpolation are wrapped in FSI/PDI, see
+/// [`I18n::set_bidi_isolation`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BidiIsolation {
+    /// Values are written as they are.
+    #[default]
+    Off,
+    /// Only when the `_meta` of the active language has `"direction": "rtl"`.
+    Rtl,
+    /// In every language.
+    Always,
+}
+
+impl I18n {
+    /// Wraps each value written into a positional placeholder by the interpolating calls
+    /// (`trans_with_inter`, `i18n!` with values, ...) in U+2068 FIRST STRONG ISOLATE and
+    /// U+2069 POP DIRECTIONAL ISOLATE, so that a value written in the other direction than the
+    /// template keeps its directionality to itself. [`BidiIsolation::Off`] by default.
+    ///
+    /// A placeholder with the `noiso` modifier, `%{1:noiso}`, is written bare, for values that
+    /// are isolated already. Placeholders without a value, kept or emptied by the
+    /// [`MissingArg`](crate::MissingArg) policy, are never wrapped.
+    pub fn set_bidi_isolation(&mut self, mode: BidiIsolation) {
+        self.bidi_isolation = mode;
+    }
+
+    /// Whether a value written into a placeholder with `modifier` is isolated.
+    pub(crate) fn isolates<V: Display>(
+        &self,
+        index: Option<usize>,
+        modifier: Option<&str>,
+        vals: &[V],
+    ) -> bool {
+        let applies = match self.bidi_isolation {
+            BidiIsolation::Off => false,
+            BidiIsolation::Always => true,
+            BidiIsolation::Rtl => self
+                .lang_source(&self.active_lang())
+                .and_then(|source| source.meta.as_ref()?.direction)
+                .is_some_and(|direction| direction == Direction::Rtl),
+        };
+        applies
+            && modifier != Some("noiso")
+            && match index {
+                Some(0) => vals.iter().any(|v| !opt::is_absent(v)),
+                Some(i) => vals.get(i - 1).is_some_and(|v| !opt::is_absent(v)),
+                None => false,
+            }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::Source;
+
+    #[test]
+    fn isolates() {
+        let mut i18n = I18n::new("ar");
+        let ar: Source = serde_json::from_str(
+            r#"{"_meta": {"direction": "rtl"}, "common": {
+                "欢迎%1": "مرحبا %1!",
+                "已购买%1，%2": "اشتريت %{1:noiso} و %2."
+            }}"#,
+        )
+        .unwrap();
+        i18n.insert_source("ar", ar);
+        i18n.insert_source("en", Source::default());
+        let vals = || vec!["iPhone 15".to_string(), "AirPods".to_string()];
+        assert_eq!(
+            i18n.trans_with_inter("欢迎%1", vals(), None),
+            "مرحبا iPhone 15!"
+        );
+
+        i18n.set_bidi_isolation(BidiIsolation::Rtl);
+        assert_eq!(
+            i18n.trans_with_inter("欢迎%1", vals(), None),
+            "مرحبا \u{2068}iPhone 15\u{2069}!"
+        );
+        assert_eq!(
+            i18n.trans_with_inter("已购买%1，%2", vals(), None),
+            "اشتريت iPhone 15 و \u{2068}AirPods\u{2069}."
+        );
+        // a missing value is not wrapped, nor is a template kept without values
+        assert_eq!(
+            i18n.trans_with_inter("已购买%1，%2", vec!["x".to_string()], None),
+            "اشتريت x و ."
+        );
+        assert_eq!(i18n.trans_with_inter("欢迎%1", vec![], None), "مرحبا %1!");
+
+        i18n.set_lang("en");
+        assert_eq!(
+            i18n.trans_with_inter("%1 (%2)", vals(), None),
+            "iPhone 15 (AirPods)"
+        );
+        i18n.set_bidi_isolation(BidiIsolation::Always);
+        assert_eq!(
+            i18n.trans_with_inter("%1 (%2)", vals(), None),
+            "\u{2068}iPhone 15\u{2069} (\u{2068}AirPods\u{2069})"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_html("<b>%1</b>", vec!["<AT&T>".to_string()], None),
+            "<b>\u{2068}&lt;AT&amp;T&gt;\u{2069}</b>"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Results of plain [`I18n::translate`](crate::I18n::translate) calls, see
+//! [`I18n::set_translate_cache`](crate::I18n::set_translate_cache), and the translations of the
+//! registered [`MsgHandle`](crate::MsgHandle)s.
+use crate::msg_handle::MsgTable;
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use crate::Namespace;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+/// The cached translations of one language by key.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+struct LangEntries {
+    /// Calls that requested no namespace.
+    default: HashMap<String, String>,
+    namespaced: HashMap<Namespace, HashMap<String, String>>,
+}
+
+#[cfg(feature = "std")]
+impl LangEntries {
+    fn keys(&self, ns: Option<&str>) -> Option<&HashMap<String, String>> {
+        match ns {
+            None => Some(&self.default),
+            Some(ns) => self.namespaced.get(ns),
+        }
+    }
+}
+
+/// Keyed by `(lang, namespace, key)` with nested maps, so a hit allocates nothing but the clone.
+/// Without `std` there is no lock to fill it through `&self`, only the handle table is kept.
+#[derive(Debug, Default)]
+pub(crate) struct TranslateCache {
+    #[cfg(feature = "std")]
+    pub(crate) enabled: bool,
+    #[cfg(feature = "std")]
+    entries: RwLock<HashMap<String, LangEntries>>,
+    /// Resolved on the first handle lookup after a change.
+    pub(crate) handles: OnceCell<MsgTable>,
+    /// Increased by every [`TranslateCache::clear`].
+    pub(crate) generation: u64,
+}
+
+impl TranslateCache {
+    #[cfg(feature = "std")]
+    pub(crate) fn get(&self, lang: &str, ns: Option<&str>, key: &str) -> Option<String> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries.get(lang)?.keys(ns)?.get(key).cloned()
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn insert(&self, lang: &str, ns: Option<&str>, key: &str, value: &str) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let lang = entries.entry(lang.to_string()).or_default();
+        let keys = match ns {
+            None => &mut lang.default,
+            Some(ns) => lang.namespaced.entry(ns.to_string()).or_default(),
+        };
+        keys.insert(key.to_string(), value.to_string());
+    }
+
+    pub(crate) fn clear(&mut self) {
+        #[cfg(feature = "std")]
+        self.entries
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self.handles = OnceCell::new();
+        self.generation += 1;
+    }
+
+    /// Drops the handle table only, after a new handle was registered. The generation still
+    /// moves on, as the snapshots taken before lack the new handle.
+    pub(crate) fn clear_handles(&mut self) {
+        self.handles = OnceCell::new();
+        self.generation += 1;
+    }
+
+    #[cfg(all(test, feature = "std"))]
+    pub(crate) fn len(&self) -> usize {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries
+            .values()
+            .map(|lang| {
+                lang.default.len() + lang.namespaced.values().map(HashMap::len).sum::<usize>()
+            })
+            .sum()
+    }
+}
+
+impl Clone for TranslateCache {
+    fn clone(&self) -> Self {
+        #[cfg(feature = "std")]
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        TranslateCache {
+            #[cfg(feature = "std")]
+            enabled: self.enabled,
+            #[cfg(feature = "std")]
+            entries: RwLock::new(entries.clone()),
+            handles: self.handles.clone(),
+            generation: self.generation,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Locale-aware case conversion.
+//!
+//! This is **not** full ICU casing. Only the rules that commonly break UI labels are
+//! special-cased; every other language falls through to the std `to_uppercase` /
+//! `to_lowercase` behavior:
+//! - Turkish and Azerbaijani (`TR`, `AZ`): dotted `i` ↔ `İ` and dotless `ı` ↔ `I`.
+//! - Greek (`EL`): final sigma when lowercasing (std already does this at word ends).
+//! - Chinese, Japanese and Korean (`CN`, `ZH`, `JA`, `KO`): text is returned untouched.
+use crate::prelude::*;
+
+/// Returns the primary subtag of a language code, e.g. `TR` for `tr-TR`.
+pub(crate) fn primary(lang: &str) -> String {
+    lang.split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_uppercase()
+}
+
+fn is_turkic(lang: &str) -> bool {
+    matches!(primary(lang).as_str(), "TR" | "AZ")
+}
+
+fn is_cjk(lang: &str) -> bool {
+    matches!(primary(lang).as_str(), "CN" | "ZH" | "JA" | "KO")
+}
+
+fn upper_char(c: char, turkic: bool, out: &mut String) {
+    match c {
+        'i' if turkic => out.push('İ'),
+        _ => out.extend(c.to_uppercase()),
+    }
+}
+
+/// Uppercases `s` using the casing rules of `lang`.
+pub fn upper(s: &str, lang: &str) -> String {
+    if is_cjk(lang) {
+        return s.to_string();
+    }
+    let turkic = is_turkic(lang);
+    let mut out = String::with_capacity(s.len());
+    s.chars().for_each(|c| upper_char(c, turkic, &mut out));
+    out
+}
+
+/// Lowercases `s` using the casing rules of `lang`.
+pub fn lower(s: &str, lang: &str) -> String {
+    if is_cjk(lang) {
+        return s.to_string();
+    }
+    if is_turkic(lang) {
+        let s = s.replace('I', "ı").replace('İ', "i");
+        return s.to_lowercase();
+    }
+    s.to_lowercase()
+}
+
+/// Uppercases the first letter of every whitespace-separated word and lowercases the rest.
+pub fn title(s: &str, lang: &str) -> String {
+    if is_cjk(lang) {
+        return s.to_string();
+    }
+    let turkic = is_turkic(lang);
+    let mut out = String::with_capacity(s.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut String| {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            upper_char(first, turkic, out);
+            out.push_str(&lower(chars.as_str(), lang));
+        }
+        word.clear();
+    };
+    for c in s.chars() {
+        if c.is_whitespace() {
+            flush(&mut word, &mut out);
+            out.push(c);
+        } else {
+            word.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+/// Applies a placeholder case modifier (`upper`, `lower`, `title`) to `s`.
+/// Unknown modifiers leave the value unchanged.
+pub(crate) fn apply(modifier: &str, s: &str, lang: &str) -> String {
+    match modifier {
+        "upper" => upper(s, lang),
+        "lower" => lower(s, lang),
+        "title" => title(s, lang),
+        _ => s.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turkish_dotted_and_dotless_i() {
+        assert_eq!(upper("istanbul ılık", "tr"), "İSTANBUL ILIK");
+        assert_eq!(lower("İSTANBUL ILIK", "TR"), "istanbul ılık");
+        assert_eq!(title("istanbul ılık", "tr-TR"), "İstanbul Ilık");
+        assert_eq!(upper("istanbul", "az"), "İSTANBUL");
+        // everyone else keeps the std mapping
+        assert_eq!(upper("istanbul", "en"), "ISTANBUL");
+        assert_eq!(lower("ILIK", "en"), "ilik");
+    }
+
+    #[test]
+    fn greek_final_sigma() {
+        assert_eq!(lower("ΟΔΟΣ", "el"), "οδο\u{3c2}");
+        assert_eq!(title("ΟΔΟΣ ΣΟΦΙΑΣ", "el"), "Οδο\u{3c2} Σοφια\u{3c2}");
+    }
+
+    #[test]
+    fn german_and_cjk() {
+        assert_eq!(upper("straße", "de"), "STRASSE");
+        assert_eq!(upper("这是一个测试 ａｂｃ", "cn"), "这是一个测试 ａｂｃ");
+        assert_eq!(title("ｈｅｌｌｏ", "ja"), "ｈｅｌｌｏ");
+    }
+}
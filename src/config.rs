@@ -0,0 +1,159 @@
+//! Declarative configuration of a translator, e.g. read from the config file of an app.
+use crate::{BidiIsolation, I18n, LoadOptions, MissingArg};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// The settings of an [`I18n`], deserializable from any serde format. Every field but `lang`
+/// may be omitted and then keeps the default of [`I18n::new`]; unknown fields are an error.
+/// ```toml
+/// [i18n]
+/// lang = "en"
+/// source_path = "./locales"
+/// fallback = ["shared"]
+/// default_namespace = "app"
+/// missing_arg = "keep"
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct I18nConfig {
+    pub lang: String,
+    /// Directory of the language files. Nothing is loaded when unset.
+    #[serde(default)]
+    pub source_path: Option<PathBuf>,
+    /// Load `source_path` with [`I18n::try_set_source`], failing on the first bad file.
+    #[serde(default)]
+    pub strict: bool,
+    /// Load every language file of `source_path` the first time its language is used, see
+    /// [`I18n::set_source_lazy`]. Ignored when `strict` is set.
+    #[serde(default)]
+    pub lazy: bool,
+    /// See [`I18n::set_base_lang`].
+    #[serde(default)]
+    pub base_lang: Option<String>,
+    /// See [`I18n::set_ns_fallback`].
+    #[serde(default)]
+    pub fallback: Vec<String>,
+    /// See [`I18n::set_common_fallback`].
+    #[serde(default)]
+    pub common_fallback: bool,
+    /// See [`I18n::set_default_namespace`].
+    #[serde(default)]
+    pub default_namespace: Option<String>,
+    /// See [`I18n::set_empty_as_missing`].
+    #[serde(default)]
+    pub empty_as_missing: bool,
+    /// See [`LoadOptions::normalize_whitespace`].
+    #[serde(default)]
+    pub normalize_whitespace: bool,
+    /// See [`I18n::set_interpolation_enabled`].
+    #[serde(default = "enabled")]
+    pub interpolation: bool,
+    /// `"empty"` or `"keep"`, see [`I18n::set_missing_arg`].
+    #[serde(default)]
+    pub missing_arg: MissingArg,
+    /// `"off"`, `"rtl"` or `"always"`, see [`I18n::set_bidi_isolation`].
+    #[serde(default)]
+    pub bidi_isolation: BidiIsolation,
+    /// See [`I18n::set_join_separator`].
+    #[serde(default)]
+    pub join_separator: Option<String>,
+    /// See [`I18n::set_translate_cache`].
+    #[serde(default)]
+    pub translate_cache: bool,
+    /// `["⟦", "⟧"]`, see [`I18n::set_missing_decoration`].
+    #[serde(default)]
+    pub missing_decoration: Option<(String, String)>,
+    /// See [`I18n::set_base_decoration`].
+    #[serde(default)]
+    pub base_decoration: Option<(String, String)>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn decoration((before, after): &(String, String)) -> (&str, &str) {
+    (before, after)
+}
+
+impl I18n {
+    /// Builds a translator with the settings of `config`, then loads its sources. Fails when the
+    /// fallback chain is invalid or a strict load fails.
+    pub fn from_config(config: I18nConfig) -> Result<I18n> {
+        let mut i18n = I18n::new(&config.lang);
+        i18n.set_load_options(LoadOptions {
+            normalize_whitespace: config.normalize_whitespace,
+        });
+        let chain: Vec<&str> = config.fallback.iter().map(String::as_str).collect();
+        i18n.set_ns_fallback(&chain)?;
+        i18n.set_common_fallback(config.common_fallback);
+        if let Some(lang) = &config.base_lang {
+            i18n.set_base_lang(lang);
+        }
+        if let Some(ns) = &config.default_namespace {
+            i18n.set_default_namespace(ns);
+        }
+        i18n.set_empty_as_missing(config.empty_as_missing);
+        i18n.set_interpolation_enabled(config.interpolation);
+        i18n.set_missing_arg(config.missing_arg);
+        i18n.set_bidi_isolation(config.bidi_isolation);
+        if let Some(sep) = &config.join_separator {
+            i18n.set_join_separator(sep);
+        }
+        i18n.set_translate_cache(config.translate_cache);
+        i18n.set_missing_decoration(config.missing_decoration.as_ref().map(decoration));
+        i18n.set_base_decoration(config.base_decoration.as_ref().map(decoration));
+        match &config.source_path {
+            Some(path) if config.strict => i18n
+                .try_set_source(path)
+                .context("[config error]: the sources can't be loaded.")?,
+            Some(path) if config.lazy => i18n.set_source_lazy(path),
+            Some(path) => i18n.set_source(path),
+            None => {}
+        }
+        Ok(i18n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_json_config() {
+        let config: I18nConfig = serde_json::from_str(
+            r#"{
+                "lang": "en",
+                "source_path": "./source",
+                "default_namespace": "namespace1",
+                "fallback": ["common"],
+                "missing_arg": "keep"
+            }"#,
+        )
+        .unwrap();
+        assert!(config.interpolation);
+        let i18n = I18n::from_config(config.clone()).unwrap();
+        assert_eq!(i18n.current_lang(), "EN");
+        assert_eq!(
+            i18n.translate("这是一个测试", None),
+            "This is a test, but it is different"
+        );
+        assert_eq!(
+            i18n.trans_with_inter("%1 和 %2", vec!["a".into()], None),
+            "a 和 %2"
+        );
+        let decorated: I18nConfig =
+            serde_json::from_str(r#"{"lang": "en", "missing_decoration": ["⟦", "⟧"]}"#).unwrap();
+        let i18n = I18n::from_config(decorated).unwrap();
+        assert_eq!(i18n.translate("缺少的键", None), "⟦缺少的键⟧");
+
+        let config = I18nConfig {
+            fallback: vec!["a".into(), "a".into()],
+            ..config
+        };
+        assert!(I18n::from_config(config).is_err());
+        let err = serde_json::from_str::<I18nConfig>(r#"{"lang": "en", "fallbacks": []}"#);
+        assert!(err.unwrap_err().to_string().contains("unknown field"));
+    }
+}
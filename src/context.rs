@@ -0,0 +1,275 @@
+//! Per-task language scopes.
+//!
+//! An [`I18nContext`] entered on a thread, or wrapped around a future, takes precedence over
+//! the language of every translator used inside the scope, without touching their state, so
+//! concurrent requests of a server can each speak their own language.
+use crate::{MissingArg, Namespace};
+use std::cell::RefCell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+thread_local! {
+    /// The entered contexts, innermost last, each already merged with the enclosing ones.
+    static STACK: RefCell<Vec<I18nContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The language, and optionally the default namespace and missing-argument policy, of a unit of
+/// work. Inside a scope, translations use them instead of the translator's own settings.
+/// ```
+/// use easy_i18n::{i18n, I18nContext, I18N};
+/// let _fr = I18nContext::new("fr").enter();
+/// i18n!("这是一个测试"); // looked up in FR
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I18nContext {
+    lang: String,
+    namespace: Option<Namespace>,
+    missing_arg: Option<MissingArg>,
+    #[cfg(feature = "metrics")]
+    counters: crate::metrics::LangCounters,
+}
+
+impl I18nContext {
+    pub fn new(lang: &str) -> I18nContext {
+        let lang = lang.to_uppercase();
+        I18nContext {
+            #[cfg(feature = "metrics")]
+            counters: crate::metrics::LangCounters::for_lang(&lang),
+            lang,
+            namespace: None,
+            missing_arg: None,
+        }
+    }
+
+    /// The namespace of lookups made without one.
+    pub fn with_namespace(mut self, ns: &str) -> I18nContext {
+        self.namespace = Some(ns.to_string());
+        self
+    }
+
+    /// How placeholders without a value are rendered, see
+    /// [`I18n::set_missing_arg`](crate::I18n::set_missing_arg).
+    pub fn with_missing_arg(mut self, policy: MissingArg) -> I18nContext {
+        self.missing_arg = Some(policy);
+        self
+    }
+
+    pub fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// The context in effect on this thread, if any.
+    pub fn current() -> Option<I18nContext> {
+        STACK.with(|stack| stack.borrow().last().cloned())
+    }
+
+    /// Makes this context current on this thread until the guard is dropped. Scopes nest: the
+    /// innermost wins, and the settings it leaves unset are taken from the enclosing one.
+    pub fn enter(&self) -> ContextGuard {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let depth = stack.len();
+            let mut context = self.clone();
+            if let Some(outer) = stack.last() {
+                context.namespace = context.namespace.or_else(|| outer.namespace.clone());
+                context.missing_arg = context.missing_arg.or(outer.missing_arg);
+            }
+            stack.push(context);
+            ContextGuard {
+                depth,
+                _not_send: PhantomData,
+            }
+        })
+    }
+
+    /// Runs `future` in this context: the context is entered around every poll, so it follows
+    /// the task across threads and across `.await` points, whatever the executor.
+    pub fn scope<F: Future>(self, future: F) -> Scoped<F> {
+        Scoped {
+            context: self,
+            future: Box::pin(future),
+        }
+    }
+}
+
+/// Leaves the scope of [`I18nContext::enter`] when dropped.
+#[must_use = "the context is left as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct ContextGuard {
+    depth: usize,
+    /// The scope belongs to the thread that entered it.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        STACK.with(|stack| stack.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// The future returned by [`I18nContext::scope`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Scoped<F> {
+    context: I18nContext,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let _guard = self.context.enter();
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Runs `f` with `lang` as the language of every translation made on this thread, then restores
+/// the previous one, even when `f` panics. Calls nest, and neither the global language nor other
+/// threads are affected.
+///
+/// The override is thread-local: in async code it doesn't follow a task that moves to another
+/// thread at an `.await`. Wrap the future with [`I18nContext::scope`] instead.
+/// ```
+/// use easy_i18n::{i18n, with_lang, I18N};
+/// let subject = with_lang("ja", || i18n!("这是一个测试"));
+/// ```
+pub fn with_lang<R>(lang: &str, f: impl FnOnce() -> R) -> R {
+    let _guard = I18nContext::new(lang).enter();
+    f()
+}
+
+pub(crate) fn lang() -> Option<String> {
+    STACK.with(|stack| stack.borrow().last().map(|context| context.lang.clone()))
+}
+
+pub(crate) fn namespace() -> Option<Namespace> {
+    STACK.with(|stack| stack.borrow().last()?.namespace.clone())
+}
+
+pub(crate) fn missing_arg() -> Option<MissingArg> {
+    STACK.with(|stack| stack.borrow().last()?.missing_arg)
+}
+
+/// Counts a lookup against the language of the current context, if there is one.
+#[cfg(feature = "metrics")]
+pub(crate) fn record(hit: bool) -> bool {
+    STACK.with(|stack| {
+        let stack = stack.borrow();
+        let context = stack.last();
+        context
+            .inspect(|context| context.counters.record(hit))
+            .is_some()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::I18n;
+    use std::path::Path;
+    use std::task::Waker;
+
+    /// Pending on the first poll, like a task waiting on I/O.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+            if std::mem::replace(&mut self.0, true) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    fn i18n() -> I18n {
+        let mut i18n = I18n::new("cn");
+        i18n.set_source(Path::new("./source"));
+        i18n
+    }
+
+    #[test]
+    fn nested_guards() {
+        let i18n = i18n();
+        let common = || Some("common".to_string());
+        assert_eq!(i18n.translate("这是一个测试", None), "这是一个测试");
+        {
+            let _en = I18nContext::new("en")
+                .with_namespace("namespace1")
+                .with_missing_arg(MissingArg::Keep)
+                .enter();
+            assert_eq!(
+                i18n.translate("这是一个测试", None),
+                "This is a test, but it is different"
+            );
+            assert_eq!(i18n.translate("这是一个测试", common()), "This is a test");
+            {
+                let _de = I18nContext::new("de").enter();
+                assert_eq!(I18nContext::current().unwrap().lang(), "DE");
+                assert_eq!(i18n.translate("这是一个测试", common()), "Das ist ein Test");
+                // the namespace and the policy of the outer scope are inherited
+                assert_eq!(i18n.translate("这是一个测试", None), "这是一个测试");
+                assert_eq!(i18n.trans_with_inter("缺少 %1", vec![], None), "缺少 %1");
+            }
+            assert_eq!(i18n.translate("这是一个测试", common()), "This is a test");
+        }
+        assert_eq!(I18nContext::current(), None);
+        assert_eq!(i18n.current_lang(), "CN");
+    }
+
+    #[test]
+    fn nested_and_panicking_closures() {
+        let i18n = i18n();
+        let test = || i18n.translate("这是一个测试", None);
+        let (de, en) = with_lang("de", || (test(), with_lang("en", test)));
+        assert_eq!(
+            (de.as_str(), en.as_str()),
+            ("Das ist ein Test", "This is a test")
+        );
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            with_lang("en", || {
+                with_lang("de", || panic!("in the middle of a job"));
+            })
+        }));
+        assert!(panicked.is_err());
+        assert_eq!(I18nContext::current(), None);
+        assert_eq!(test(), "这是一个测试");
+    }
+
+    #[test]
+    fn interleaved_tasks() {
+        let i18n = i18n();
+        let task = |lang: &str| {
+            let i18n = &i18n;
+            I18nContext::new(lang).scope(async move {
+                let before = i18n.translate("这是一个测试", None);
+                YieldOnce(false).await;
+                let after = i18n.translate("这是一个测试", None);
+                (before, after)
+            })
+        };
+        let mut en = std::pin::pin!(task("en"));
+        let mut cn = std::pin::pin!(task("cn"));
+        let mut cx = Context::from_waker(Waker::noop());
+        // poll the tasks in turns, as an executor would between their awaits
+        assert!(en.as_mut().poll(&mut cx).is_pending());
+        assert!(cn.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(I18nContext::current(), None);
+        let Poll::Ready(cn) = cn.as_mut().poll(&mut cx) else {
+            panic!("not done")
+        };
+        let Poll::Ready(en) = en.as_mut().poll(&mut cx) else {
+            panic!("not done")
+        };
+        assert_eq!(
+            en,
+            ("This is a test".to_string(), "This is a test".to_string())
+        );
+        assert_eq!(cn, ("这是一个测试".to_string(), "这是一个测试".to_string()));
+    }
+}
@@ -0,0 +1,80 @@
+//! Deserialization of language files.
+//!
+//! The derived `Deserialize` of a map silently keeps the last value of a repeated key, which has
+//! eaten translations during messy merges. The visitors here walk every entry instead, so repeated
+//! keys are recorded in [`RawSource::duplicates`] and reported by the loader.
+use crate::prelude::*;
+use crate::{Entry, LangMeta, Namespace, SourceMap};
+use core::fmt;
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+
+/// The file layout of a [`Source`](crate::Source), before keys are normalized.
+#[derive(Debug, Default)]
+pub(crate) struct RawSource {
+    pub(crate) meta: Option<LangMeta>,
+    pub(crate) namespaces: SourceMap,
+    /// `(namespace, key)` of every key that appeared more than once, in file order.
+    pub(crate) duplicates: Vec<(Namespace, String)>,
+}
+
+impl<'de> Deserialize<'de> for RawSource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(RawSourceVisitor)
+    }
+}
+
+struct RawSourceVisitor;
+
+impl<'de> Visitor<'de> for RawSourceVisitor {
+    type Value = RawSource;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of namespaces")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawSource, A::Error> {
+        let mut raw = RawSource::default();
+        while let Some(name) = map.next_key::<String>()? {
+            if name == crate::META {
+                raw.meta = map.next_value()?;
+                continue;
+            }
+            let Entries(entries) = map.next_value()?;
+            // a namespace repeated at the top level is merged into the first one
+            let namespace = raw.namespaces.entry(name.clone()).or_default();
+            for (key, val) in entries {
+                if namespace.insert(key.clone(), val).is_some() {
+                    raw.duplicates.push((name.clone(), key));
+                }
+            }
+        }
+        Ok(raw)
+    }
+}
+
+/// The entries of one namespace in file order, repeated keys included.
+struct Entries(Vec<(String, Entry)>);
+
+impl<'de> Deserialize<'de> for Entries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+struct EntriesVisitor;
+
+impl<'de> Visitor<'de> for EntriesVisitor {
+    type Value = Entries;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of translations")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Entries, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Entries(entries))
+    }
+}
@@ -0,0 +1,245 @@
+//! The value stored under a key.
+use crate::prelude::*;
+use crate::template::Template;
+use crate::PlaceholderStyle;
+use alloc::collections::BTreeMap;
+use core::fmt;
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// A translation. In a language file it is either a plain string or an object carrying variants:
+/// ```json
+/// {
+///   "common": {
+///     "保存": "Save",
+///     "继续": {
+///       "value": "Continue",
+///       "platforms": { "web": "Click to continue", "mobile": "Tap to continue" }
+///     },
+///     "发布": { "value": "Post", "comment": "a verb: publish the draft" },
+///     "存档": { "value": "Save", "comment": "button label", "max_len": 12 },
+///     "%1条消息": { "one": "%1 message", "other": "%1 messages" }
+///   }
+/// }
+/// ```
+/// An object with plural categories (`zero`, `one`, `two`, `few`, `many`, `other`) may leave out
+/// `value`, which is then its `other` branch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The translation used when no variant applies.
+    pub value: String,
+    /// Variants by platform (`web`, `mobile`, `cli`, ...).
+    pub platforms: BTreeMap<String, String>,
+    /// Context note for translators. Never used by lookups.
+    pub comment: Option<String>,
+    /// The most characters the translation may have, e.g. for a fixed-width button. Never
+    /// enforced by lookups.
+    pub max_len: Option<usize>,
+    /// Variants by plural category, see [`Entry::plural`].
+    pub plurals: BTreeMap<String, String>,
+    /// `value` with its placeholders located, see [`Entry::from`].
+    pub(crate) template: Template,
+}
+
+impl Entry {
+    /// The variant for `platform`, or the base value.
+    pub fn for_platform(&self, platform: &str) -> &str {
+        self.platforms
+            .get(platform)
+            .map(|v| v.as_str())
+            .unwrap_or(self.value.as_str())
+    }
+
+    /// The variant for the plural `category`, else the `other` one, else the base value.
+    pub fn plural(&self, category: &str) -> &str {
+        self.plurals
+            .get(category)
+            .or_else(|| self.plurals.get("other"))
+            .unwrap_or(&self.value)
+    }
+
+    fn is_plain(&self) -> bool {
+        self.platforms.is_empty()
+            && self.comment.is_none()
+            && self.max_len.is_none()
+            && self.plurals.is_empty()
+    }
+}
+
+/// Entries made this way, and the ones read from language files, locate the placeholders of
+/// their value once, so interpolating them runs no regex. They are compiled for `%N`
+/// placeholders, and recompiled when their language uses another [`PlaceholderStyle`].
+impl From<String> for Entry {
+    fn from(value: String) -> Self {
+        Entry {
+            template: Template::compile(&value, PlaceholderStyle::Percent),
+            value,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for Entry {
+    fn from(value: &str) -> Self {
+        Entry::from(value.to_string())
+    }
+}
+
+impl Serialize for Entry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_plain() {
+            return serializer.serialize_str(&self.value);
+        }
+        let mut map = serializer.serialize_map(None)?;
+        if self.plurals.get("other") != Some(&self.value) {
+            map.serialize_entry("value", &self.value)?;
+        }
+        if !self.platforms.is_empty() {
+            map.serialize_entry("platforms", &self.platforms)?;
+        }
+        if let Some(comment) = &self.comment {
+            map.serialize_entry("comment", comment)?;
+        }
+        if let Some(max_len) = self.max_len {
+            map.serialize_entry("max_len", &max_len)?;
+        }
+        for category in PLURAL_CATEGORIES {
+            if let Some(variant) = self.plurals.get(*category) {
+                map.serialize_entry(category, variant)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Entry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(EntryVisitor)
+    }
+}
+
+struct EntryVisitor;
+
+impl<'de> Visitor<'de> for EntryVisitor {
+    type Value = Entry;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string or an object with a \"value\"")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Entry, E> {
+        Ok(Entry::from(value))
+    }
+
+    fn visit_string<E: Error>(self, value: String) -> Result<Entry, E> {
+        Ok(Entry::from(value))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Entry, A::Error> {
+        let mut value = None;
+        let mut entry = Entry::default();
+        while let Some(field) = map.next_key::<String>()? {
+            match field.as_str() {
+                "value" => value = Some(map.next_value()?),
+                "platforms" => entry.platforms = map.next_value()?,
+                "comment" => entry.comment = Some(map.next_value()?),
+                "max_len" => entry.max_len = Some(map.next_value()?),
+                category if PLURAL_CATEGORIES.contains(&category) => {
+                    entry.plurals.insert(field, map.next_value()?);
+                }
+                _ => return Err(A::Error::unknown_field(&field, FIELDS)),
+            }
+        }
+        entry.value = match (value, entry.plurals.get("other")) {
+            (Some(value), _) => value,
+            (None, Some(other)) => other.clone(),
+            (None, None) if entry.plurals.is_empty() => {
+                return Err(A::Error::missing_field("value"))
+            }
+            (None, None) => return Err(A::Error::missing_field("other")),
+        };
+        entry.template = Template::compile(&entry.value, PlaceholderStyle::Percent);
+        Ok(entry)
+    }
+}
+
+/// Fields of the object form.
+pub(crate) const FIELDS: &[&str] = &[
+    "value",
+    "platforms",
+    "comment",
+    "max_len",
+    "zero",
+    "one",
+    "two",
+    "few",
+    "many",
+    "other",
+];
+
+/// The plural categories of CLDR, in the order they are written.
+pub(crate) const PLURAL_CATEGORIES: &[&str] = &["zero", "one", "two", "few", "many", "other"];
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_and_object_forms() {
+        let json = r#"{"a": "A", "b": {"value": "B", "platforms": {"web": "Web B"}}}"#;
+        let entries: BTreeMap<String, Entry> = serde_json::from_str(json).unwrap();
+        assert_eq!(entries["a"], Entry::from("A"));
+        assert_eq!(entries["b"].for_platform("web"), "Web B");
+        assert_eq!(entries["b"].for_platform("cli"), "B");
+        assert_eq!(
+            serde_json::to_string(&entries).unwrap(),
+            json.replace(": ", ":").replace(", ", ",")
+        );
+
+        let err = serde_json::from_str::<Entry>(r#"{"platforms": {}}"#).unwrap_err();
+        assert!(err.to_string().contains("missing field `value`"));
+        assert!(serde_json::from_str::<Entry>(r#"{"value": "x", "other": 1}"#).is_err());
+        assert!(serde_json::from_str::<Entry>("12").is_err());
+    }
+
+    #[test]
+    fn comments() {
+        let json = r#"{"value":"Post","comment":"a verb: \"publish\" ✍️\n(not the noun)"}"#;
+        let entry: Entry = serde_json::from_str(json).unwrap();
+        assert_eq!(entry.value, "Post");
+        assert_eq!(
+            entry.comment.as_deref(),
+            Some("a verb: \"publish\" ✍️\n(not the noun)")
+        );
+        assert_eq!(serde_json::to_string(&entry).unwrap(), json);
+    }
+
+    #[test]
+    fn plural_variants() {
+        let json = r#"{"a":"A","b":{"one":"%1 message","other":"%1 messages"},"c":{"value":"C","zero":"none"}}"#;
+        let entries: BTreeMap<String, Entry> = serde_json::from_str(json).unwrap();
+        assert_eq!(entries["b"].value, "%1 messages");
+        assert_eq!(entries["b"].plural("one"), "%1 message");
+        assert_eq!(entries["b"].plural("few"), "%1 messages");
+        assert_eq!(entries["c"].plural("zero"), "none");
+        assert_eq!(entries["c"].plural("one"), "C");
+        assert_eq!(entries["a"].plural("one"), "A");
+        assert_eq!(serde_json::to_string(&entries).unwrap(), json);
+
+        let err = serde_json::from_str::<Entry>(r#"{"one": "x"}"#).unwrap_err();
+        assert!(err.to_string().contains("missing field `other`"));
+        assert!(serde_json::from_str::<Entry>(r#"{"other": 1}"#).is_err());
+    }
+
+    #[test]
+    fn max_len() {
+        let json = r#"{"value":"Save","comment":"button label","max_len":12}"#;
+        let entry: Entry = serde_json::from_str(json).unwrap();
+        assert_eq!((entry.value.as_str(), entry.max_len), ("Save", Some(12)));
+        assert_eq!(serde_json::to_string(&entry).unwrap(), json);
+        let entry: Entry = serde_json::from_str(r#"{"value":"Save","max_len":4}"#).unwrap();
+        assert_eq!(entry.comment, None);
+        assert!(serde_json::from_str::<Entry>(r#"{"value":"x","max_len":-1}"#).is_err());
+        assert!(serde_json::from_str::<Entry>(r#"{"value":"x","max_len":"4"}"#).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+//! A shared, cheaply clonable translator.
+use crate::{
+    load_source, load_source_recursive, load_source_strict, load_source_with_progress, I18n,
+    InterpolationError, Namespace,
+};
+use anyhow::Result;
+#[cfg(feature = "tracing")]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::sync::{
+    Arc, LockResult, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError,
+    TryLockResult,
+};
+
+/// An [`I18n`] behind an `Arc<RwLock<_>>`: clones share one translator, and translations only
+/// take the read lock, so any number of threads translate at once. The global [`I18N`](crate::I18N)
+/// is one of these; create others for independent catalogs, or to inject a translator into code
+/// under test without touching global state.
+///
+/// The translating methods and `set_lang`/`set_source*` are available directly; every other
+/// setting goes through [`I18nHandle::write`].
+///
+/// A panic while the translator is borrowed, e.g. in a missing-key handler, doesn't take every
+/// later translation down with it: the next borrow clears the poison and goes on with the
+/// translator as the panicking code left it, the catalogs being read-mostly and consistent
+/// between two statements. The borrowing methods therefore always return `Ok`; they keep their
+/// `LockResult` types for the code written against the plain lock.
+/// ```
+/// use easy_i18n::{i18n_in, I18n, I18nHandle};
+/// let legal = I18nHandle::from(I18n::new("en"));
+/// legal.set_source(std::path::Path::new("./source"));
+/// let service = legal.clone();
+/// assert_eq!(i18n_in!(service, "这是一个测试"), "This is a test");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct I18nHandle(Arc<RwLock<I18n>>);
+
+impl From<I18n> for I18nHandle {
+    fn from(i18n: I18n) -> Self {
+        I18nHandle(Arc::new(RwLock::new(i18n)))
+    }
+}
+
+impl I18nHandle {
+    /// A handle to a new translator for `lang`, see [`I18n::new`].
+    pub fn new(lang: &str) -> I18nHandle {
+        I18nHandle::from(I18n::new(lang))
+    }
+
+    /// Borrows the translator for reading, recovering it from a poisoning panic.
+    pub fn read(&self) -> LockResult<RwLockReadGuard<'_, I18n>> {
+        Ok(self
+            .0
+            .read()
+            .unwrap_or_else(|poisoned| self.recover(poisoned)))
+    }
+
+    /// Borrows the translator for changing its settings, recovering it from a poisoning panic.
+    pub fn write(&self) -> LockResult<RwLockWriteGuard<'_, I18n>> {
+        Ok(self
+            .0
+            .write()
+            .unwrap_or_else(|poisoned| self.recover(poisoned)))
+    }
+
+    /// Same as [`I18nHandle::write`], for the code written when the global was a `Mutex`.
+    pub fn lock(&self) -> LockResult<RwLockWriteGuard<'_, I18n>> {
+        self.write()
+    }
+
+    /// Like [`I18nHandle::lock`], failing instead of blocking when the translator is borrowed.
+    pub fn try_lock(&self) -> TryLockResult<RwLockWriteGuard<'_, I18n>> {
+        match self.0.try_write() {
+            Err(TryLockError::Poisoned(poisoned)) => Ok(self.recover(poisoned)),
+            result => result,
+        }
+    }
+
+    /// Whether a thread panicked while changing the translator since it was last borrowed.
+    pub fn is_poisoned(&self) -> bool {
+        self.0.is_poisoned()
+    }
+
+    /// Runs `f` on the translator borrowed for reading, then emits the `tracing` events of the
+    /// misses it found, once the lock is released.
+    pub(crate) fn reading<R>(&self, f: impl FnOnce(&I18n) -> R) -> R {
+        let _misses = MissEvents::defer();
+        let i18n = self.read().unwrap();
+        f(&i18n)
+    }
+
+    fn recover<G>(&self, poisoned: PoisonError<G>) -> G {
+        self.0.clear_poison();
+        poisoned.into_inner()
+    }
+
+    pub fn set_lang(&self, lang: &str) {
+        self.write().unwrap().set_lang(lang);
+    }
+
+    pub fn current_lang(&self) -> String {
+        self.read().unwrap().current_lang().to_string()
+    }
+
+    /// [`I18n::set_source`], reading the files before taking the write lock.
+    pub fn set_source(&self, path: &Path) {
+        let (options, loaders) = self.load_settings();
+        let (sources, warnings) = load_source(path, &options, &loaders);
+        self.write()
+            .unwrap()
+            .replace_sources(Some(path), sources, warnings);
+    }
+
+    /// [`I18n::set_source_with_progress`], reading the files before taking the write lock.
+    pub fn set_source_with_progress(&self, path: &Path, mut progress: impl FnMut(usize, usize)) {
+        let (options, loaders) = self.load_settings();
+        let (sources, warnings) =
+            load_source_with_progress(path, &options, &loaders, &mut progress);
+        self.write()
+            .unwrap()
+            .replace_sources(Some(path), sources, warnings);
+    }
+
+    /// [`I18n::try_set_source`], reading the files before taking the write lock.
+    pub fn try_set_source(&self, path: &Path) -> Result<()> {
+        let (options, loaders) = self.load_settings();
+        let (sources, warnings) = load_source_strict(path, &options, &loaders)?;
+        self.write()
+            .unwrap()
+            .replace_sources(Some(path), sources, warnings);
+        Ok(())
+    }
+
+    /// [`I18n::set_source_recursive`], reading the files before taking the write lock.
+    pub fn set_source_recursive(&self, path: &Path, extensions: &[&str]) {
+        let (options, loaders) = self.load_settings();
+        let (sources, warnings) = load_source_recursive(path, extensions, &options, &loaders);
+        self.write()
+            .unwrap()
+            .replace_sources(Some(path), sources, warnings);
+    }
+
+    pub fn set_source_lazy(&self, path: &Path) {
+        self.write().unwrap().set_source_lazy(path);
+    }
+
+    fn load_settings(&self) -> (crate::LoadOptions, crate::loader::Loaders) {
+        let i18n = self.read().unwrap();
+        (i18n.load_options.clone(), i18n.loaders.clone())
+    }
+
+    pub fn translate(&self, text: &str, ns: Option<Namespace>) -> String {
+        self.reading(|i18n| i18n.translate(text, ns))
+    }
+
+    pub fn translate_opt(&self, text: &str, ns: Option<Namespace>) -> Option<String> {
+        self.reading(|i18n| i18n.translate_opt(text, ns))
+    }
+
+    pub fn has_translation(&self, text: &str, ns: Option<Namespace>) -> bool {
+        self.reading(|i18n| i18n.has_translation(text, ns))
+    }
+
+    pub fn translate_into(
+        &self,
+        out: &mut impl fmt::Write,
+        text: &str,
+        ns: Option<Namespace>,
+    ) -> fmt::Result {
+        self.reading(|i18n| i18n.translate_into(out, text, ns))
+    }
+
+    pub fn trans_with_inter(&self, text: &str, vals: Vec<String>, ns: Option<Namespace>) -> String {
+        self.reading(|i18n| i18n.trans_with_inter(text, vals, ns))
+    }
+
+    pub fn trans_plural(
+        &self,
+        text: &str,
+        count: impl Display,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        self.reading(|i18n| i18n.trans_plural(text, count, vals, ns))
+    }
+
+    pub fn trans_with_inter_html(
+        &self,
+        text: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        self.reading(|i18n| i18n.trans_with_inter_html(text, vals, ns))
+    }
+
+    pub fn trans_with_inter_opt(
+        &self,
+        text: &str,
+        vals: Vec<Option<String>>,
+        ns: Option<Namespace>,
+    ) -> String {
+        self.reading(|i18n| i18n.trans_with_inter_opt(text, vals, ns))
+    }
+
+    pub fn trans_with_inter_into(
+        &self,
+        out: &mut impl fmt::Write,
+        text: &str,
+        vals: &[&dyn Display],
+        ns: Option<Namespace>,
+    ) -> fmt::Result {
+        self.reading(|i18n| i18n.trans_with_inter_into(out, text, vals, ns))
+    }
+
+    pub fn trans_with_inter_defaults(
+        &self,
+        text: &str,
+        vals: Vec<String>,
+        defaults: &[&str],
+        ns: Option<Namespace>,
+    ) -> String {
+        self.reading(|i18n| i18n.trans_with_inter_defaults(text, vals, defaults, ns))
+    }
+
+    pub fn trans_with_inter_checked(
+        &self,
+        text: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> Result<String, InterpolationError> {
+        self.reading(|i18n| i18n.trans_with_inter_checked(text, vals, ns))
+    }
+
+    pub fn trans_with_map(
+        &self,
+        text: &str,
+        vals: &HashMap<&str, String>,
+        ns: Option<&str>,
+    ) -> String {
+        self.reading(|i18n| i18n.trans_with_map(text, vals, ns))
+    }
+
+    pub fn trans_with_named(
+        &self,
+        text: &str,
+        vals: &HashMap<&str, String>,
+        ns: Option<&str>,
+    ) -> String {
+        self.reading(|i18n| i18n.trans_with_named(text, vals, ns))
+    }
+
+    pub fn trans_platform(
+        &self,
+        text: &str,
+        platform: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        self.reading(|i18n| i18n.trans_platform(text, platform, vals, ns))
+    }
+
+    pub fn join_list(&self, items: &[String], lang: &str) -> String {
+        self.reading(|i18n| i18n.join_list(items, lang))
+    }
+}
+
+#[cfg(feature = "tracing")]
+thread_local! {
+    /// The misses found on this thread while a [`MissEvents`] is alive, `None` when none is.
+    static DEFERRED: RefCell<Option<Vec<Miss>>> = const { RefCell::new(None) };
+}
+
+/// Holds back the `tracing` events of the misses found on this thread until it is dropped, so
+/// that subscribers don't run while a shared translator is locked. Created before the lock is
+/// taken, it is dropped after the guard; the outermost one emits the events.
+#[doc(hidden)]
+#[must_use]
+pub struct MissEvents {
+    #[cfg(feature = "tracing")]
+    outermost: bool,
+}
+
+impl MissEvents {
+    pub fn defer() -> MissEvents {
+        MissEvents {
+            #[cfg(feature = "tracing")]
+            outermost: DEFERRED.with(|deferred| {
+                let mut deferred = deferred.borrow_mut();
+                let outermost = deferred.is_none();
+                deferred.get_or_insert_with(Vec::new);
+                outermost
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for MissEvents {
+    fn drop(&mut self) {
+        if !self.outermost {
+            return;
+        }
+        let misses = DEFERRED.with(|deferred| deferred.borrow_mut().take());
+        misses.into_iter().flatten().for_each(|miss| miss.emit());
+    }
+}
+
+/// A missing translation, reported as a `tracing` event.
+#[cfg(feature = "tracing")]
+pub(crate) struct Miss {
+    pub(crate) lang: String,
+    pub(crate) namespace: Namespace,
+    pub(crate) key: String,
+}
+
+#[cfg(feature = "tracing")]
+impl Miss {
+    /// Emits the event, or queues it while a [`MissEvents`] is alive on this thread.
+    pub(crate) fn report(self) {
+        let miss = DEFERRED.with(|deferred| match &mut *deferred.borrow_mut() {
+            Some(misses) => {
+                misses.push(self);
+                None
+            }
+            None => Some(self),
+        });
+        if let Some(miss) = miss {
+            miss.emit();
+        }
+    }
+
+    fn emit(&self) {
+        tracing::info!(lang = %self.lang, ns = %self.namespace, key = self.key, "missing translation");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn independent_handles() {
+        let product = I18nHandle::new("en");
+        let legal = I18nHandle::new("en");
+        product.set_source(Path::new("./source"));
+        assert_eq!(product.translate("这是一个测试", None), "This is a test");
+        assert_eq!(legal.translate("这是一个测试", None), "这是一个测试");
+
+        let clone = product.clone();
+        clone.set_lang("de");
+        assert_eq!(product.current_lang(), "DE");
+        clone
+            .write()
+            .unwrap()
+            .set_override("de", "common", "这是一个测试", "Probe");
+        assert_eq!(product.translate("这是一个测试", None), "Probe");
+
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                let handle = product.clone();
+                std::thread::spawn(move || {
+                    handle.trans_with_inter("%1!", vec![i.to_string()], None)
+                })
+            })
+            .collect();
+        let results: Vec<String> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+        assert_eq!(results, ["0!", "1!", "2!", "3!"]);
+    }
+}
@@ -0,0 +1,60 @@
+//! Escaping of interpolated values for HTML output.
+use alloc::borrow::Cow;
+use core::fmt;
+
+fn entity(c: char) -> Option<&'static str> {
+    match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&#39;"),
+        _ => None,
+    }
+}
+
+/// Escapes `& < > " '` so that `s` can be put in HTML text or a quoted attribute.
+/// ```
+/// assert_eq!(easy_i18n::escape_html("<b>Tom & Jerry</b>"), "&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;");
+/// ```
+pub fn escape_html(s: &str) -> Cow<'_, str> {
+    if !s.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(s);
+    }
+    Cow::Owned(crate::render(s.len() + 16, |out| {
+        fmt::Write::write_str(&mut HtmlEscaper(out), s)
+    }))
+}
+
+/// A sink escaping everything written to it into the wrapped one.
+pub(crate) struct HtmlEscaper<'a, W: fmt::Write + ?Sized>(pub(crate) &'a mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for HtmlEscaper<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut last = 0;
+        for (i, c) in s.char_indices() {
+            if let Some(entity) = entity(c) {
+                self.0.write_str(&s[last..i])?;
+                self.0.write_str(entity)?;
+                last = i + 1;
+            }
+        }
+        self.0.write_str(&s[last..])
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(
+            escape_html(r#"<a href="x" title='y'>&</a>"#),
+            "&lt;a href=&quot;x&quot; title=&#39;y&#39;&gt;&amp;&lt;/a&gt;"
+        );
+        assert!(matches!(escape_html("plain 文本"), Cow::Borrowed(_)));
+        // already escaped text is escaped again, it may well be meant literally
+        assert_eq!(escape_html("&amp;"), "&amp;amp;");
+    }
+}
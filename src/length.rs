@@ -0,0 +1,221 @@
+//! Character budgets of translations, for displays that truncate long labels.
+use crate::prelude::*;
+use crate::style::Token;
+use crate::{I18n, Namespace};
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use anyhow::{Context, Result};
+use core::fmt;
+use serde::Serialize;
+#[cfg(feature = "std")]
+use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The budgets checked by [`I18n::validate_lengths`] besides the `max_len` of the entries.
+#[derive(Debug, Clone, Default)]
+pub struct LengthOptions {
+    /// Budgets by namespace and key, winning over the `max_len` of the entries.
+    pub limits: BTreeMap<Namespace, BTreeMap<String, usize>>,
+    /// The characters counted for each placeholder of a template, as the value it stands for
+    /// isn't known. 0 by default.
+    pub placeholder_allowance: usize,
+}
+
+impl LengthOptions {
+    /// Reads [`LengthOptions::limits`] from a json file of the same shape as a language file,
+    /// with budgets for values: `{"common": {"保存": 12}}`.
+    #[cfg(feature = "std")]
+    pub fn from_path(path: &Path) -> Result<LengthOptions> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("[length error]: can't read {}.", path.display()))?;
+        let limits = serde_json::from_str(&json)
+            .with_context(|| format!("[length error]: {} is not a limits file.", path.display()))?;
+        Ok(LengthOptions {
+            limits,
+            ..Default::default()
+        })
+    }
+
+    fn limit(&self, ns: &str, key: &str) -> Option<usize> {
+        self.limits.get(ns)?.get(key).copied()
+    }
+}
+
+/// A translation longer than its budget, found by [`I18n::validate_lengths`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LengthViolation {
+    pub lang: String,
+    pub namespace: Namespace,
+    pub key: String,
+    /// The platform of the variant, `None` for the base value.
+    pub platform: Option<String>,
+    /// The plural category of the variant, `None` for the base value.
+    pub plural: Option<String>,
+    pub max_len: usize,
+    /// The characters counted, placeholder allowances included.
+    pub len: usize,
+}
+
+impl fmt::Display for LengthViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}/{}", self.lang, self.namespace, self.key)?;
+        if let Some(platform) = &self.platform {
+            write!(f, " (platform '{platform}')")?;
+        }
+        if let Some(plural) = &self.plural {
+            write!(f, " (plural '{plural}')")?;
+        }
+        write!(f, ": {} characters, at most {}", self.len, self.max_len)
+    }
+}
+
+impl I18n {
+    /// The length of [`I18n::translate`] of `text` in grapheme clusters, as a user counts
+    /// characters: `"保存"` is 2 where its `len()` is 6, an accent written as a combining mark
+    /// adds nothing.
+    pub fn translate_len(&self, text: &str, ns: Option<Namespace>) -> usize {
+        self.translate(text, ns).graphemes(true).count()
+    }
+
+    /// Checks the translations of every language, lazily registered ones included, against their
+    /// budget: the one of `opts` for the key, else the `max_len` of the entry. Platform and
+    /// plural variants are held to the budget of their entry.
+    ///
+    /// Characters are counted as grapheme clusters, so `"保存"` and `"é"` written with a combining
+    /// accent count 2 and 1. Each placeholder of the style of the language counts
+    /// [`LengthOptions::placeholder_allowance`], an escaped delimiter counts 1. Violations are
+    /// sorted by language, namespace, key and variant.
+    pub fn validate_lengths(&self, opts: &LengthOptions) -> Vec<LengthViolation> {
+        let mut violations = vec![];
+        for lang in self.languages() {
+            let Some(source) = self.lang_source(&lang) else {
+                continue;
+            };
+            let style = self.stored_style(&lang);
+            for (ns, entries) in &source.namespaces {
+                for (key, entry) in entries {
+                    let Some(max_len) = opts.limit(ns, key).or(entry.max_len) else {
+                        continue;
+                    };
+                    let platforms = entry.platforms.iter().map(|(p, v)| (Some(p), None, v));
+                    // the `other` variant is the value itself
+                    let plurals = entry
+                        .plurals
+                        .iter()
+                        .filter(|(_, v)| **v != entry.value)
+                        .map(|(c, v)| (None, Some(c), v));
+                    let values = core::iter::once((None, None, &entry.value))
+                        .chain(platforms)
+                        .chain(plurals);
+                    for (platform, plural, value) in values {
+                        let mut len = 0;
+                        let mut last = 0;
+                        for caps in style.regex().captures_iter(value) {
+                            let token = caps.get(0).unwrap();
+                            len += value[last..token.start()].graphemes(true).count();
+                            len += match style.token(&caps) {
+                                Token::Escape => 1,
+                                Token::Placeholder(_) | Token::Rest => opts.placeholder_allowance,
+                            };
+                            last = token.end();
+                        }
+                        len += value[last..].graphemes(true).count();
+                        if len > max_len {
+                            violations.push(LengthViolation {
+                                lang: lang.clone(),
+                                namespace: ns.clone(),
+                                key: key.clone(),
+                                platform: platform.cloned(),
+                                plural: plural.cloned(),
+                                max_len,
+                                len,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        violations.sort_by(|a, b| {
+            (&a.lang, &a.namespace, &a.key, &a.platform, &a.plural).cmp(&(
+                &b.lang,
+                &b.namespace,
+                &b.key,
+                &b.platform,
+                &b.plural,
+            ))
+        });
+        violations
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{PlaceholderStyle, Source};
+
+    #[test]
+    fn translate_len() {
+        let mut i18n = I18n::new("ja");
+        i18n.set_override("ja", "common", "保存", "セーブ");
+        i18n.set_override("ja", "hud", "名字", "Ame\u{301}lie 👩‍👩‍👧");
+        assert_eq!(i18n.translate_len("保存", None), 3);
+        assert_eq!(i18n.translate_len("名字", Some("hud".to_string())), 8);
+        assert_eq!(i18n.translate_len("没有", None), 2);
+    }
+
+    #[test]
+    fn budgets() {
+        let mut i18n = I18n::new("en");
+        let en: Source = serde_json::from_str(
+            r#"{"hud": {
+                "保存": {"value": "Save the game", "max_len": 12},
+                "生命": {"value": "HP %1/%2", "max_len": 8},
+                "继续": {"value": "Go", "platforms": {"web": "Click to continue"}, "max_len": 10},
+                "%1条消息": {"one": "One new message", "other": "%1 new", "max_len": 10},
+                "退出": "Leave the game now"
+            }}"#,
+        )
+        .unwrap();
+        i18n.insert_source("en", en);
+        let ja: Source = serde_json::from_str(
+            r#"{"hud": {"保存": {"value": "セーブ", "max_len": 3}, "名字": "Ché %%"}}"#,
+        )
+        .unwrap();
+        i18n.insert_source("ja", ja);
+
+        let mut opts = LengthOptions::default();
+        let found = |i18n: &I18n, opts: &LengthOptions| -> Vec<String> {
+            let violations = i18n.validate_lengths(opts);
+            violations.iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(
+            found(&i18n, &opts),
+            [
+                "EN hud/%1条消息 (plural 'one'): 15 characters, at most 10",
+                "EN hud/保存: 13 characters, at most 12",
+                "EN hud/继续 (platform 'web'): 17 characters, at most 10"
+            ]
+        );
+        opts.placeholder_allowance = 3;
+        opts.limits =
+            serde_json::from_str(r#"{"hud": {"退出": 20, "名字": 4, "保存": 13}}"#).unwrap();
+        assert_eq!(
+            found(&i18n, &opts),
+            [
+                "EN hud/%1条消息 (plural 'one'): 15 characters, at most 10",
+                "EN hud/生命: 10 characters, at most 8",
+                "EN hud/继续 (platform 'web'): 17 characters, at most 10",
+                // `é` is one grapheme, `%%` one character
+                "JA hud/名字: 5 characters, at most 4"
+            ]
+        );
+        i18n.set_placeholder_style_for("en", PlaceholderStyle::Brace);
+        opts.limits.clear();
+        assert_eq!(found(&i18n, &opts).len(), 3);
+        let json = serde_json::to_string(&i18n.validate_lengths(&opts)).unwrap();
+        assert!(json.starts_with(
+            r#"[{"lang":"EN","namespace":"hud","key":"%1条消息","platform":null,"plural":"one""#
+        ));
+        assert!(LengthOptions::from_path(Path::new("./source/en.json")).is_err());
+    }
+}
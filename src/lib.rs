@@ -1,6 +1,7 @@
 //! A easy i18n tool
 //! ### Example
-//! ``` rust
+#![cfg_attr(feature = "std", doc = "``` rust")]
+#![cfg_attr(not(feature = "std"), doc = "``` ignore")]
 //! use easy_i18n::{self, i18n, I18N};
 //! use std::path::Path;
 //! // load source
@@ -18,161 +19,6411 @@
 //!
 //! // If you have different translation results in other contexts, you can set the namespace
 //! i18n!("他的成绩是，语文：%1, 数学：%2", ns="namespace1", 88, 100); // His grades are Chinese: 88, Mathematics: 100, and the test is not bad.
+//!
+//! // A placeholder can request casing of its value with %{N:upper}, %{N:lower} or %{N:title}
+//! i18n!("欢迎，%{1:title}", "jane doe"); // Welcome, Jane Doe
+//!
+//! // Case helpers that follow the active language (see the `case` module for the rules)
+//! easy_i18n::to_upper("istanbul"); // ISTANBUL, or İSTANBUL when the language is TR
 //! ```
-use anyhow::{Context, Result};
-use once_cell::sync::Lazy;
-use regex::{Captures, Regex};
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use anyhow::{bail, Context, Result};
+use core::fmt::{self, Display};
+use de::RawSource;
+use html::HtmlEscaper;
+use loader::Loaders;
+use prelude::*;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "log", feature = "intern"))]
 use std::sync::Mutex;
-use std::{collections::HashMap, fs, fs::File, io::BufReader, path::Path};
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+use style::Token;
+use template::{Segment, Template};
+
+#[cfg(feature = "zip")]
+mod archive;
+#[cfg(feature = "async")]
+mod async_handle;
+mod bidi;
+mod cache;
+pub mod case;
+#[cfg(feature = "std")]
+mod config;
+#[cfg(feature = "std")]
+mod context;
+/// Without `std` there are no threads to scope a context to, so none is ever entered.
+#[cfg(not(feature = "std"))]
+mod context {
+    use crate::prelude::*;
+    use crate::{MissingArg, Namespace};
+
+    pub(crate) fn lang() -> Option<String> {
+        None
+    }
+
+    pub(crate) fn namespace() -> Option<Namespace> {
+        None
+    }
+
+    pub(crate) fn missing_arg() -> Option<MissingArg> {
+        None
+    }
+}
+mod de;
+mod entry;
+#[cfg(feature = "std")]
+mod handle;
+mod html;
+mod length;
+mod lint;
+mod list;
+mod loader;
+#[cfg(feature = "std")]
+mod message;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod msg_handle;
+mod negotiate;
+#[cfg(feature = "std")]
+mod notify;
+mod opt;
+mod prelude;
+mod provenance;
+mod pseudo;
+#[cfg(feature = "std")]
+mod save;
+mod schema;
+#[cfg(feature = "std")]
+mod snapshot;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+mod style;
+mod suggest;
+mod template;
+#[cfg(feature = "tmx")]
+pub mod tmx;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
+
+#[cfg(feature = "async")]
+pub use async_handle::AsyncI18nHandle;
+pub use bidi::BidiIsolation;
+#[cfg(feature = "std")]
+pub use config::I18nConfig;
+#[cfg(feature = "std")]
+pub use context::{with_lang, ContextGuard, I18nContext, Scoped};
+#[cfg(feature = "derive")]
+pub use easy_i18n_derive::I18nLabel;
+pub use entry::Entry;
+#[cfg(feature = "std")]
+pub use handle::I18nHandle;
+#[doc(hidden)]
+#[cfg(feature = "std")]
+pub use handle::MissEvents;
+pub use html::escape_html;
+pub use length::{LengthOptions, LengthViolation};
+pub use lint::{LintKind, LintOptions, LintWarning, PlaceholderGap, StringIssue, StringLint};
+pub use list::LIST_NS;
+#[cfg(feature = "yaml")]
+pub use loader::YamlLoader;
+pub use loader::{JsonLoader, SourceLoader};
+#[cfg(feature = "std")]
+pub use message::Message;
+#[cfg(feature = "metrics")]
+pub use metrics::{metrics, reset_metrics, I18nMetrics, LangMetrics};
+pub use msg_handle::{MsgHandle, MsgTable};
+pub use negotiate::parse_accept_language;
+#[cfg(feature = "std")]
+pub use notify::{on_lang_change, on_source_change, unsubscribe, SubscriptionId};
+pub use opt::Opt;
+pub use provenance::TranslationResult;
+pub use schema::{source_schema, SchemaViolation};
+pub use style::PlaceholderStyle;
+
+/// The `alloc` items the exported macros expand to, so they build in `no_std` crates too.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
+
+static PLURAL_REG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{(?:%(\d+):)?([^{}|]*)\|([^{}|]*)\}").unwrap());
+
+/// A conditional section, `%{2?，中间名 %2}`, kept only when value 2 is present.
+static SECTION_REG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"%\{(\d+)\?((?:[^{}]|%\{\w+(?::\w+)?\})*)\}").unwrap());
 
-static INTER_REG: Lazy<Regex> = Lazy::new(|| Regex::new(r"%\d+").unwrap());
+static NAMED_REG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"%\{([A-Za-z_]\w*)(?::(\w+))?\}").unwrap());
 
-pub static I18N: Lazy<Mutex<I18n>> = Lazy::new(|| Mutex::new(I18n::new("cn")));
+/// [`NAMED_REG`] or a bare `%name`, whose name is ASCII so that `%math分` ends at `分`.
+static KEYWORD_REG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"%\{([A-Za-z_]\w*)(?::(\w+))?\}|%([A-Za-z_][A-Za-z0-9_]*)").unwrap());
 
+/// The global translator used by the free functions and the macros.
+#[cfg(feature = "std")]
+pub static I18N: Lazy<I18nHandle> = Lazy::new(|| I18nHandle::new("cn"));
+
+/// Switches the global language and notifies the [`on_lang_change`] subscribers if it changed.
+#[cfg(feature = "std")]
 pub fn set_lang(lang: &str) {
-    let mut i18n = I18N.lock().unwrap();
-    i18n.set_lang(lang);
+    let (old, new) = {
+        let mut i18n = I18N.lock().unwrap();
+        let old = i18n.lang.clone();
+        i18n.set_lang(lang);
+        (old, i18n.lang.clone())
+    };
+    if old != new {
+        notify::lang_changed(&old, &new);
+    }
+}
+
+/// Switches the global language to the first of `prefs` that is loaded, see
+/// [`I18n::negotiate_preferences`], and returns it.
+#[cfg(feature = "std")]
+pub fn set_lang_from_preferences(prefs: &[&str]) -> Option<String> {
+    let lang = I18N.read().unwrap().negotiate_preferences(prefs)?;
+    set_lang(&lang);
+    Some(lang)
+}
+
+/// The active language of the global translator, see [`I18n::current_lang`].
+#[cfg(feature = "std")]
+pub fn current_lang() -> String {
+    I18N.current_lang()
 }
 
+/// Loads the global catalogs and notifies the [`on_source_change`] subscribers.
+///
+/// The files are read and parsed without holding the global lock, with the load options and
+/// loaders set at the time of the call; translations keep being served from the previous
+/// catalogs until the new ones are swapped in.
+#[cfg(feature = "std")]
 pub fn set_source(path: &Path) {
+    I18N.set_source(path);
+    notify::source_changed();
+}
+
+/// Initializes the global translator from `path` in `lang` unless it was already, see
+/// [`I18n::init_once`]. The check and the load happen under one lock, so concurrent
+/// initializers load the files once; the files are therefore read with the lock held.
+#[cfg(feature = "std")]
+pub fn init_once(path: &Path, lang: &str) -> bool {
+    let (loaded, old, new) = {
+        let mut i18n = I18N.lock().unwrap();
+        let old = i18n.lang.clone();
+        let loaded = i18n.init_once(path, lang);
+        (loaded, old, i18n.lang.clone())
+    };
+    if loaded {
+        notify::source_changed();
+        if old != new {
+            notify::lang_changed(&old, &new);
+        }
+    }
+    loaded
+}
+
+/// [`set_source`], calling `progress(done, total)` after each language file, see
+/// [`I18n::set_source_with_progress`]. The callback runs without the global lock held.
+#[cfg(feature = "std")]
+pub fn set_source_with_progress(path: &Path, progress: impl FnMut(usize, usize)) {
+    I18N.set_source_with_progress(path, progress);
+    notify::source_changed();
+}
+
+/// Replaces the global catalogs with sources built in memory, see [`I18n::set_source_map`].
+#[cfg(feature = "std")]
+pub fn set_source_map(map: HashMap<String, Source>) {
+    I18N.lock().unwrap().set_source_map(map);
+    notify::source_changed();
+}
+
+/// Replaces the global translator with `i18n`, e.g. one received from a coordinating process
+/// with [`I18n::from_bytes`].
+#[cfg(feature = "std")]
+pub fn install(i18n: I18n) {
+    let mut global = I18N.lock().unwrap();
+    let old = core::mem::replace(&mut *global, i18n);
+    let new = global.lang.clone();
+    drop(global);
+    notify::source_changed();
+    if old.lang != new {
+        notify::lang_changed(&old.lang, &new);
+    }
+}
+
+/// Loads a named set of global catalogs, see [`I18n::add_source_set`].
+/// Loading the active set notifies the [`on_source_change`] subscribers.
+#[cfg(feature = "std")]
+pub fn add_source_set(name: &str, path: &Path) {
+    let active = {
+        let mut i18n = I18N.lock().unwrap();
+        i18n.add_source_set(name, path);
+        name == i18n.active_source_set()
+    };
+    if active {
+        notify::source_changed();
+    }
+}
+
+/// Switches the global translator to another source set, see [`I18n::set_active_source_set`],
+/// and notifies the [`on_source_change`] subscribers.
+#[cfg(feature = "std")]
+pub fn set_active_source_set(name: &str) -> Result<()> {
+    I18N.lock().unwrap().set_active_source_set(name)?;
+    notify::source_changed();
+    Ok(())
+}
+
+/// Sets the options used by the next `set_source*` call and by key lookups, see [`LoadOptions`].
+#[cfg(feature = "std")]
+pub fn set_load_options(options: LoadOptions) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_load_options(options);
+}
+
+/// Treat empty or whitespace-only translations as missing, see [`I18n::set_empty_as_missing`].
+#[cfg(feature = "std")]
+pub fn set_empty_as_missing(on: bool) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_empty_as_missing(on);
+}
+
+/// Look missing namespaced keys up in `common`, see [`I18n::set_common_fallback`].
+#[cfg(feature = "std")]
+pub fn set_common_fallback(on: bool) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_common_fallback(on);
+}
+
+/// Sets the namespaces searched after the requested one, see [`I18n::set_ns_fallback`].
+#[cfg(feature = "std")]
+pub fn set_ns_fallback(chain: &[&str]) -> Result<()> {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_ns_fallback(chain)
+}
+
+#[deprecated(note = "use `set_ns_fallback`")]
+#[cfg(feature = "std")]
+pub fn set_namespace_fallback_chain(chain: &[&str]) -> Result<()> {
+    set_ns_fallback(chain)
+}
+
+/// Sets the separator used by `%0`, see [`I18n::set_join_separator`].
+#[cfg(feature = "std")]
+pub fn set_join_separator(sep: &str) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_join_separator(sep);
+}
+
+/// Layers translations from an environment variable, see [`I18n::set_source_from_env`].
+#[cfg(feature = "std")]
+pub fn set_source_from_env(var: &str) -> Result<()> {
+    I18N.lock().unwrap().set_source_from_env(var)?;
+    notify::source_changed();
+    Ok(())
+}
+
+/// Strict variant of [`set_source`], see [`I18n::try_set_source`]. Like [`set_source`], the
+/// files are loaded outside the global lock.
+#[cfg(feature = "std")]
+pub fn try_set_source(path: &Path) -> Result<()> {
+    I18N.try_set_source(path)?;
+    notify::source_changed();
+    Ok(())
+}
+
+/// Turns placeholder substitution on or off for every thread, see
+/// [`I18n::set_interpolation_enabled`].
+#[cfg(feature = "std")]
+pub fn set_interpolation_enabled(on: bool) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_interpolation_enabled(on);
+}
+
+/// Loads language files from a directory tree, see [`I18n::set_source_recursive`]. Like
+/// [`set_source`], the files are loaded outside the global lock.
+#[cfg(feature = "std")]
+pub fn set_source_recursive(path: &Path, extensions: &[&str]) {
+    I18N.set_source_recursive(path, extensions);
+    notify::source_changed();
+}
+
+/// Records the language files found in `path` without parsing them, see [`I18n::set_source_lazy`].
+#[cfg(feature = "std")]
+pub fn set_source_lazy(path: &Path) {
+    I18N.lock().unwrap().set_source_lazy(path);
+    notify::source_changed();
+}
+
+/// Loads the global catalogs from a SQLite database, see [`I18n::set_source_sqlite`].
+#[cfg(feature = "sqlite")]
+pub fn set_source_sqlite(path: &Path) -> Result<()> {
+    I18N.lock().unwrap().set_source_sqlite(path)?;
+    notify::source_changed();
+    Ok(())
+}
+
+/// Loads the global catalogs from an open SQLite connection, see
+/// [`I18n::set_source_sqlite_conn`].
+#[cfg(feature = "sqlite")]
+pub fn set_source_sqlite_conn(conn: &rusqlite::Connection) -> Result<()> {
+    I18N.lock().unwrap().set_source_sqlite_conn(conn)?;
+    notify::source_changed();
+    Ok(())
+}
+
+/// Loads the global catalogs from a zip archive, see [`I18n::set_source_from_zip`].
+#[cfg(feature = "zip")]
+pub fn set_source_from_zip(path: &Path) -> Result<()> {
+    I18N.lock().unwrap().set_source_from_zip(path)?;
+    notify::source_changed();
+    Ok(())
+}
+
+/// Adds a parser for a file format to the global translator, see [`I18n::register_loader`].
+#[cfg(feature = "std")]
+pub fn register_loader(loader: impl SourceLoader + 'static) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.register_loader(loader);
+}
+
+/// Reports missing keys of the global translator to `handler`, see
+/// [`I18n::set_missing_key_handler`]. The handler must not translate through the global
+/// translator, which is locked while it runs.
+#[cfg(feature = "std")]
+pub fn set_missing_key_handler(handler: impl Fn(&MissingKey) + Send + Sync + 'static) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_missing_key_handler(handler);
+}
+
+/// Rewrites the keys of every global lookup, see [`I18n::set_key_transform`].
+#[cfg(feature = "std")]
+pub fn set_key_transform(transform: impl Fn(&str) -> String + Send + Sync + 'static) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_key_transform(transform);
+}
+
+/// Joins `items` the way `lang` writes lists, see [`I18n::join_list`].
+#[cfg(feature = "std")]
+pub fn join_list(items: &[String], lang: &str) -> String {
+    I18N.join_list(items, lang)
+}
+
+/// Sets the namespace of global lookups made without one, see [`I18n::set_default_namespace`].
+#[cfg(feature = "std")]
+pub fn set_default_namespace(ns: &str) {
+    I18N.lock().unwrap().set_default_namespace(ns);
+}
+
+/// Sets the language used on a miss of the global language, see [`I18n::set_base_lang`].
+#[cfg(feature = "std")]
+pub fn set_base_lang(lang: &str) {
+    I18N.lock().unwrap().set_base_lang(lang);
+}
+
+/// Caches the results of plain global lookups, see [`I18n::set_translate_cache`].
+#[cfg(feature = "std")]
+pub fn set_translate_cache(on: bool) {
+    I18N.lock().unwrap().set_translate_cache(on);
+}
+
+/// Registers a key of the global translator for [`MsgTable::get`], see [`I18n::key_handle`].
+#[cfg(feature = "std")]
+pub fn key_handle(key: &str, ns: Option<&str>) -> MsgHandle {
+    I18N.lock().unwrap().key_handle(key, ns)
+}
+
+/// A snapshot of the global translations of every handle, to read in a loop without taking the
+/// lock, see [`I18n::msg_table`].
+#[cfg(feature = "std")]
+pub fn msg_table() -> MsgTable {
+    I18N.read().unwrap().msg_table()
+}
+
+/// Pseudo-localizes the output of the global translating calls, see [`I18n::set_pseudo`].
+#[cfg(feature = "std")]
+pub fn set_pseudo(on: bool) {
+    I18N.lock().unwrap().set_pseudo(on);
+}
+
+/// Annotates the output of the global translating calls with their keys, see
+/// [`I18n::set_debug_keys`].
+#[cfg(feature = "std")]
+pub fn set_debug_keys(on: bool) {
+    I18N.lock().unwrap().set_debug_keys(on);
+}
+
+/// Decorates the keys the global translator shows untranslated, see
+/// [`I18n::set_missing_decoration`].
+#[cfg(feature = "std")]
+pub fn set_missing_decoration(decoration: Option<(&str, &str)>) {
+    I18N.lock().unwrap().set_missing_decoration(decoration);
+}
+
+/// Decorates the translations of the base language, see [`I18n::set_base_decoration`].
+#[cfg(feature = "std")]
+pub fn set_base_decoration(decoration: Option<(&str, &str)>) {
+    I18N.lock().unwrap().set_base_decoration(decoration);
+}
+
+/// Adds "did you mean" suggestions to missing-key reports, see [`I18n::set_suggest_missing`].
+#[cfg(feature = "std")]
+pub fn set_suggest_missing(on: bool) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_suggest_missing(on);
+}
+
+/// Translates `key` with interpolation values known only at runtime.
+///
+/// This is the function form of [`i18n!`]: it locks the global translator once and gives the same
+/// output as the macro called with the same key, namespace and values.
+/// ```
+/// use easy_i18n::t;
+/// let vals = vec![88, 100];
+/// let args: Vec<&dyn std::fmt::Display> = vals.iter().map(|v| v as _).collect();
+/// t("他的成绩是，语文：%1, 数学：%2", None, &args);
+/// ```
+#[cfg(feature = "std")]
+pub fn t(key: &str, ns: Option<&str>, args: &[&dyn Display]) -> String {
+    // formatted before locking, as a value may well translate itself
+    let vals: Vec<String> = args.iter().map(|v| v.to_string()).collect();
+    let ns = ns.map(|ns| ns.to_string());
+    let _misses = MissEvents::defer();
+    let i18n = I18N.read().unwrap();
+    if vals.is_empty() {
+        i18n.translate(key, ns)
+    } else {
+        i18n.trans_with_inter(key, vals, ns)
+    }
+}
+
+/// Translates `key` and fills its placeholders from a list built at runtime, e.g. with
+/// [`i18n_args!`].
+///
+/// Unlike [`t`], the template is always interpolated: `%1` is the first value, `%0` all of them,
+/// and indices past the end of `args`, which may well be empty, follow the policy set by
+/// [`set_missing_arg`].
+/// ```
+/// use easy_i18n::{i18n_args, t_args};
+/// let reasons = vec!["timeout", "refused"];
+/// t_args("原因：%0", None, &i18n_args![..reasons]);
+/// t_args("他的成绩是，语文：%1, 数学：%2", None, &[88, 100]);
+/// ```
+#[cfg(feature = "std")]
+pub fn t_args<T: Display>(key: &str, ns: Option<&str>, args: &[T]) -> String {
+    // formatted before locking, as in `t`
+    let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+    let _misses = MissEvents::defer();
+    let i18n = I18N.read().unwrap();
+    i18n.interpolate_translation(key, &args, ns.map(|ns| ns.to_string()), Fill::T_ARGS, None)
+}
+
+/// Translates `key` with named values, see [`I18n::trans_with_map`].
+#[cfg(feature = "std")]
+pub fn trans_with_map(key: &str, vals: &HashMap<&str, String>, ns: Option<&str>) -> String {
+    I18N.trans_with_map(key, vals, ns)
+}
+
+/// Translates `key` with values that may be absent, see [`I18n::trans_with_inter_opt`].
+#[cfg(feature = "std")]
+pub fn trans_with_inter_opt(key: &str, vals: Vec<Option<String>>, ns: Option<&str>) -> String {
+    I18N.trans_with_inter_opt(key, vals, ns.map(|ns| ns.to_string()))
+}
+
+/// The length of the global translation of `key` in grapheme clusters, see
+/// [`I18n::translate_len`].
+#[cfg(feature = "std")]
+pub fn translate_len(key: &str, ns: Option<&str>) -> usize {
+    I18N.reading(|i18n| i18n.translate_len(key, ns.map(|ns| ns.to_string())))
+}
+
+/// The global translation of `key` with the language and namespace that provided it, see
+/// [`I18n::translate_checked`].
+#[cfg(feature = "std")]
+pub fn translate_checked(key: &str, ns: Option<&str>) -> TranslationResult {
+    I18N.reading(|i18n| i18n.translate_checked(key, ns.map(|ns| ns.to_string())))
+}
+
+/// The global translations of `keys`, in their order, taking the lock once instead of once per
+/// key as [`i18n!`] does. See [`I18n::translate_many`].
+#[cfg(feature = "std")]
+pub fn translate_many<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+    ns: Option<&str>,
+) -> Vec<String> {
+    I18N.reading(|i18n| i18n.translate_many(keys, ns))
+}
+
+/// The global translations of `keys` keyed by the keys, see [`I18n::translate_many_map`].
+#[cfg(feature = "std")]
+pub fn translate_many_map<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+    ns: Option<&str>,
+) -> HashMap<&'a str, String> {
+    I18N.reading(|i18n| i18n.translate_many_map(keys, ns))
+}
+
+/// The translation of `key` with its placeholders shown as `[N]`, see [`I18n::preview`].
+#[cfg(feature = "std")]
+pub fn preview(key: &str, ns: Option<&str>) -> String {
+    I18N.reading(|i18n| i18n.preview(key, ns.map(|ns| ns.to_string())))
+}
+
+/// The key the translation of `key` in the disambiguation context `ctx` is stored under:
+/// `key`, the EOT character `\u{4}`, then `ctx`, as gettext does, see [`I18n::translate_ctx`].
+pub fn context_key(key: &str, ctx: &str) -> String {
+    format!("{key}\u{4}{ctx}")
+}
+
+/// Translates `key` with keyword values, see [`I18n::trans_with_named`].
+#[cfg(feature = "std")]
+pub fn trans_with_named(key: &str, vals: &HashMap<&str, String>, ns: Option<&str>) -> String {
+    I18N.trans_with_named(key, vals, ns)
+}
+
+/// Sets how placeholders without a value are rendered, see [`I18n::set_missing_arg`].
+#[cfg(feature = "std")]
+pub fn set_missing_arg(policy: MissingArg) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_missing_arg(policy);
+}
+
+/// Wraps the values of the global interpolations in bidi isolates, see
+/// [`I18n::set_bidi_isolation`].
+#[cfg(feature = "std")]
+pub fn set_bidi_isolation(mode: BidiIsolation) {
+    I18N.lock().unwrap().set_bidi_isolation(mode);
+}
+
+/// Sets the default placeholder style, see [`I18n::set_placeholder_style`].
+#[cfg(feature = "std")]
+pub fn set_placeholder_style(style: PlaceholderStyle) {
+    let mut i18n = I18N.lock().unwrap();
+    i18n.set_placeholder_style(style);
+}
+
+/// Sets the placeholder style of `lang`, see [`I18n::set_placeholder_style_for`].
+#[cfg(feature = "std")]
+pub fn set_placeholder_style_for(lang: &str, style: PlaceholderStyle) {
     let mut i18n = I18N.lock().unwrap();
-    i18n.set_source(path);
+    i18n.set_placeholder_style_for(lang, style);
+}
+
+/// Uppercases `s` following the casing rules of the active language, that of the current
+/// [`I18nContext`] if any.
+#[cfg(feature = "std")]
+pub fn to_upper(s: &str) -> String {
+    let lang = I18N.reading(|i18n| i18n.active_lang().into_owned());
+    case::upper(s, &lang)
+}
+
+/// Lowercases `s` following the casing rules of the active language, that of the current
+/// [`I18nContext`] if any.
+#[cfg(feature = "std")]
+pub fn to_lower(s: &str) -> String {
+    let lang = I18N.reading(|i18n| i18n.active_lang().into_owned());
+    case::lower(s, &lang)
+}
+
+/// Title-cases `s` following the casing rules of the active language, that of the current
+/// [`I18nContext`] if any.
+#[cfg(feature = "std")]
+pub fn to_title(s: &str) -> String {
+    let lang = I18N.reading(|i18n| i18n.active_lang().into_owned());
+    case::title(s, &lang)
 }
 
 type Namespace = String;
 
+/// The string `f` writes, for the calls returning what their `_into` counterpart writes.
+fn render(capacity: usize, f: impl FnOnce(&mut String) -> fmt::Result) -> String {
+    let mut out = String::with_capacity(capacity);
+    // writing into a String never fails
+    let _ = f(&mut out);
+    out
+}
+
+/// How an interpolating call fills the placeholders of its template.
+#[derive(Debug, Clone, Copy, Default)]
+struct Fill {
+    /// HTML-escape the values, see [`I18n::trans_with_inter_html`].
+    html: bool,
+    /// Apply the missing-arg policy even when no value is given, instead of keeping the
+    /// placeholders as written.
+    without_values: bool,
+    /// The style the placeholders of the template are written in.
+    style: PlaceholderStyle,
+}
+
+impl Fill {
+    const HTML: Fill = Fill {
+        html: true,
+        without_values: false,
+        style: PlaceholderStyle::Percent,
+    };
+    /// [`t_args`] always interpolates.
+    #[cfg(feature = "std")]
+    const T_ARGS: Fill = Fill {
+        html: false,
+        without_values: true,
+        style: PlaceholderStyle::Percent,
+    };
+}
+
+/// The namespace used when none is given.
+const DEFAULT_NS: &str = "common";
+
+/// A successful lookup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Found<'a> {
+    pub(crate) entry: &'a Entry,
+    pub(crate) namespace: Namespace,
+    /// Found in the base language after a miss in the active one, see [`I18n::set_base_lang`].
+    pub(crate) from_base: bool,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct I18n {
     pub(crate) lang: String,
     pub(crate) source: HashMap<String, Source>,
+    pub(crate) lazy: HashMap<String, LazySource>,
+    /// The source sets other than the active one, by name.
+    pub(crate) source_sets: HashMap<String, SourceSet>,
+    /// The name of the active source set, `None` for [`DEFAULT_SOURCE_SET`].
+    pub(crate) active_set: Option<String>,
+    pub(crate) load_options: LoadOptions,
+    pub(crate) load_warnings: Vec<LoadWarning>,
+    pub(crate) empty_as_missing: bool,
+    pub(crate) common_fallback: bool,
+    pub(crate) ns_fallback: Vec<Namespace>,
+    pub(crate) default_namespace: Option<Namespace>,
+    pub(crate) base_lang: Option<String>,
+    pub(crate) join_separator: String,
+    pub(crate) interpolation_enabled: bool,
+    pub(crate) missing_arg: MissingArg,
+    pub(crate) bidi_isolation: BidiIsolation,
+    pub(crate) placeholder_style: PlaceholderStyle,
+    /// The placeholder styles set by language, see [`I18n::set_placeholder_style_for`].
+    pub(crate) placeholder_styles: HashMap<String, PlaceholderStyle>,
+    pub(crate) loaders: Loaders,
+    #[cfg(feature = "std")]
+    pub(crate) source_path: Option<PathBuf>,
+    /// Translations set at runtime by language, consulted before `source`.
+    pub(crate) overrides: HashMap<String, Source>,
+    pub(crate) missing_key_handler: MissingKeyHandler,
+    pub(crate) key_transform: KeyTransform,
+    pub(crate) debug_keys: bool,
+    /// Written around a key shown untranslated, see [`I18n::set_missing_decoration`].
+    pub(crate) missing_decoration: Option<(String, String)>,
+    /// Written around a translation of the base language, see [`I18n::set_base_decoration`].
+    pub(crate) base_decoration: Option<(String, String)>,
+    pub(crate) pseudo: bool,
+    pub(crate) pseudo_expansion: f32,
+    pub(crate) suggest_missing: bool,
+    pub(crate) translate_cache: cache::TranslateCache,
+    /// The keys of the handles given by [`I18n::key_handle`].
+    pub(crate) msg_keys: msg_handle::MsgKeys,
+    /// Whether [`I18n::init_once`] has loaded the sources.
+    pub(crate) initialized: bool,
+    #[cfg(feature = "intern")]
+    pub(crate) interner: Interner,
+    #[cfg(feature = "log")]
+    pub(crate) logged_misses: MissLog,
+    /// The counters of `lang`.
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: metrics::LangCounters,
 }
 
-impl I18n {
-    pub fn new(lang: &str) -> I18n {
-        I18n {
-            lang: lang.to_uppercase(),
-            source: HashMap::new(),
+/// The misses already logged, as `(lang, namespace, key)`, so that each is logged once. The key
+/// is `None` for a namespace the language doesn't have.
+#[cfg(feature = "log")]
+#[derive(Debug, Default)]
+pub(crate) struct MissLog(Mutex<std::collections::HashSet<(String, Namespace, Option<String>)>>);
+
+#[cfg(feature = "log")]
+impl MissLog {
+    fn warn_once(&self, lang: &str, ns: &str, key: &str) {
+        let mut logged = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if logged.insert((lang.to_string(), ns.to_string(), Some(key.to_string()))) {
+            log::warn!("missing translation of `{key}` in namespace `{ns}` ({lang})");
         }
     }
 
-    pub fn set_lang(&mut self, lang: &str) {
-        self.lang = lang.to_uppercase();
+    fn warn_namespace_once(&self, lang: &str, ns: &str) {
+        let mut logged = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if logged.insert((lang.to_string(), ns.to_string(), None)) {
+            log::warn!("namespace `{ns}` not found for lang {lang}");
+        }
     }
 
-    pub fn set_source(&mut self, path: &Path) {
-        self.source = load_source(path);
+    fn clear(&self) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clear();
     }
+}
 
-    pub fn translate(&self, text: &str, ns: Option<Namespace>) -> String {
-        self.source
-            .get(self.lang.as_str())
-            .and_then(|source| source.get_val(text, ns))
-            .unwrap_or(text.to_string())
+#[cfg(feature = "log")]
+impl Clone for MissLog {
+    fn clone(&self) -> Self {
+        MissLog(Mutex::new(
+            self.0.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        ))
     }
+}
 
-    pub fn trans_with_inter(&self, text: &str, vals: Vec<String>, ns: Option<Namespace>) -> String {
-        let new_text = self.translate(text, ns);
-        INTER_REG
-            .replace_all(new_text.as_str(), |caps: &Captures| {
-                caps.get(0)
-                    .and_then(|m| m.as_str().replace('%', "").parse::<u8>().ok())
-                    .and_then(|v| vals.get(v as usize - 1))
-                    .map(|v| v.to_string())
-                    .unwrap_or("".to_string())
-            })
-            .into_owned()
+/// Pool of translated strings handed out by [`I18n::translate_interned`].
+#[cfg(feature = "intern")]
+#[derive(Debug, Default)]
+pub(crate) struct Interner(Mutex<std::collections::HashSet<Arc<str>>>);
+
+#[cfg(feature = "intern")]
+impl Interner {
+    fn intern(&self, text: String) -> Arc<str> {
+        let mut pool = self.0.lock().unwrap();
+        if let Some(interned) = pool.get(text.as_str()) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(text);
+        pool.insert(interned.clone());
+        interned
     }
-}
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct Source(HashMap<Namespace, HashMap<String, String>>);
-impl Source {
-    pub fn from_path(path: &Path) -> Result<Self> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let mut json_val = serde_json::Deserializer::from_reader(reader);
-        Source::deserialize(&mut json_val).context("[source error]: source parse error.")
+    fn clear(&self) {
+        self.0.lock().unwrap().clear();
     }
+}
 
-    pub fn get_val(&self, key: &str, ns: Option<Namespace>) -> Option<String> {
-        let ns = ns.unwrap_or("common".to_string());
-        self.0
-            .get(ns.as_str())
-            .and_then(|map| map.get(key).map(|v| v.to_string()))
+#[cfg(feature = "intern")]
+impl Clone for Interner {
+    fn clone(&self) -> Self {
+        Interner(Mutex::new(self.0.lock().unwrap().clone()))
     }
 }
 
-fn load_source(path: &Path) -> HashMap<String, Source> {
-    let mut map = HashMap::new();
-    if let Ok(dir) = fs::read_dir(path) {
-        for entry in dir.flatten() {
-            let path = entry.path();
-            if !path.is_dir() {
-                if let Some((file_name, file_type)) = path
-                    .file_name()
-                    .and_then(|f| f.to_str())
-                    .and_then(|f| f.rsplit_once('.'))
-                {
-                    if file_type.to_lowercase() == *"json" {
-                        if let Ok(source) = Source::from_path(&path) {
-                            map.insert(file_name.to_uppercase(), source);
-                        }
-                    }
-                }
-            }
+/// What a placeholder whose index is past the end of the values becomes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingArg {
+    /// Replaced by nothing: `"%1 and %2"` with one value gives `"a and "`.
+    #[default]
+    Empty,
+    /// Left as written: `"%1 and %2"` with one value gives `"a and %2"`.
+    Keep,
+}
+
+/// A lookup that fell back to the raw text, passed to the handler set by
+/// [`I18n::set_missing_key_handler`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingKey {
+    pub lang: String,
+    /// The namespace requested, `common` when none was.
+    pub namespace: Namespace,
+    pub key: String,
+    /// The closest existing keys of the namespace, best first, when
+    /// [`I18n::set_suggest_missing`] is on. Empty otherwise.
+    pub suggestions: Vec<(String, f32)>,
+}
+
+impl fmt::Display for MissingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key not found: `{}` in namespace `{}` ({})",
+            self.key, self.namespace, self.lang
+        )?;
+        if !self.suggestions.is_empty() {
+            let keys: Vec<String> = self
+                .suggestions
+                .iter()
+                .map(|(key, _)| format!("`{key}`"))
+                .collect();
+            write!(f, "; did you mean {}?", keys.join(", "))?;
         }
+        Ok(())
     }
-    map
 }
 
-#[macro_export]
-#[allow(clippy::crate_in_macro_def)]
-macro_rules! i18n {
-    ($key:expr, ns=$ns:expr, $($args:expr),+ $(,)?) => {
-        {
-            let i18n = I18N.lock().unwrap();
-            let text = i18n.translate($key, Some($ns.to_string()));
-            let mut vals = vec![];
-            $(vals.push($args.to_string());)+
-            i18n.trans_with_inter($key, vals, Some($ns.to_string()))
-        }
-    };
+type MissingKeyCallback = Arc<dyn Fn(&MissingKey) + Send + Sync>;
 
-    ($key:expr, ns=$ns:expr) => {
-        {
-            let i18n = I18N.lock().unwrap();
-            i18n.translate($key, Some($ns.to_string()))
+#[derive(Clone, Default)]
+pub(crate) struct MissingKeyHandler(Option<MissingKeyCallback>);
+
+impl fmt::Debug for MissingKeyHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Some(..)" } else { "None" })
+    }
+}
+
+type KeyTransformFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub(crate) struct KeyTransform(Option<KeyTransformFn>);
+
+impl fmt::Debug for KeyTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Some(..)" } else { "None" })
+    }
+}
+
+/// What [`I18n::export_missing`] found and wrote.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    /// The number of keys of the reference language missing in each other language, 0 for the
+    /// complete ones.
+    pub missing: BTreeMap<String, usize>,
+    /// The skeleton files written, one per incomplete language.
+    pub files: Vec<PathBuf>,
+}
+
+#[cfg(feature = "std")]
+impl ExportSummary {
+    /// The languages missing more than `max` keys, e.g. to fail a CI job.
+    pub fn behind(&self, max: usize) -> Vec<&str> {
+        self.missing
+            .iter()
+            .filter(|(_, missing)| **missing > max)
+            .map(|(lang, _)| lang.as_str())
+            .collect()
+    }
+}
+
+/// Mismatch between the placeholders of a template and the values given for it, returned by
+/// [`I18n::trans_with_inter_checked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpolationError {
+    /// Indices of placeholders without a value, sorted: `[2]` for `%2` with one value.
+    pub missing: Vec<usize>,
+    /// 1-based positions of values no placeholder uses, sorted.
+    pub unused: Vec<usize>,
+    /// Placeholders whose index is too large to stand for any value, as written, in order.
+    pub invalid: Vec<String>,
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |indices: &[usize]| {
+            let indices: Vec<String> = indices.iter().map(|i| format!("%{i}")).collect();
+            indices.join(", ")
+        };
+        let mut problems = vec![];
+        if !self.missing.is_empty() {
+            problems.push(format!("no value for {}", list(&self.missing)));
         }
-    };
+        if !self.invalid.is_empty() {
+            problems.push(format!("invalid index in {}", self.invalid.join(", ")));
+        }
+        if !self.unused.is_empty() {
+            problems.push(format!("no placeholder uses {}", list(&self.unused)));
+        }
+        f.write_str(&problems.join("; "))
+    }
+}
 
-    ($key:expr, $($args:expr),+) => {
-        {
-            let i18n = I18N.lock().unwrap();
-            let text = i18n.translate($key, None);
-            let mut vals = vec![];
-            $(vals.push($args.to_string());)+
-            i18n.trans_with_inter($key, vals, None)
+impl core::error::Error for InterpolationError {}
+
+/// Options applied when sources are loaded, and to keys at lookup time where relevant.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Trim keys and collapse every internal run of whitespace (including NBSP and full-width
+    /// spaces) into a single ASCII space. Applied identically when loading and when looking up,
+    /// so `"确认 删除 "` finds `"确认\u{a0}删除"`. Off by default.
+    pub normalize_whitespace: bool,
+}
+
+impl LoadOptions {
+    fn normalize<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        if self.normalize_whitespace {
+            collapse_whitespace(key)
+        } else {
+            Cow::Borrowed(key)
         }
-    };
+    }
+}
 
-    ($key:expr) => {
-        {
-            let i18n = I18N.lock().unwrap();
-            i18n.translate($key, None)
+/// A problem found while loading sources that didn't prevent the load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadWarning {
+    pub lang: String,
+    /// The file the source was read from, `None` when it was built in memory.
+    #[cfg(feature = "std")]
+    pub file: Option<PathBuf>,
+    pub namespace: Namespace,
+    pub kind: LoadWarningKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadWarningKind {
+    /// Several keys became the same key after normalization. `keys` lists the original keys in
+    /// sorted order; the value of the first one is kept under `normalized`.
+    NormalizedDuplicate {
+        normalized: String,
+        keys: Vec<String>,
+    },
+    /// `key` appeared more than once in the namespace; the last value was kept.
+    DuplicateKey { key: String },
+    /// `key` has the same value in every namespace of `namespaces` (sorted). This may be
+    /// intentional, so it is only reported as [`Severity::Info`]; `namespace` is the first one.
+    SameValueInNamespaces {
+        key: String,
+        namespaces: Vec<Namespace>,
+    },
+}
+
+/// How serious a [`LoadWarning`] or a [`LintWarning`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+}
+
+impl LoadWarningKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            LoadWarningKind::SameValueInNamespaces { .. } => Severity::Info,
+            _ => Severity::Warning,
         }
-    };
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The catalogs of a source set that is not active, see [`I18n::add_source_set`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SourceSet {
+    source: HashMap<String, Source>,
+    lazy: HashMap<String, LazySource>,
+}
 
-    #[test]
-    fn it_works() {
-        set_source(Path::new("./source"));
-        set_lang("en");
-        dbg!(i18n!("这是一个测试"));
-        dbg!(i18n!("这是一个测试", ns = "namespace1"));
+/// The name of the source set loaded by `set_source*`, active until another one is selected.
+pub const DEFAULT_SOURCE_SET: &str = "default";
+
+/// A language file whose `Source` is parsed on first use.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub(crate) struct LazySource {
+    path: PathBuf,
+    loader: Arc<dyn SourceLoader>,
+    source: OnceCell<Option<Source>>,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for LazySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazySource")
+            .field("path", &self.path)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl LazySource {
+    fn get(&self, options: &LoadOptions) -> Option<&Source> {
+        self.source
+            .get_or_init(|| {
+                let mut source = match loader::load_file(self.loader.as_ref(), &self.path, false) {
+                    Ok(source) => source,
+                    Err(_err) => {
+                        #[cfg(feature = "log")]
+                        log::warn!("skipped {}: {_err:#}", self.path.display());
+                        return None;
+                    }
+                };
+                source.normalize_keys(options);
+                Some(source)
+            })
+            .as_ref()
+    }
+}
+
+/// No file is read without `std`, so no language is loaded lazily.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone)]
+pub(crate) enum LazySource {}
+
+#[cfg(not(feature = "std"))]
+impl LazySource {
+    fn get(&self, _options: &LoadOptions) -> Option<&Source> {
+        match *self {}
+    }
+}
+
+impl I18n {
+    pub fn new(lang: &str) -> I18n {
+        I18n {
+            lang: lang.to_uppercase(),
+            source: HashMap::new(),
+            lazy: HashMap::new(),
+            source_sets: HashMap::new(),
+            active_set: None,
+            load_options: LoadOptions::default(),
+            load_warnings: vec![],
+            empty_as_missing: false,
+            common_fallback: false,
+            ns_fallback: vec![],
+            default_namespace: None,
+            base_lang: None,
+            join_separator: ", ".to_string(),
+            interpolation_enabled: true,
+            missing_arg: MissingArg::default(),
+            bidi_isolation: BidiIsolation::default(),
+            placeholder_style: PlaceholderStyle::default(),
+            placeholder_styles: HashMap::new(),
+            loaders: Loaders::default(),
+            #[cfg(feature = "std")]
+            source_path: None,
+            overrides: HashMap::new(),
+            missing_key_handler: MissingKeyHandler::default(),
+            key_transform: KeyTransform::default(),
+            debug_keys: false,
+            missing_decoration: None,
+            base_decoration: None,
+            pseudo: false,
+            pseudo_expansion: 1.3,
+            suggest_missing: false,
+            translate_cache: cache::TranslateCache::default(),
+            msg_keys: msg_handle::MsgKeys::default(),
+            initialized: false,
+            #[cfg(feature = "intern")]
+            interner: Interner::default(),
+            #[cfg(feature = "log")]
+            logged_misses: MissLog::default(),
+            #[cfg(feature = "metrics")]
+            metrics: metrics::LangCounters::for_lang(&lang.to_uppercase()),
+        }
+    }
+
+    /// Sets the options used by the next `set_source*` call and by key lookups.
+    pub fn set_load_options(&mut self, options: LoadOptions) {
+        self.load_options = options;
+        self.translate_cache.clear();
+    }
+
+    /// Treat empty or whitespace-only translations as missing, so the usual fallback kicks in
+    /// instead of showing a blank label. Off by default.
+    ///
+    /// A blank value doesn't stop the lookup: the next namespace of the fallback chain (parents,
+    /// [`I18n::set_ns_fallback`], `common`) is tried, and the raw text is returned only when every
+    /// candidate is missing or blank. A blank platform variant gives way to the entry's `value`,
+    /// and [`I18n::languages_with_key`] doesn't count blank values.
+    pub fn set_empty_as_missing(&mut self, on: bool) {
+        self.empty_as_missing = on;
+        self.translate_cache.clear();
+    }
+
+    /// Whether `val` counts as a translation under the current settings.
+    pub(crate) fn is_translated(&self, val: &str) -> bool {
+        !(self.empty_as_missing && val.trim().is_empty())
+    }
+
+    /// When a key is missing in the requested namespace, look it up in `common` before giving up.
+    /// Keys present in the namespace still win. Off by default.
+    pub fn set_common_fallback(&mut self, on: bool) {
+        self.common_fallback = on;
+        self.translate_cache.clear();
+    }
+
+    /// Separator used to join every value when a template contains `%0`. Defaults to `", "`.
+    pub fn set_join_separator(&mut self, sep: &str) {
+        self.join_separator = sep.to_string();
+    }
+
+    /// When disabled, interpolating calls return the translated template verbatim with its
+    /// placeholders intact, e.g. to produce translation review sheets. Enabled by default.
+    pub fn set_interpolation_enabled(&mut self, on: bool) {
+        self.interpolation_enabled = on;
+    }
+
+    /// How placeholders without a matching value are rendered. Defaults to [`MissingArg::Empty`].
+    pub fn set_missing_arg(&mut self, policy: MissingArg) {
+        self.missing_arg = policy;
+    }
+
+    /// Namespaces searched, in order, after the requested one and its parents.
+    /// The chain must not contain the same namespace twice. `common` may be placed anywhere in
+    /// it, e.g. `&["common", "shared"]` to prefer it over `shared`;
+    /// [`I18n::set_common_fallback`] only adds it at the end when the chain leaves it out.
+    pub fn set_ns_fallback(&mut self, chain: &[&str]) -> Result<()> {
+        for (i, ns) in chain.iter().enumerate() {
+            if chain[..i].contains(ns) {
+                bail!("[fallback error]: namespace '{ns}' appears twice in the fallback chain.");
+            }
+        }
+        self.ns_fallback = chain.iter().map(|ns| ns.to_string()).collect();
+        self.translate_cache.clear();
+        Ok(())
+    }
+
+    #[deprecated(note = "use `set_ns_fallback`")]
+    pub fn set_namespace_fallback_chain(&mut self, chain: &[&str]) -> Result<()> {
+        self.set_ns_fallback(chain)
+    }
+
+    /// The namespace of lookups made without one, instead of `common`. Keys are still looked up
+    /// in `common` last when [`I18n::set_common_fallback`] is on.
+    pub fn set_default_namespace(&mut self, ns: &str) {
+        self.default_namespace = Some(ns.to_string());
+        self.translate_cache.clear();
+    }
+
+    /// The language whose translation is used when the active one misses a key, e.g. the source
+    /// language whose catalog is always complete, so that users see its text rather than the raw
+    /// key. Only the raw key is returned when the base language misses it too. The miss of the
+    /// active language is still reported to the missing-key handler and counted by the metrics.
+    ///
+    /// Applies to the calls that fall back to the raw text (`translate`, the interpolating calls);
+    /// `translate_opt` and `has_translation` keep answering for the active language only.
+    pub fn set_base_lang(&mut self, lang: &str) {
+        self.base_lang = Some(lang.to_uppercase());
+        self.translate_cache.clear();
+    }
+
+    /// Removes the language set by [`I18n::set_base_lang`].
+    pub fn clear_base_lang(&mut self) {
+        self.base_lang = None;
+        self.translate_cache.clear();
+    }
+
+    /// The namespace a lookup in `ns` is made in: `ns`, else the one of the current
+    /// [`I18nContext`], else the one set by [`I18n::set_default_namespace`].
+    fn requested_namespace(&self, ns: Option<Namespace>) -> Option<Namespace> {
+        ns.or_else(context::namespace)
+            .or_else(|| self.default_namespace.clone())
+    }
+
+    /// The namespaces searched, in order, for a lookup in `ns`:
+    /// 1. `ns` itself (`common` when `None`);
+    /// 2. its parents when it contains dots, `checkout.mobile` → `checkout`;
+    /// 3. the chain set by [`I18n::set_ns_fallback`];
+    /// 4. `common`, when [`I18n::set_common_fallback`] is on.
+    pub(crate) fn namespaces(&self, ns: Option<Namespace>) -> Vec<Namespace> {
+        let mut namespaces = vec![];
+        let mut ns = ns.unwrap_or(DEFAULT_NS.to_string());
+        while let Some((parent, _)) = ns.rsplit_once('.') {
+            let parent = parent.to_string();
+            namespaces.push(ns);
+            ns = parent;
+        }
+        namespaces.push(ns);
+        namespaces.extend(self.ns_fallback.iter().cloned());
+        if self.common_fallback {
+            namespaces.push(DEFAULT_NS.to_string());
+        }
+        let mut seen = HashSet::new();
+        namespaces.retain(|ns| seen.insert(ns.clone()));
+        namespaces
+    }
+
+    /// The namespace that satisfies a lookup of `text` in `ns` once fallbacks are applied,
+    /// `None` when the text is not translated. Meant for debugging namespace resolution.
+    pub fn matched_namespace(&self, text: &str, ns: Option<Namespace>) -> Option<Namespace> {
+        self.lookup(text, ns).map(|found| found.namespace)
+    }
+
+    /// Finds the translation of `text`, along with the namespace that provided it.
+    pub(crate) fn lookup(&self, text: &str, ns: Option<Namespace>) -> Option<Found<'_>> {
+        self.lookup_in(&self.active_lang(), text, ns)
+    }
+
+    fn lookup_in(&self, lang: &str, text: &str, ns: Option<Namespace>) -> Option<Found<'_>> {
+        self.lookup_exact(lang, text, self.requested_namespace(ns))
+    }
+
+    /// Like [`I18n::lookup_in`], in `ns` as given: neither the context nor the default namespace
+    /// replace it.
+    pub(crate) fn lookup_exact(
+        &self,
+        lang: &str,
+        text: &str,
+        ns: Option<Namespace>,
+    ) -> Option<Found<'_>> {
+        let key = self.catalog_key(text);
+        let overrides = self.overrides.get(lang);
+        let source = self.lang_source(lang);
+        if overrides.is_none() && source.is_none() {
+            return None;
+        }
+        self.namespaces(ns).into_iter().find_map(|namespace| {
+            [overrides, source]
+                .into_iter()
+                .flatten()
+                .filter_map(|source| source.get_entry(&key, &namespace))
+                .find(|entry| self.is_translated(&entry.value))
+                .map(|entry| Found {
+                    entry,
+                    namespace,
+                    from_base: false,
+                })
+        })
+    }
+
+    /// The `(namespace, key)` pairs, sorted, whose translation in `lang` is exactly `translated`.
+    /// Platform variants are searched too. Meant for support tools: every entry is scanned.
+    pub fn reverse_lookup(&self, translated: &str, lang: &str) -> Vec<(Namespace, String)> {
+        self.reverse_matches(lang, |value| value == translated)
+    }
+
+    /// Like [`I18n::reverse_lookup`], for translations containing `fragment`.
+    pub fn reverse_lookup_contains(&self, fragment: &str, lang: &str) -> Vec<(Namespace, String)> {
+        self.reverse_matches(lang, |value| value.contains(fragment))
+    }
+
+    /// Like [`I18n::reverse_lookup`], except that the placeholders of a translation (`%1`,
+    /// `%{1:upper}`, `%{name}`, or `{0}` in the brace style) match any run of text, so
+    /// `"His grades are Chinese: 88"` is found under the template `"His grades are Chinese: %1"`.
+    pub fn reverse_lookup_fuzzy(&self, text: &str, lang: &str) -> Vec<(Namespace, String)> {
+        let style = self.placeholder_style(lang);
+        self.reverse_matches(lang, |value| {
+            let mut pattern = String::from("^");
+            let mut last = 0;
+            // an escaped delimiter matches the delimiter it stands for
+            let positional = style.regex().captures_iter(value).map(|caps| {
+                let escape = matches!(style.token(&caps), Token::Escape);
+                (caps.get(0).unwrap().range(), escape)
+            });
+            let named = NAMED_REG.find_iter(value).map(|m| (m.range(), false));
+            let mut placeholders = positional.chain(named).collect::<Vec<_>>();
+            placeholders.sort_by_key(|(range, _)| range.start);
+            for (range, escape) in placeholders {
+                if range.start < last {
+                    continue;
+                }
+                pattern.push_str(&regex::escape(&value[last..range.start]));
+                match escape {
+                    true => pattern.push_str(&regex::escape(&value[range.start + 1..range.end])),
+                    false => pattern.push_str("(?s:.*?)"),
+                }
+                last = range.end;
+            }
+            pattern.push_str(&regex::escape(&value[last..]));
+            pattern.push('$');
+            Regex::new(&pattern).is_ok_and(|re| re.is_match(text))
+        })
+    }
+
+    fn reverse_matches(
+        &self,
+        lang: &str,
+        matches: impl Fn(&str) -> bool,
+    ) -> Vec<(Namespace, String)> {
+        let Some(source) = self.lang_source(&lang.to_uppercase()) else {
+            return vec![];
+        };
+        let mut found: Vec<(Namespace, String)> = source
+            .namespaces
+            .iter()
+            .flat_map(|(ns, entries)| entries.iter().map(move |(key, entry)| (ns, key, entry)))
+            .filter(|(_, _, entry)| {
+                matches(&entry.value) || entry.platforms.values().any(|v| matches(v))
+            })
+            .map(|(ns, key, _)| (ns.clone(), key.clone()))
+            .collect();
+        found.sort();
+        found
+    }
+
+    /// The loaded languages, sorted, that contain `key` in `ns`.
+    pub fn languages_with_key(&self, key: &str, ns: Option<Namespace>) -> Vec<String> {
+        let key = self.catalog_key(key);
+        let ns = ns.unwrap_or(DEFAULT_NS.to_string());
+        let mut langs: Vec<String> = self
+            .languages()
+            .into_iter()
+            .filter(|lang| {
+                self.lang_source(lang)
+                    .and_then(|source| source.get(&key, &ns))
+                    .is_some_and(|val| self.is_translated(val))
+            })
+            .collect();
+        langs.sort();
+        langs
+    }
+
+    /// The value of `key` in every namespace of the active language that has it, overrides
+    /// included, e.g. to find out why a string reads differently in two screens. Only exact
+    /// entries are listed: no namespace fallback, base language or empty-value policy applies.
+    pub fn all_namespace_values(&self, key: &str) -> HashMap<Namespace, String> {
+        let key = self.catalog_key(key);
+        let lang = self.active_lang();
+        let mut values = HashMap::new();
+        let sources = [self.lang_source(&lang), self.overrides.get(lang.as_ref())];
+        for source in sources.into_iter().flatten() {
+            for ns in source.namespaces.keys() {
+                if let Some(val) = source.get(&key, ns) {
+                    values.insert(ns.clone(), val.to_string());
+                }
+            }
+        }
+        values
+    }
+
+    /// The number of languages loaded, lazily registered ones included.
+    pub fn lang_count(&self) -> usize {
+        self.source.len() + self.lazy.len()
+    }
+
+    /// The number of keys of `lang` across its namespaces, parsing it first if it was registered
+    /// lazily. Empty values are not counted when [`I18n::set_empty_as_missing`] is on, nor are
+    /// overrides, and 0 is returned for an unknown language.
+    pub fn key_count(&self, lang: &str) -> usize {
+        self.lang_source(&lang.to_uppercase()).map_or(0, |source| {
+            let entries = source.namespaces.values().flat_map(HashMap::values);
+            entries
+                .filter(|entry| self.is_translated(&entry.value))
+                .count()
+        })
+    }
+
+    /// [`I18n::key_count`] summed over every language, e.g. for a startup log line.
+    pub fn total_key_count(&self) -> usize {
+        self.languages()
+            .iter()
+            .map(|lang| self.key_count(lang))
+            .sum()
+    }
+
+    /// Every language known to this translator, loaded or registered lazily.
+    pub(crate) fn languages(&self) -> Vec<String> {
+        self.source
+            .keys()
+            .chain(self.lazy.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Writes every loaded language to `dir` as `<lang>.json`, creating the directory if needed.
+    ///
+    /// Namespaces and keys are written in sorted order and the output is pretty-printed, so
+    /// exporting unchanged data produces byte-identical files.
+    #[cfg(feature = "std")]
+    pub fn export_to_dir(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut langs = self.languages();
+        langs.sort();
+        for lang in langs {
+            let Some(source) = self.lang_source(&lang) else {
+                continue;
+            };
+            let path = dir.join(format!("{}.json", lang.to_lowercase()));
+            let mut json = serde_json::to_string_pretty(source)?;
+            json.push('\n');
+            fs::write(&path, json).with_context(|| format!("can't write {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Writes to `out_dir`, for every language but `reference_lang`, a `<lang>.json` file shaped
+    /// like a language file with only the keys the language is missing, each prefilled with the
+    /// entry of the reference language so translators have the text to translate. Blank values
+    /// count as missing when [`I18n::set_empty_as_missing`] is on. Complete languages get no
+    /// file. The output is sorted and pretty-printed like [`I18n::export_to_dir`].
+    #[cfg(feature = "std")]
+    pub fn export_missing(&self, reference_lang: &str, out_dir: &Path) -> Result<ExportSummary> {
+        let reference_lang = reference_lang.to_uppercase();
+        let Some(reference) = self.lang_source(&reference_lang) else {
+            bail!("[export error]: no catalog for the reference language {reference_lang}.");
+        };
+        let mut summary = ExportSummary::default();
+        let mut langs = self.languages();
+        langs.sort();
+        for lang in langs.into_iter().filter(|lang| *lang != reference_lang) {
+            let source = self.lang_source(&lang);
+            let mut skeleton = Source::default();
+            for (ns, entries) in &reference.namespaces {
+                for (key, entry) in entries {
+                    let translated = source
+                        .and_then(|source| source.get(key, ns))
+                        .is_some_and(|val| self.is_translated(val));
+                    if !translated && self.is_translated(&entry.value) {
+                        skeleton
+                            .namespaces
+                            .entry(ns.clone())
+                            .or_default()
+                            .insert(key.clone(), entry.clone());
+                    }
+                }
+            }
+            let missing = skeleton.namespaces.values().map(HashMap::len).sum();
+            if missing > 0 {
+                fs::create_dir_all(out_dir)?;
+                let path = out_dir.join(format!("{}.json", lang.to_lowercase()));
+                let mut json = serde_json::to_string_pretty(&skeleton)?;
+                json.push('\n');
+                fs::write(&path, json)
+                    .with_context(|| format!("can't write {}", path.display()))?;
+                summary.files.push(path);
+            }
+            summary.missing.insert(lang, missing);
+        }
+        Ok(summary)
+    }
+
+    /// Metadata of `lang` from the `_meta` section of its file.
+    pub fn meta(&self, lang: &str) -> Option<&LangMeta> {
+        self.lang_source(&lang.to_uppercase())
+            .and_then(|source| source.meta())
+    }
+
+    /// Warnings collected by the last `set_source` call, or by the last
+    /// [`I18n::add_source_set`] of the active set.
+    /// Languages loaded by [`I18n::set_source_lazy`] are normalized but not reported here.
+    pub fn load_warnings(&self) -> &[LoadWarning] {
+        &self.load_warnings
+    }
+
+    /// The language lookups use: the one of the current [`I18nContext`], or else the active one.
+    fn active_lang(&self) -> Cow<'_, str> {
+        match context::lang() {
+            Some(lang) => Cow::Owned(lang),
+            None => Cow::Borrowed(&self.lang),
+        }
+    }
+
+    /// The active language, uppercased as stored: `EN` after `set_lang("en")`. An entered
+    /// [`I18nContext`] doesn't change it.
+    pub fn current_lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// The directory given to the last `set_source*` call, if any.
+    #[cfg(feature = "std")]
+    pub fn source_path(&self) -> Option<&Path> {
+        self.source_path.as_deref()
+    }
+
+    pub fn set_lang(&mut self, lang: &str) {
+        self.lang = lang.to_uppercase();
+        self.translate_cache.clear();
+        #[cfg(feature = "intern")]
+        self.interner.clear();
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics = metrics::LangCounters::for_lang(&self.lang);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn set_source(&mut self, path: &Path) {
+        let (sources, warnings) = load_source(path, &self.load_options, &self.loaders);
+        self.replace_sources(Some(path), sources, warnings);
+    }
+
+    /// [`I18n::set_source`] from `path` and [`I18n::set_lang`] to `lang`, unless a previous call
+    /// did so already: for apps with several entry points, each initializing the translator
+    /// without knowing whether another one did, and which would otherwise parse the files again
+    /// and drop the overrides set since. Returns whether it loaded the sources.
+    ///
+    /// Only `init_once` marks the translator initialized, a plain `set_source` doesn't.
+    #[cfg(feature = "std")]
+    pub fn init_once(&mut self, path: &Path, lang: &str) -> bool {
+        if self.initialized {
+            return false;
+        }
+        self.set_source(path);
+        self.set_lang(lang);
+        self.initialized = true;
+        true
+    }
+
+    /// Whether [`I18n::init_once`] has loaded the sources.
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    /// [`I18n::set_source`] calling `progress(done, total)` each time one of the `total` language
+    /// files has been read, e.g. to drive a splash-screen progress bar. A file that can't be
+    /// parsed is skipped as usual, and still counted as done.
+    #[cfg(feature = "std")]
+    pub fn set_source_with_progress(
+        &mut self,
+        path: &Path,
+        mut progress: impl FnMut(usize, usize),
+    ) {
+        let (sources, warnings) =
+            load_source_with_progress(path, &self.load_options, &self.loaders, &mut progress);
+        self.replace_sources(Some(path), sources, warnings);
+    }
+
+    /// Installs the sources read from `path` (`None` when they were built in memory), dropping
+    /// everything derived from the previous ones.
+    #[cfg(feature = "std")]
+    fn replace_sources(
+        &mut self,
+        path: Option<&Path>,
+        sources: HashMap<String, Source>,
+        warnings: Vec<LoadWarning>,
+    ) {
+        self.source_path = path.map(Path::to_path_buf);
+        self.install_sources(sources, warnings);
+    }
+
+    /// Installs `sources`, dropping everything derived from the previous ones.
+    fn install_sources(&mut self, sources: HashMap<String, Source>, warnings: Vec<LoadWarning>) {
+        self.overrides.clear();
+        #[cfg(feature = "log")]
+        self.logged_misses.clear();
+        self.load_warnings = warnings;
+        self.replace_catalogs(sources);
+    }
+
+    /// Makes `sources` the catalogs of the active set, dropping the translations cached, interned
+    /// and compiled from the previous ones.
+    fn replace_catalogs(&mut self, sources: HashMap<String, Source>) {
+        self.translate_cache.clear();
+        #[cfg(feature = "intern")]
+        self.interner.clear();
+        self.lazy.clear();
+        self.source = sources;
+        self.compile_templates(None);
+    }
+
+    /// Replaces every catalog with the sources of `map`, by language, without reading any file,
+    /// e.g. for deterministic test fixtures or catalogs generated at runtime. Keys are normalized
+    /// as for a file, the warnings replace [`I18n::load_warnings`] and [`I18n::source_path`]
+    /// becomes `None`.
+    pub fn set_source_map(&mut self, map: HashMap<String, Source>) {
+        let mut warnings = vec![];
+        let sources = map
+            .into_iter()
+            .map(|(lang, mut source)| {
+                let lang = lang.to_uppercase();
+                warnings.extend(source.load_warnings(&lang, &self.load_options));
+                (lang, source)
+            })
+            .collect();
+        #[cfg(feature = "std")]
+        {
+            self.source_path = None;
+        }
+        self.install_sources(sources, warnings);
+    }
+
+    /// Strict variant of [`I18n::set_source`]: a directory that can't be read, or any file that
+    /// can't be read, doesn't match [`source_schema`] or repeats a key within a namespace, fails
+    /// the whole load with a message listing every violation, and the current sources are kept.
+    #[cfg(feature = "std")]
+    pub fn try_set_source(&mut self, path: &Path) -> Result<()> {
+        let (sources, warnings) = load_source_strict(path, &self.load_options, &self.loaders)?;
+        self.replace_sources(Some(path), sources, warnings);
+        Ok(())
+    }
+
+    /// Sets the catalog of `lang` from a source built in memory, replacing the one it had. Keys are
+    /// normalized as for a file and the warnings are added to [`I18n::load_warnings`].
+    pub fn insert_source(&mut self, lang: &str, mut source: Source) {
+        let lang = lang.to_uppercase();
+        let warnings = source.load_warnings(&lang, &self.load_options);
+        self.load_warnings.extend(warnings);
+        self.translate_cache.clear();
+        #[cfg(feature = "intern")]
+        self.interner.clear();
+        self.lazy.remove(&lang);
+        self.source.insert(lang.clone(), source);
+        self.compile_templates(Some(&lang));
+    }
+
+    /// Loads every file under `path`, subdirectories included, whose extension is one of
+    /// `extensions` (`json`, and `yaml`/`yml` with the `yaml` feature).
+    ///
+    /// Files at the top level give their language by their stem (`en.json`); files in a
+    /// subdirectory belong to the language named by their first path component, so
+    /// `fr/checkout/errors.json` is part of `FR`. Files of the same language are merged.
+    #[cfg(feature = "std")]
+    pub fn set_source_recursive(&mut self, path: &Path, extensions: &[&str]) {
+        let (sources, warnings) =
+            load_source_recursive(path, extensions, &self.load_options, &self.loaders);
+        self.replace_sources(Some(path), sources, warnings);
+    }
+
+    /// Loads the language files in `path` as the source set `name`, a parallel set of catalogs
+    /// like a formal and a casual tone of voice, to be switched to with
+    /// [`I18n::set_active_source_set`] without reading the files again. Loading the active set
+    /// replaces its catalogs, and `set_source*` calls always load into the active set, which is
+    /// [`DEFAULT_SOURCE_SET`] at first.
+    #[cfg(feature = "std")]
+    pub fn add_source_set(&mut self, name: &str, path: &Path) {
+        let (source, warnings) = load_source(path, &self.load_options, &self.loaders);
+        if name != self.active_source_set() {
+            let set = SourceSet {
+                source,
+                lazy: HashMap::new(),
+            };
+            self.source_sets.insert(name.to_string(), set);
+            return;
+        }
+        self.load_warnings = warnings;
+        self.replace_catalogs(source);
+    }
+
+    /// Makes the source set `name` the one translations are looked up in. Overrides and settings
+    /// are shared by every set. Fails, keeping the active set, when no set has that name.
+    pub fn set_active_source_set(&mut self, name: &str) -> Result<()> {
+        if name == self.active_source_set() {
+            return Ok(());
+        }
+        let Some(set) = self.source_sets.remove(name) else {
+            bail!("[source error]: no source set named '{name}'.");
+        };
+        let previous = SourceSet {
+            source: core::mem::replace(&mut self.source, set.source),
+            lazy: core::mem::replace(&mut self.lazy, set.lazy),
+        };
+        self.source_sets
+            .insert(self.active_source_set().to_string(), previous);
+        self.active_set = (name != DEFAULT_SOURCE_SET).then(|| name.to_string());
+        self.translate_cache.clear();
+        self.compile_templates(None);
+        #[cfg(feature = "intern")]
+        self.interner.clear();
+        Ok(())
+    }
+
+    /// The name of the active source set.
+    pub fn active_source_set(&self) -> &str {
+        self.active_set.as_deref().unwrap_or(DEFAULT_SOURCE_SET)
+    }
+
+    /// Adds a parser for the extensions of `loader`, used by every `set_source*` call after this
+    /// one. When several loaders handle an extension, the last registered wins, so the built-in
+    /// JSON loader can be replaced too.
+    pub fn register_loader(&mut self, loader: impl SourceLoader + 'static) {
+        self.loaders.register(Arc::new(loader));
+    }
+
+    /// Lazy variant of [`I18n::set_source`]: the language files in `path` are only recorded here,
+    /// each one is parsed the first time a translation in that language is requested and cached
+    /// afterwards. Startup gets faster and unused languages never take memory, at the cost of a
+    /// small latency on the first lookup.
+    #[cfg(feature = "std")]
+    pub fn set_source_lazy(&mut self, path: &Path) {
+        self.translate_cache.clear();
+        #[cfg(feature = "intern")]
+        self.interner.clear();
+        self.source.clear();
+        self.load_warnings.clear();
+        self.overrides.clear();
+        #[cfg(feature = "log")]
+        self.logged_misses.clear();
+        self.source_path = Some(path.to_path_buf());
+        self.lazy = source_files(path, &self.loaders)
+            .into_iter()
+            .filter_map(|(lang, path)| {
+                let lazy = LazySource {
+                    loader: self.loaders.for_path(&path)?.clone(),
+                    path,
+                    source: OnceCell::new(),
+                };
+                Some((lang, lazy))
+            })
+            .collect();
+    }
+
+    /// Layers translations read from the environment variable `var` over the loaded sources.
+    /// The variable holds a json object keyed by language, each value shaped like a language file:
+    /// `{"EN": {"common": {"这是一个测试": "This is a test"}}}`.
+    #[cfg(feature = "std")]
+    pub fn set_source_from_env(&mut self, var: &str) -> Result<()> {
+        let json = std::env::var(var)
+            .with_context(|| format!("[source error]: environment variable {var} is not set."))?;
+        let sources: HashMap<String, Source> = serde_json::from_str(&json)
+            .with_context(|| format!("[source error]: {var} is not a valid source."))?;
+        self.merge_source(sources);
+        Ok(())
+    }
+
+    /// Rewrites every key given to a lookup before it is searched, e.g. to strip the module path
+    /// prefixed in code: `settings::title` → `title`. Applies to `translate`, `translate_opt`,
+    /// `has_translation`, the interpolating calls and [`I18n::languages_with_key`] alike; when
+    /// nothing is found the original text is returned, not the rewritten one.
+    pub fn set_key_transform(
+        &mut self,
+        transform: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) {
+        self.key_transform = KeyTransform(Some(Arc::new(transform)));
+        self.translate_cache.clear();
+    }
+
+    /// Removes the transform set by [`I18n::set_key_transform`].
+    pub fn clear_key_transform(&mut self) {
+        self.key_transform = KeyTransform(None);
+        self.translate_cache.clear();
+    }
+
+    /// The key searched in the catalogs for `text`: the key transform, then [`LoadOptions::normalize`].
+    fn catalog_key<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match &self.key_transform.0 {
+            Some(transform) => {
+                Cow::Owned(self.load_options.normalize(&transform(text)).into_owned())
+            }
+            None => self.load_options.normalize(text),
+        }
+    }
+
+    /// Overrides the translation of `key` in namespace `ns` of `lang` without touching the loaded
+    /// files, e.g. for live copy edits or A/B tests. Overrides win over loaded translations, are
+    /// kept by `set_lang` and are all cleared by `set_source*`.
+    pub fn set_override(&mut self, lang: &str, ns: &str, key: &str, value: &str) {
+        let key = normalize_key(&self.load_options.normalize(key)).into_owned();
+        let lang = lang.to_uppercase();
+        let mut entry = Entry::from(value);
+        let style = self.stored_style(&lang);
+        if style != PlaceholderStyle::Percent {
+            entry.template = Template::compile(value, style);
+        }
+        self.translate_cache.clear();
+        self.overrides
+            .entry(lang)
+            .or_default()
+            .namespaces
+            .entry(ns.to_string())
+            .or_default()
+            .insert(key, entry);
+    }
+
+    /// Removes the override of `key` in `ns` of `lang`. Returns whether there was one.
+    pub fn clear_override(&mut self, lang: &str, ns: &str, key: &str) -> bool {
+        let key = normalize_key(&self.load_options.normalize(key)).into_owned();
+        self.translate_cache.clear();
+        self.overrides
+            .get_mut(&lang.to_uppercase())
+            .and_then(|source| source.namespaces.get_mut(ns))
+            .and_then(|entries| entries.remove(&key))
+            .is_some()
+    }
+
+    /// Removes every override.
+    pub fn clear_all_overrides(&mut self) {
+        self.overrides.clear();
+        self.translate_cache.clear();
+    }
+
+    /// Merges a file organized by namespace rather than by language into namespace `ns` of every
+    /// language it mentions. The file maps languages to entries:
+    /// ```json
+    /// {
+    ///   "EN": { "未找到": "Not found" },
+    ///   "FR": { "未找到": "Introuvable" }
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn add_namespace_file(&mut self, ns: &str, path: &Path) -> Result<()> {
+        let file = File::open(path)
+            .with_context(|| format!("[source error]: can't open {}.", path.display()))?;
+        let langs: HashMap<String, HashMap<String, Entry>> =
+            serde_json::from_reader(BufReader::new(file)).with_context(|| {
+                format!(
+                    "[source error]: {} is not a namespace file.",
+                    path.display()
+                )
+            })?;
+        let sources = langs
+            .into_iter()
+            .map(|(lang, entries)| {
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, val)| (normalize_key(&key).into_owned(), val))
+                    .collect();
+                let source = Source {
+                    namespaces: HashMap::from([(ns.to_string(), entries)]),
+                    ..Default::default()
+                };
+                (lang, source)
+            })
+            .collect();
+        self.merge_source(sources);
+        Ok(())
+    }
+
+    /// Merges `sources` (keyed by language) into the loaded ones; entries of `sources` win.
+    pub fn merge_source(&mut self, sources: HashMap<String, Source>) {
+        self.translate_cache.clear();
+        for (lang, mut source) in sources {
+            let lang = lang.to_uppercase();
+            source.normalize_keys(&self.load_options);
+            if !self.source.contains_key(&lang) {
+                if let Some(lazy) = self.lazy.remove(&lang) {
+                    let loaded = lazy.get(&self.load_options).cloned().unwrap_or_default();
+                    self.source.insert(lang.clone(), loaded);
+                }
+            }
+            self.source.entry(lang.clone()).or_default().merge(source);
+            self.compile_templates(Some(&lang));
+        }
+    }
+
+    /// Returns the source of `lang`, parsing it first if it was registered lazily.
+    pub(crate) fn lang_source(&self, lang: &str) -> Option<&Source> {
+        self.source.get(lang).or_else(|| {
+            self.lazy
+                .get(lang)
+                .and_then(|lazy| lazy.get(&self.load_options))
+        })
+    }
+
+    pub fn translate(&self, text: &str, ns: Option<Namespace>) -> String {
+        if self.debug_keys
+            || self.pseudo
+            || self.missing_decoration.is_some()
+            || self.base_decoration.is_some()
+        {
+            return render(0, |out| self.translate_into(out, text, ns));
+        }
+        #[cfg(feature = "std")]
+        if self.translate_cache.enabled {
+            return self.translate_cached(text, ns);
+        }
+        self.resolve(text, ns)
+            .map(|found| found.entry.value.clone())
+            .unwrap_or(text.to_string())
+    }
+
+    /// [`I18n::translate`] of each of `keys` in namespace `ns`, in the order of `keys`, for
+    /// rendering many labels at once, e.g. the cells of a table. A key without a translation
+    /// gives itself, as for a single lookup.
+    pub fn translate_many<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a str>,
+        ns: Option<&str>,
+    ) -> Vec<String> {
+        let ns = ns.map(str::to_string);
+        keys.into_iter()
+            .map(|key| self.translate(key, ns.clone()))
+            .collect()
+    }
+
+    /// [`I18n::translate_many`] keyed by the keys, each translated once.
+    pub fn translate_many_map<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a str>,
+        ns: Option<&str>,
+    ) -> HashMap<&'a str, String> {
+        let ns = ns.map(str::to_string);
+        let mut translations = HashMap::new();
+        for key in keys {
+            translations
+                .entry(key)
+                .or_insert_with(|| self.translate(key, ns.clone()));
+        }
+        translations
+    }
+
+    /// Serves the plain `translate` calls from a cache keyed by language, namespace and key,
+    /// filled on the first lookup of each, so hot labels skip the catalog and fallback search.
+    /// Only translations found are cached: misses still reach the missing-key handler. The
+    /// cache is emptied by `set_lang`, `set_source*` and every setting that changes lookups.
+    /// Off by default, and only available with `std`.
+    #[cfg(feature = "std")]
+    pub fn set_translate_cache(&mut self, on: bool) {
+        self.translate_cache.enabled = on;
+        self.translate_cache.clear();
+    }
+
+    #[cfg(feature = "std")]
+    fn translate_cached(&self, text: &str, ns: Option<Namespace>) -> String {
+        let ns = self.requested_namespace(ns);
+        let lang = self.active_lang();
+        if let Some(value) = self.translate_cache.get(&lang, ns.as_deref(), text) {
+            #[cfg(feature = "metrics")]
+            self.record_lookup(true);
+            return value;
+        }
+        match self.resolve(text, ns.clone()) {
+            // a value of the base language stands in for a miss, which must keep being reported
+            Some(found) if found.from_base => found.entry.value.clone(),
+            Some(found) => {
+                let value = found.entry.value.clone();
+                self.translate_cache
+                    .insert(&lang, ns.as_deref(), text, &value);
+                value
+            }
+            None => text.to_string(),
+        }
+    }
+
+    /// QA aid: prefix the output of every translating call with the namespace and key that
+    /// produced it, as in `«common:保存» Save`. Placeholders are still filled, after the
+    /// annotation. The namespace is the one that provided the translation, the requested one for
+    /// a miss. Off by default.
+    pub fn set_debug_keys(&mut self, on: bool) {
+        self.debug_keys = on;
+    }
+
+    /// QA aid: write `before` and `after` around every key a translating call shows untranslated,
+    /// as in `⟦这是一个测试⟧`, so that untranslated text stands out in screenshots and reviews.
+    /// `{lang}` in either stands for the active language: `("[{lang}] ", "")`. With
+    /// interpolation, the decoration surrounds the filled key. Off by default.
+    ///
+    /// A key found only in the base language is not decorated, see
+    /// [`I18n::set_base_decoration`], and pseudo-localization replaces both decorations.
+    pub fn set_missing_decoration(&mut self, decoration: Option<(&str, &str)>) {
+        self.missing_decoration = decoration.map(|(b, a)| (b.to_string(), a.to_string()));
+    }
+
+    /// Like [`I18n::set_missing_decoration`], for translations taken from the base language
+    /// after a miss of the active one, see [`I18n::set_base_lang`]. Off by default.
+    pub fn set_base_decoration(&mut self, decoration: Option<(&str, &str)>) {
+        self.base_decoration = decoration.map(|(b, a)| (b.to_string(), a.to_string()));
+    }
+
+    /// Writes what `body` writes with the decoration of the lookup that found `found` around it.
+    fn decorate<W: fmt::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        found: Option<&Found>,
+        body: impl FnOnce(&mut W) -> fmt::Result,
+    ) -> fmt::Result {
+        let decoration = match found {
+            _ if self.pseudo => None,
+            None => self.missing_decoration.as_ref(),
+            Some(found) if found.from_base => self.base_decoration.as_ref(),
+            Some(_) => None,
+        };
+        let Some((before, after)) = decoration else {
+            return body(out);
+        };
+        let lang = self.active_lang();
+        out.write_str(&before.replace("{lang}", &lang))?;
+        body(out)?;
+        out.write_str(&after.replace("{lang}", &lang))
+    }
+
+    /// QA aid: pseudo-localize everything the translating calls return, translations and misses
+    /// alike: `Save %1` comes back as `⟦Şȧṽḗ %1~~⟧`. Placeholders keep working and the values
+    /// filled in are left as they are. Off by default.
+    pub fn set_pseudo(&mut self, on: bool) {
+        self.pseudo = on;
+    }
+
+    /// How much longer than the original pseudo-localized text gets, `1.3` by default, i.e.
+    /// padded by 30%.
+    pub fn set_pseudo_expansion(&mut self, factor: f32) {
+        self.pseudo_expansion = factor;
+    }
+
+    /// `text` pseudo-localized when [`I18n::set_pseudo`] is on.
+    fn pseudo<'t>(&self, text: &'t str, style: PlaceholderStyle) -> Cow<'t, str> {
+        match self.pseudo {
+            true => Cow::Owned(pseudo::pseudo(text, style, self.pseudo_expansion)),
+            false => Cow::Borrowed(text),
+        }
+    }
+
+    /// Writes the `«ns:key» ` annotation of [`I18n::set_debug_keys`] when it is on.
+    fn annotate<W: fmt::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        text: &str,
+        ns: Option<&str>,
+        found: Option<&Found>,
+    ) -> fmt::Result {
+        if !self.debug_keys {
+            return Ok(());
+        }
+        let default = self.requested_namespace(None);
+        let namespace = match found {
+            Some(found) => found.namespace.as_str(),
+            None => ns.or(default.as_deref()).unwrap_or(DEFAULT_NS),
+        };
+        write!(out, "«{namespace}:{text}» ")
+    }
+
+    /// [`I18n::lookup`] for calls that fall back to the raw text: a miss is reported to the
+    /// missing-key handler, and the lookup is counted with the `metrics` feature.
+    /// A miss is then looked up in the base language, if any.
+    pub(crate) fn resolve(&self, text: &str, ns: Option<Namespace>) -> Option<Found<'_>> {
+        if self.missing_key_handler.0.is_none()
+            && self.base_lang.is_none()
+            && !cfg!(feature = "log")
+            && !cfg!(feature = "tracing")
+            && !cfg!(feature = "metrics")
+        {
+            return self.lookup(text, ns);
+        }
+        let ns = self.requested_namespace(ns);
+        let lang = self.active_lang();
+        let found = self.lookup_in(&lang, text, ns.clone());
+        #[cfg(feature = "metrics")]
+        self.record_lookup(found.is_some());
+        if found.is_some() {
+            return found;
+        }
+        self.report_missing(text, ns.clone());
+        let base = self.base_lang.as_deref().filter(|base| *base != lang)?;
+        self.lookup_in(base, text, ns).map(|found| Found {
+            from_base: true,
+            ..found
+        })
+    }
+
+    /// Counts a lookup against the language it was made in.
+    #[cfg(feature = "metrics")]
+    fn record_lookup(&self, hit: bool) {
+        if !context::record(hit) {
+            self.metrics.record(hit);
+        }
+    }
+
+    fn report_missing(&self, text: &str, ns: Option<Namespace>) {
+        let namespace = ns.unwrap_or(DEFAULT_NS.to_string());
+        let lang = self.active_lang();
+        #[cfg(feature = "log")]
+        match self.is_unknown_namespace(&lang, &namespace) {
+            true => self.logged_misses.warn_namespace_once(&lang, &namespace),
+            false => self.logged_misses.warn_once(&lang, &namespace, text),
+        }
+        #[cfg(feature = "tracing")]
+        handle::Miss {
+            lang: lang.to_string(),
+            namespace: namespace.clone(),
+            key: text.to_string(),
+        }
+        .report();
+        if let Some(handler) = &self.missing_key_handler.0 {
+            let suggestions = match self.suggest_missing {
+                true => self.suggest(text, Some(namespace.clone()), &lang, 3),
+                false => vec![],
+            };
+            handler(&MissingKey {
+                lang: lang.into_owned(),
+                namespace,
+                key: text.to_string(),
+                suggestions,
+            });
+        }
+    }
+
+    /// Whether `lang` is loaded but has no namespace `ns`, neither in its catalog nor in its
+    /// overrides: a misspelled namespace rather than a missing key.
+    #[cfg(feature = "log")]
+    fn is_unknown_namespace(&self, lang: &str, ns: &str) -> bool {
+        let Some(source) = self.lang_source(lang) else {
+            return false;
+        };
+        !source.has_namespace(ns)
+            && !self
+                .overrides
+                .get(lang)
+                .is_some_and(|overrides| overrides.has_namespace(ns))
+    }
+
+    /// Calls `handler` every time a translating call falls back to the raw text because the key
+    /// is missing. The handler runs while the translator is borrowed, so when it is the global
+    /// one the handler must not translate through it.
+    pub fn set_missing_key_handler(
+        &mut self,
+        handler: impl Fn(&MissingKey) + Send + Sync + 'static,
+    ) {
+        self.missing_key_handler = MissingKeyHandler(Some(Arc::new(handler)));
+    }
+
+    /// Removes the handler set by [`I18n::set_missing_key_handler`].
+    pub fn clear_missing_key_handler(&mut self) {
+        self.missing_key_handler = MissingKeyHandler(None);
+    }
+
+    /// Development aid: with a missing-key handler set, look for the keys closest to a missing
+    /// one (see [`I18n::suggest`]) and pass them in [`MissingKey::suggestions`]. This scans the
+    /// whole namespace on every miss, leave it off in release builds. Off by default.
+    pub fn set_suggest_missing(&mut self, on: bool) {
+        self.suggest_missing = on;
+    }
+
+    /// Returns the translation of `text`, or `None` when the active language has no entry for it
+    /// instead of falling back to the raw text.
+    pub fn translate_opt(&self, text: &str, ns: Option<Namespace>) -> Option<String> {
+        let found = self.lookup(text, ns)?;
+        let style = self.template_style(Some(&found));
+        Some(self.pseudo(&found.entry.value, style).into_owned())
+    }
+
+    /// Returns the translation of `text`, or `default` when neither the namespace, its fallback
+    /// namespaces nor the base language have one. As for a miss of [`I18n::translate`], the
+    /// missing-key handler is called.
+    pub fn translate_with_default(
+        &self,
+        text: &str,
+        default: &str,
+        ns: Option<Namespace>,
+    ) -> String {
+        match self.resolve(text, ns) {
+            Some(found) => {
+                let style = self.template_style(Some(&found));
+                self.pseudo(&found.entry.value, style).into_owned()
+            }
+            None => default.to_string(),
+        }
+    }
+
+    /// Whether the active language really translates `text`.
+    pub fn has_translation(&self, text: &str, ns: Option<Namespace>) -> bool {
+        self.lookup(text, ns).is_some()
+    }
+
+    /// Like [`I18n::translate`], but identical results share one allocation from an interner pool.
+    /// The pool is emptied by `set_lang` and `set_source*`, so no stale string is ever served.
+    #[cfg(feature = "intern")]
+    pub fn translate_interned(&self, text: &str, ns: Option<Namespace>) -> Arc<str> {
+        self.interner.intern(self.translate(text, ns))
+    }
+
+    /// Writes the translation of `text` into `out` without allocating.
+    pub fn translate_into(
+        &self,
+        out: &mut impl fmt::Write,
+        text: &str,
+        ns: Option<Namespace>,
+    ) -> fmt::Result {
+        let requested = self.debug_keys.then(|| ns.clone()).flatten();
+        let found = self.resolve(text, ns);
+        self.annotate(out, text, requested.as_deref(), found.as_ref())?;
+        let translation = found.as_ref().map_or(text, |found| &found.entry.value);
+        let style = self.template_style(found.as_ref());
+        self.decorate(out, found.as_ref(), |out| {
+            out.write_str(&self.pseudo(translation, style))
+        })
+    }
+
+    /// Writes the translation of `text` into `out` with its placeholders filled from `vals`.
+    /// Values are formatted straight into the sink, no intermediate `String` is built.
+    pub fn trans_with_inter_into(
+        &self,
+        out: &mut impl fmt::Write,
+        text: &str,
+        vals: &[&dyn Display],
+        ns: Option<Namespace>,
+    ) -> fmt::Result {
+        let requested = self.debug_keys.then(|| ns.clone()).flatten();
+        let found = self.resolve(text, ns);
+        self.annotate(out, text, requested.as_deref(), found.as_ref())?;
+        let fill = Fill {
+            style: self.template_style(found.as_ref()),
+            ..Fill::default()
+        };
+        self.decorate(out, found.as_ref(), |out| match &found {
+            Some(found) => self.interpolate_entry_into(out, found.entry, vals, fill),
+            None => self.interpolate_into(out, text, vals, fill),
+        })
+    }
+
+    pub fn trans_with_inter(&self, text: &str, vals: Vec<String>, ns: Option<Namespace>) -> String {
+        self.interpolate_translation(text, &vals, ns, Fill::default(), None)
+    }
+
+    /// Like [`I18n::trans_with_inter`] for HTML output: the values are escaped (`& < > " '`), the
+    /// translated template is not, so markup written by translators is kept. Each value is
+    /// escaped once however many placeholders use it. To escape only some values, use the `html`
+    /// modifier in the template instead: `%{1:html}`.
+    pub fn trans_with_inter_html(
+        &self,
+        text: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        self.interpolate_translation(text, &vals, ns, Fill::HTML, None)
+    }
+
+    /// Like [`I18n::trans_with_inter`] with values that may be absent: a `None` is a value known
+    /// not to exist, see [`Opt`]. Its placeholder follows [`I18n::set_missing_arg`] whatever the
+    /// length of `vals`, it is left out of `%0`, and the conditional sections depending on it,
+    /// `%{2?（%2）}`, are dropped.
+    pub fn trans_with_inter_opt(
+        &self,
+        text: &str,
+        vals: Vec<Option<String>>,
+        ns: Option<Namespace>,
+    ) -> String {
+        let vals: Vec<Opt<String>> = vals.into_iter().map(Opt).collect();
+        let vals: Vec<&dyn Display> = vals.iter().map(|v| v as &dyn Display).collect();
+        render(0, |out| self.trans_with_inter_into(out, text, &vals, ns))
+    }
+
+    /// Translates `text` in the disambiguation context `ctx`, gettext's `msgctxt`, for a short
+    /// key with several meanings on the same screen: "Post" the verb and "Post" the noun. The
+    /// contextual translation is stored under the compound key [`context_key`]`(text, ctx)`, in
+    /// json `"Post\u0004verb"`, next to the plain one, and the translation of the plain `text` is
+    /// used when the active language has none for `ctx`.
+    pub fn translate_ctx(&self, text: &str, ctx: &str, ns: Option<Namespace>) -> String {
+        let key = context_key(text, ctx);
+        match self.lookup(&key, ns.clone()) {
+            Some(_) => self.translate(&key, ns),
+            None => self.translate(text, ns),
+        }
+    }
+
+    /// [`I18n::translate_ctx`] with the placeholders filled from `vals`, see
+    /// [`I18n::trans_with_inter`].
+    pub fn trans_with_inter_ctx(
+        &self,
+        text: &str,
+        ctx: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        let key = context_key(text, ctx);
+        match (self.lookup(&key, ns.clone()), vals.is_empty()) {
+            (Some(_), true) => self.translate(&key, ns),
+            (Some(_), false) => self.trans_with_inter(&key, vals, ns),
+            (None, true) => self.translate(text, ns),
+            (None, false) => self.trans_with_inter(text, vals, ns),
+        }
+    }
+
+    /// Translates and interpolates `text`, with the variant for the plural `category` of an entry
+    /// that has plural variants.
+    fn interpolate_translation(
+        &self,
+        text: &str,
+        vals: &[String],
+        ns: Option<Namespace>,
+        fill: Fill,
+        category: Option<&str>,
+    ) -> String {
+        let requested = self.debug_keys.then(|| ns.clone()).flatten();
+        let found = self.resolve(text, ns);
+        let variant = found
+            .as_ref()
+            .filter(|found| !found.entry.plurals.is_empty())
+            .zip(category)
+            .map(|(found, category)| found.entry.plural(category));
+        let template = variant.unwrap_or(found.as_ref().map_or(text, |found| &found.entry.value));
+        let len = template.len() + vals.iter().map(String::len).sum::<usize>();
+        let fill = Fill {
+            style: self.template_style(found.as_ref()),
+            ..fill
+        };
+        render(len, |out| {
+            self.annotate(out, text, requested.as_deref(), found.as_ref())?;
+            self.decorate(out, found.as_ref(), |out| match (&found, variant) {
+                (_, Some(variant)) => self.interpolate_into(out, variant, vals, fill),
+                (Some(found), None) => self.interpolate_entry_into(out, found.entry, vals, fill),
+                (None, None) => self.interpolate_into(out, text, vals, fill),
+            })
+        })
+    }
+
+    /// Translates `text` for `count` items: `count` is `%1` and, being the first value, drives the
+    /// inline plurals of the template (`{message|messages}`); `vals` fill `%2`, `%3`, ...
+    ///
+    /// An entry written with plural categories, `{"one": "%1 message", "other": "%1 messages"}`,
+    /// gives its variant for [`plural_category`]`(count)`, else its `other` one.
+    pub fn trans_plural(
+        &self,
+        text: &str,
+        count: impl Display,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        let mut all = Vec::with_capacity(vals.len() + 1);
+        all.push(count.to_string());
+        all.extend(vals);
+        let category = plural_category(&all[0]);
+        self.interpolate_translation(text, &all, ns, Fill::default(), Some(category))
+    }
+
+    /// Like [`I18n::trans_with_inter`], with `defaults[i - 1]` standing in for `%i` when `vals`
+    /// has no value for it. Only indices past `defaults` follow [`I18n::set_missing_arg`], and
+    /// `%0` joins the values and the defaults used.
+    pub fn trans_with_inter_defaults(
+        &self,
+        text: &str,
+        mut vals: Vec<String>,
+        defaults: &[&str],
+        ns: Option<Namespace>,
+    ) -> String {
+        let given = vals.len();
+        vals.extend(defaults.iter().skip(given).map(|d| d.to_string()));
+        self.trans_with_inter(text, vals, ns)
+    }
+
+    /// Like [`I18n::trans_with_inter`], but fails when a placeholder of the translated template
+    /// has no value or when a value is used by no placeholder (`%0` uses them all).
+    pub fn trans_with_inter_checked(
+        &self,
+        text: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> Result<String, InterpolationError> {
+        let requested = self.debug_keys.then(|| ns.clone()).flatten();
+        let found = self.resolve(text, ns);
+        let template = found.as_ref().map_or(text, |found| &found.entry.value);
+        let fill = Fill {
+            style: self.template_style(found.as_ref()),
+            ..Fill::default()
+        };
+        let mut used = vec![false; vals.len()];
+        let mut missing = vec![];
+        let mut invalid = vec![];
+        for caps in fill.style.regex().captures_iter(template) {
+            match fill.style.token(&caps) {
+                Token::Escape => {}
+                Token::Placeholder(Some(0)) | Token::Rest => {
+                    used.iter_mut().for_each(|u| *u = true)
+                }
+                Token::Placeholder(Some(i)) if i <= vals.len() => used[i - 1] = true,
+                Token::Placeholder(Some(i)) => missing.push(i),
+                Token::Placeholder(None) => invalid.push(caps[0].to_string()),
+            }
+        }
+        missing.sort_unstable();
+        missing.dedup();
+        let unused: Vec<usize> = (1..=vals.len()).filter(|i| !used[i - 1]).collect();
+        if !missing.is_empty() || !unused.is_empty() || !invalid.is_empty() {
+            return Err(InterpolationError {
+                missing,
+                unused,
+                invalid,
+            });
+        }
+        Ok(render(0, |out| {
+            self.annotate(out, text, requested.as_deref(), found.as_ref())?;
+            self.decorate(out, found.as_ref(), |out| {
+                self.interpolate_into(out, template, &vals, fill)
+            })
+        }))
+    }
+
+    /// The translation of `text` with every placeholder shown as its index in brackets, `%1` as
+    /// `[1]` and `%{2:upper}` as `[2:upper]`, for translator tools showing the structure of a
+    /// template without real values. Indices are written as in the template, so `{0}` of the
+    /// brace style is `[0]`. Escaped delimiters are unescaped, and the syntax of inline plurals
+    /// and conditional sections is kept.
+    pub fn preview(&self, text: &str, ns: Option<Namespace>) -> String {
+        let found = self.resolve(text, ns);
+        let style = self.template_style(found.as_ref());
+        let template = found.as_ref().map_or(text, |found| &found.entry.value);
+        let preview = style
+            .regex()
+            .replace_all(template, |caps: &regex::Captures| {
+                let whole = &caps[0];
+                match style.token(caps) {
+                    Token::Escape => whole[1..].to_string(),
+                    Token::Rest => "[*]".to_string(),
+                    Token::Placeholder(_) => {
+                        let index = caps.get(1).or_else(|| caps.get(3)).unwrap().as_str();
+                        match caps.get(2) {
+                            Some(modifier) => format!("[{index}:{}]", modifier.as_str()),
+                            None => format!("[{index}]"),
+                        }
+                    }
+                }
+            });
+        preview.into_owned()
+    }
+
+    /// Translates `text` and fills its named placeholders, `%{name}` or `%{name:upper}`, from
+    /// `vals`. Entries of `vals` the template doesn't use are ignored, placeholders without an
+    /// entry follow [`I18n::set_missing_arg`], and positional placeholders (`%1`, `%{1}`) are left
+    /// untouched.
+    pub fn trans_with_map(
+        &self,
+        text: &str,
+        vals: &HashMap<&str, String>,
+        ns: Option<&str>,
+    ) -> String {
+        self.fill_named(&NAMED_REG, text, vals, ns)
+    }
+
+    /// [`I18n::trans_with_map`] also filling bare keyword placeholders, `%chinese`, as the
+    /// `name = value` form of `i18n!` does. Names are ASCII identifiers, so a placeholder may be
+    /// followed by Chinese text directly: `%math分`.
+    pub fn trans_with_named(
+        &self,
+        text: &str,
+        vals: &HashMap<&str, String>,
+        ns: Option<&str>,
+    ) -> String {
+        self.fill_named(&KEYWORD_REG, text, vals, ns)
+    }
+
+    /// Translates `text` and fills the named placeholders matched by `reg`, whose name is its
+    /// first or third group and modifier its second.
+    fn fill_named(
+        &self,
+        reg: &Regex,
+        text: &str,
+        vals: &HashMap<&str, String>,
+        ns: Option<&str>,
+    ) -> String {
+        let found = self.resolve(text, ns.map(|ns| ns.to_string()));
+        let template = found.as_ref().map_or(text, |found| &found.entry.value);
+        let template = self.pseudo(template, self.template_style(found.as_ref()));
+        let template = template.as_ref();
+        render(template.len(), |out| {
+            self.annotate(out, text, ns, found.as_ref())?;
+            self.decorate(out, found.as_ref(), |out| {
+                if !self.interpolation_enabled {
+                    out.push_str(template);
+                    return Ok(());
+                }
+                let mut last = 0;
+                for caps in reg.captures_iter(template) {
+                    let whole = caps.get(0).unwrap();
+                    out.push_str(&template[last..whole.start()]);
+                    last = whole.end();
+                    let name = caps.get(1).or_else(|| caps.get(3)).unwrap().as_str();
+                    match (vals.get(name), caps.get(2)) {
+                        (Some(v), None) => out.push_str(v),
+                        (Some(v), Some(modifier)) => {
+                            out.push_str(&case::apply(modifier.as_str(), v, &self.active_lang()))
+                        }
+                        (None, _) => self.write_missing(out, whole.as_str())?,
+                    }
+                }
+                out.push_str(&template[last..]);
+                Ok(())
+            })
+        })
+    }
+
+    /// Translates `text` using its variant for `platform` (`web`, `mobile`, `cli`, ...) when the
+    /// catalog has one, its base value otherwise, then fills the placeholders with `vals`.
+    pub fn trans_platform(
+        &self,
+        text: &str,
+        platform: &str,
+        vals: Vec<String>,
+        ns: Option<Namespace>,
+    ) -> String {
+        let requested = self.debug_keys.then(|| ns.clone()).flatten();
+        let found = self.resolve(text, ns);
+        let new_text = found
+            .as_ref()
+            .map(|found| {
+                let variant = found.entry.for_platform(platform);
+                match self.is_translated(variant) {
+                    true => variant,
+                    false => &found.entry.value,
+                }
+            })
+            .unwrap_or(text);
+        let fill = Fill {
+            style: self.template_style(found.as_ref()),
+            ..Fill::default()
+        };
+        render(new_text.len(), |out| {
+            self.annotate(out, text, requested.as_deref(), found.as_ref())?;
+            self.decorate(out, found.as_ref(), |out| {
+                self.interpolate_into(out, new_text, &vals, fill)
+            })
+        })
+    }
+
+    /// Writes `template` into `out` with its placeholders filled from `vals`, one match at a
+    /// time so that nothing but case-modified values is allocated.
+    fn interpolate_into<W, V>(
+        &self,
+        out: &mut W,
+        template: &str,
+        vals: &[V],
+        fill: Fill,
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        V: Display,
+    {
+        let template = self.pseudo(template, fill.style);
+        if !self.interpolation_enabled {
+            return out.write_str(&template);
+        }
+        let template = select_sections(&template, vals, fill);
+        let template = select_plurals(&template, vals);
+        let template = template.as_ref();
+        let consumed = template
+            .contains('*')
+            .then(|| consumed(template, vals.len(), fill.style));
+        let mut last = 0;
+        for caps in fill.style.regex().captures_iter(template) {
+            let whole = caps.get(0).unwrap();
+            out.write_str(&template[last..whole.start()])?;
+            last = whole.end();
+            match fill.style.token(&caps) {
+                Token::Escape => write_escape(out, whole.as_str(), vals, fill)?,
+                Token::Rest => {
+                    let consumed = consumed.as_deref().unwrap_or_default();
+                    self.write_rest(out, whole.as_str(), consumed, vals, fill)?
+                }
+                Token::Placeholder(index) => {
+                    let modifier = caps.get(2).map(|m| m.as_str());
+                    self.write_placeholder(out, index, modifier, whole.as_str(), vals, fill)?
+                }
+            }
+        }
+        out.write_str(&template[last..])
+    }
+
+    /// [`I18n::interpolate_into`] for the value of `entry`, walking the template compiled when the
+    /// entry was built instead of matching placeholders again.
+    fn interpolate_entry_into<W, V>(
+        &self,
+        out: &mut W,
+        entry: &Entry,
+        vals: &[V],
+        fill: Fill,
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        V: Display,
+    {
+        let value = entry.value.as_str();
+        match &entry.template {
+            _ if self.pseudo => self.interpolate_into(out, value, vals, fill),
+            _ if !self.interpolation_enabled => out.write_str(value),
+            Template::Dynamic => self.interpolate_into(out, value, vals, fill),
+            // compiled for another style, e.g. a lazily loaded language overridden since
+            template if template.style() != Some(fill.style) => {
+                self.interpolate_into(out, value, vals, fill)
+            }
+            Template::Plain(_) => out.write_str(value),
+            Template::Segments(_, segments) => {
+                for segment in segments.iter() {
+                    match segment {
+                        Segment::Literal(range) => out.write_str(&value[range.clone()])?,
+                        Segment::Escape(raw) => write_escape(out, &value[raw.clone()], vals, fill)?,
+                        Segment::Placeholder {
+                            index,
+                            modifier,
+                            raw,
+                        } => {
+                            let modifier = modifier.clone().map(|m| &value[m]);
+                            self.write_placeholder(
+                                out,
+                                *index,
+                                modifier,
+                                &value[raw.clone()],
+                                vals,
+                                fill,
+                            )?
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the value of placeholder `raw`, `index` being `None` when it doesn't fit a `usize`.
+    /// The value is HTML-escaped when `fill.html` is set or the modifier is `html`. Without any
+    /// value the placeholder is kept as written, since it may well be literal text (`%2024年`).
+    fn write_placeholder<W, V>(
+        &self,
+        out: &mut W,
+        index: Option<usize>,
+        modifier: Option<&str>,
+        raw: &str,
+        vals: &[V],
+        fill: Fill,
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        V: Display,
+    {
+        if vals.is_empty() && !fill.without_values {
+            return out.write_str(raw);
+        }
+        if self.isolates(index, modifier, vals) {
+            out.write_char(bidi::FSI)?;
+            self.write_filled(out, index, modifier, raw, vals, fill)?;
+            return out.write_char(bidi::PDI);
+        }
+        self.write_filled(out, index, modifier, raw, vals, fill)
+    }
+
+    fn write_filled<W, V>(
+        &self,
+        out: &mut W,
+        index: Option<usize>,
+        modifier: Option<&str>,
+        raw: &str,
+        vals: &[V],
+        fill: Fill,
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        V: Display,
+    {
+        match modifier {
+            Some("html") => self.write_value(&mut HtmlEscaper(out), index, None, raw, vals),
+            _ if fill.html => self.write_value(&mut HtmlEscaper(out), index, modifier, raw, vals),
+            _ => self.write_value(out, index, modifier, raw, vals),
+        }
+    }
+
+    fn write_value<W, V>(
+        &self,
+        out: &mut W,
+        index: Option<usize>,
+        modifier: Option<&str>,
+        raw: &str,
+        vals: &[V],
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        V: Display,
+    {
+        let Some(index) = index else {
+            return self.write_missing(out, raw);
+        };
+        match (index, modifier) {
+            (0, None) => {
+                let present = vals.iter().filter(|v| !opt::is_absent(v));
+                for (i, v) in present.enumerate() {
+                    if i > 0 {
+                        out.write_str(&self.join_separator)?;
+                    }
+                    write!(out, "{v}")?;
+                }
+                Ok(())
+            }
+            (0, Some(modifier)) => {
+                let present = vals.iter().filter(|v| !opt::is_absent(v));
+                let joined = present.map(|v| v.to_string()).collect::<Vec<_>>();
+                let joined = joined.join(&self.join_separator);
+                out.write_str(&case::apply(modifier, &joined, &self.active_lang()))
+            }
+            (i, modifier) => match (vals.get(i - 1), modifier) {
+                (Some(v), _) if opt::is_absent(v) => self.write_missing(out, raw),
+                (Some(v), None) => write!(out, "{v}"),
+                (Some(v), Some(modifier)) => {
+                    let v = v.to_string();
+                    out.write_str(&case::apply(modifier, &v, &self.active_lang()))
+                }
+                (None, _) => self.write_missing(out, raw),
+            },
+        }
+    }
+
+    /// Writes the rest placeholder `raw`: the present values whose index is not `consumed` by a
+    /// numbered placeholder, joined with the list separator of the active language. Nothing is
+    /// written without such values.
+    fn write_rest<W, V>(
+        &self,
+        out: &mut W,
+        raw: &str,
+        consumed: &[bool],
+        vals: &[V],
+        fill: Fill,
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        V: Display,
+    {
+        if vals.is_empty() && !fill.without_values {
+            return out.write_str(raw);
+        }
+        let separator = list::separator(&self.active_lang());
+        let isolate = self.isolates(Some(0), None, vals);
+        let rest = vals
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| !consumed.get(*i).copied().unwrap_or_default() && !opt::is_absent(v));
+        for (n, (_, v)) in rest.enumerate() {
+            if n > 0 {
+                out.write_str(separator)?;
+            }
+            if isolate {
+                out.write_char(bidi::FSI)?;
+            }
+            match fill.html {
+                true => fmt::Write::write_fmt(&mut HtmlEscaper(&mut *out), format_args!("{v}"))?,
+                false => write!(out, "{v}")?,
+            }
+            if isolate {
+                out.write_char(bidi::PDI)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_missing<W: fmt::Write + ?Sized>(&self, out: &mut W, placeholder: &str) -> fmt::Result {
+        match context::missing_arg().unwrap_or(self.missing_arg) {
+            MissingArg::Empty => Ok(()),
+            MissingArg::Keep => out.write_str(placeholder),
+        }
+    }
+}
+
+type SourceMap = HashMap<Namespace, HashMap<String, Entry>>;
+
+/// Name of the reserved metadata section of a language file.
+const META: &str = "_meta";
+
+/// The translations of one language, grouped by namespace.
+///
+/// With the `nfc` feature (on by default) keys are normalized to Unicode NFC both when the source
+/// is built and when a key is looked up, so a decomposed `é` typed on macOS still matches the
+/// composed `é` stored in the json file. Values are never normalized.
+///
+/// A reserved top-level `_meta` object carries [`LangMeta`] for the language and is never
+/// treated as a namespace.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(from = "RawSource")]
+pub struct Source {
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    meta: Option<LangMeta>,
+    #[serde(flatten, serialize_with = "sorted_namespaces")]
+    namespaces: SourceMap,
+    /// Keys repeated in the file this source was read from.
+    #[serde(skip)]
+    duplicates: Vec<(Namespace, String)>,
+    /// `(namespace, key kept, original keys)` of every group of keys merged into one by
+    /// normalization.
+    #[serde(skip)]
+    collapsed: Vec<(Namespace, String, Vec<String>)>,
+}
+
+/// Metadata about a language, read from the `_meta` section of its file:
+/// ```json
+/// {
+///   "_meta": { "display_name": "Français", "direction": "ltr", "plural_rule": "fr" },
+///   "common": { ... }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct LangMeta {
+    /// Name of the language in that language, for language pickers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<Direction>,
+    /// Identifier of the plural rule used by the language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plural_rule: Option<String>,
+    /// A string or a number, read as written: `"version": 3` is `"3"`.
+    #[serde(
+        default,
+        deserialize_with = "string_or_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_updated: Option<String>,
+    /// How the placeholders of the language are written, see [`PlaceholderStyle`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder_style: Option<PlaceholderStyle>,
+    /// Every other field, preserved as is.
+    #[serde(flatten, serialize_with = "sorted")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Reads a string, or a number as its text.
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Text(String),
+        Number(serde_json::Number),
+    }
+    Ok(
+        Option::<Value>::deserialize(deserializer)?.map(|value| match value {
+            Value::Text(text) => text,
+            Value::Number(number) => number.to_string(),
+        }),
+    )
+}
+
+/// Text direction of a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+/// Serializes a map in key order, so that exports of unchanged data are byte-identical.
+pub(crate) fn sorted<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+fn sorted_namespaces<S: serde::Serializer>(
+    namespaces: &SourceMap,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        namespaces
+            .iter()
+            .map(|(ns, entries)| (ns, entries.iter().collect::<BTreeMap<_, _>>()))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+impl From<RawSource> for Source {
+    fn from(raw: RawSource) -> Self {
+        let mut collapsed = vec![];
+        let namespaces = raw
+            .namespaces
+            .into_iter()
+            .map(|(ns, entries)| {
+                let mut originals: HashMap<String, Vec<String>> = HashMap::new();
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, val)| {
+                        let normalized = normalize_key(&key).into_owned();
+                        originals.entry(normalized.clone()).or_default().push(key);
+                        (normalized, val)
+                    })
+                    .collect();
+                for (normalized, mut keys) in originals {
+                    if keys.len() > 1 {
+                        keys.sort();
+                        collapsed.push((ns.clone(), normalized, keys));
+                    }
+                }
+                (ns, entries)
+            })
+            .collect();
+        collapsed.sort();
+        let mut source = Source {
+            meta: raw.meta,
+            namespaces,
+            duplicates: raw.duplicates,
+            collapsed,
+        };
+        if let Some(style) = source.meta.as_ref().and_then(|m| m.placeholder_style) {
+            source.compile_templates(style);
+        }
+        source
+    }
+}
+
+impl Source {
+    /// Reads the json file at `path`. With the `json-comments` feature, `//` line comments are
+    /// allowed and discarded.
+    #[cfg(feature = "std")]
+    pub fn from_path(path: &Path) -> Result<Self> {
+        loader::load_file(&JsonLoader, path, false)
+    }
+
+    /// Builds a source from translations by namespace and key, without any file format.
+    pub fn from_map(map: HashMap<Namespace, HashMap<String, String>>) -> Self {
+        let namespaces = map
+            .into_iter()
+            .map(|(ns, entries)| {
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, val)| (normalize_key(&key).into_owned(), Entry::from(val)))
+                    .collect();
+                (ns, entries)
+            })
+            .collect();
+        Source {
+            namespaces,
+            ..Default::default()
+        }
+    }
+
+    /// Parses a json catalog held in memory, e.g. embedded in the binary with `include_str!`.
+    pub fn from_static(json: &str) -> Result<Self> {
+        JsonLoader
+            .load(json.as_bytes())
+            .context("[source error]: embedded catalog")
+    }
+
+    /// Whether this source has namespace `ns`, even an empty one.
+    pub fn has_namespace(&self, ns: &str) -> bool {
+        self.namespaces.contains_key(ns)
+    }
+
+    pub fn get_val(&self, key: &str, ns: Option<Namespace>) -> Option<String> {
+        let ns = ns.unwrap_or(DEFAULT_NS.to_string());
+        self.get(key, &ns).map(|v| v.to_string())
+    }
+
+    /// The variant of `key` for the plural `category` (`one`, `other`, ...), see
+    /// [`Entry::plural`]. A plain string is the variant of every category.
+    pub fn get_variant(&self, key: &str, ns: Option<Namespace>, category: &str) -> Option<String> {
+        let ns = ns.unwrap_or(DEFAULT_NS.to_string());
+        let entry = self.get_entry(key, &ns)?;
+        Some(entry.plural(category).to_string())
+    }
+
+    /// The translation of `text` in namespace `ns` (`common` when `None`) of this source alone, or
+    /// the text itself when it has none. No fallback namespace is searched.
+    pub fn translate(&self, text: &str, ns: Option<Namespace>) -> String {
+        let ns = ns.unwrap_or(DEFAULT_NS.to_string());
+        self.get(text, &ns).unwrap_or(text).to_string()
+    }
+
+    /// [`Source::translate`] with the placeholders filled from `vals`, following the default
+    /// settings of an [`I18n`]: `, ` for `%0` and empty missing values.
+    pub fn trans_with_inter(&self, text: &str, vals: Vec<String>, ns: Option<Namespace>) -> String {
+        static DEFAULTS: Lazy<I18n> = Lazy::new(|| I18n::new(""));
+        let ns = ns.unwrap_or(DEFAULT_NS.to_string());
+        render(0, |out| match self.get_entry(text, &ns) {
+            Some(entry) => DEFAULTS.interpolate_entry_into(out, entry, &vals, Fill::default()),
+            None => DEFAULTS.interpolate_into(out, text, &vals, Fill::default()),
+        })
+    }
+
+    /// Reads `path` with the built-in loader matching its extension.
+    #[cfg(feature = "std")]
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Loaders::default().load(path, false)
+    }
+
+    /// Reads `path`, checking it against [`source_schema`] first so that errors point at the
+    /// offending values instead of being raw serde messages.
+    #[cfg(feature = "std")]
+    pub fn from_path_strict(path: &Path) -> Result<Self> {
+        loader::load_file(&JsonLoader, path, true)
+    }
+
+    /// Adds every entry of `other` to this source, replacing existing ones.
+    pub fn merge(&mut self, other: Source) {
+        if other.meta.is_some() {
+            self.meta = other.meta;
+        }
+        for (ns, entries) in other.namespaces {
+            self.namespaces.entry(ns).or_default().extend(entries);
+        }
+    }
+
+    /// The translator comment attached to `key` in `ns`, if any.
+    pub fn comment(&self, key: &str, ns: &str) -> Option<&str> {
+        self.get_entry(key, ns)
+            .and_then(|entry| entry.comment.as_deref())
+    }
+
+    /// The `max_len` attached to `key` in `ns`, if any.
+    pub fn max_len(&self, key: &str, ns: &str) -> Option<usize> {
+        self.get_entry(key, ns).and_then(|entry| entry.max_len)
+    }
+
+    /// The `_meta` section of the file, if any.
+    pub fn meta(&self) -> Option<&LangMeta> {
+        self.meta.as_ref()
+    }
+
+    pub(crate) fn get(&self, key: &str, ns: &str) -> Option<&str> {
+        self.get_entry(key, ns).map(|entry| entry.value.as_str())
+    }
+
+    /// The entry stored under `key` in `ns`, with all its variants.
+    pub fn get_entry(&self, key: &str, ns: &str) -> Option<&Entry> {
+        let key = normalize_key(key);
+        self.namespaces
+            .get(ns)
+            .and_then(|map| map.get(key.as_ref()))
+    }
+
+    /// Rewrites every key with [`LoadOptions::normalize`]. Returns, per namespace, the sorted
+    /// original keys of every group that collapsed into one normalized key.
+    fn normalize_keys(&mut self, options: &LoadOptions) -> Vec<(Namespace, String, Vec<String>)> {
+        let mut collisions = vec![];
+        if !options.normalize_whitespace {
+            return collisions;
+        }
+        for (ns, entries) in self.namespaces.iter_mut() {
+            let mut groups: HashMap<String, Vec<(String, Entry)>> = HashMap::new();
+            for (key, val) in entries.drain() {
+                let normalized = options.normalize(&key).into_owned();
+                groups.entry(normalized).or_default().push((key, val));
+            }
+            for (normalized, mut group) in groups {
+                group.sort_by(|(a, _), (b, _)| a.cmp(b));
+                if group.len() > 1 {
+                    let keys: Vec<String> = group.iter().map(|(key, _)| key.clone()).collect();
+                    self.collapsed
+                        .push((ns.clone(), normalized.clone(), keys.clone()));
+                    collisions.push((ns.clone(), normalized.clone(), keys));
+                }
+                let (_, val) = group.swap_remove(0);
+                entries.insert(normalized, val);
+            }
+        }
+        collisions.sort();
+        collisions
+    }
+}
+
+impl Source {
+    /// Normalizes the keys of a source freshly read or built and reports what is suspicious
+    /// about it: keys repeated in the file, keys collapsed by normalization and keys with the
+    /// same value in several namespaces.
+    fn load_warnings(&mut self, lang: &str, options: &LoadOptions) -> Vec<LoadWarning> {
+        let warning = |namespace, kind| LoadWarning {
+            lang: lang.to_string(),
+            #[cfg(feature = "std")]
+            file: None,
+            namespace,
+            kind,
+        };
+        let mut warnings: Vec<LoadWarning> = self
+            .duplicates
+            .iter()
+            .map(|(ns, key)| {
+                warning(
+                    ns.clone(),
+                    LoadWarningKind::DuplicateKey { key: key.clone() },
+                )
+            })
+            .collect();
+        for (ns, normalized, keys) in self.normalize_keys(options) {
+            warnings.push(warning(
+                ns,
+                LoadWarningKind::NormalizedDuplicate { normalized, keys },
+            ));
+        }
+
+        let mut by_value: HashMap<(&str, &Entry), Vec<Namespace>> = HashMap::new();
+        for (ns, entries) in &self.namespaces {
+            for (key, val) in entries {
+                by_value.entry((key, val)).or_default().push(ns.clone());
+            }
+        }
+        let mut shared: Vec<_> = by_value
+            .into_iter()
+            .filter(|(_, namespaces)| namespaces.len() > 1)
+            .map(|((key, _), mut namespaces)| {
+                namespaces.sort();
+                (namespaces, key.to_string())
+            })
+            .collect();
+        shared.sort();
+        for (namespaces, key) in shared {
+            let ns = namespaces[0].clone();
+            warnings.push(warning(
+                ns,
+                LoadWarningKind::SameValueInNamespaces { key, namespaces },
+            ));
+        }
+        warnings
+    }
+
+    /// [`Source::load_warnings`] of a source read from `path`.
+    #[cfg(feature = "std")]
+    fn file_warnings(
+        &mut self,
+        lang: &str,
+        path: &Path,
+        options: &LoadOptions,
+    ) -> Vec<LoadWarning> {
+        let mut warnings = self.load_warnings(lang, options);
+        for warning in &mut warnings {
+            warning.file = Some(path.to_path_buf());
+        }
+        warnings
+    }
+}
+
+/// The plural category of `count` for the variants of an entry: `zero` for 0, `one` for 1 and
+/// `other` for any other number or text. The plural rules of CLDR, where `few` and `many` apply,
+/// are not followed; a variant missing for the category gives way to the `other` one.
+pub fn plural_category(count: &str) -> &'static str {
+    match count.trim().parse::<f64>() {
+        Ok(0.0) => "zero",
+        Ok(1.0) => "one",
+        _ => "other",
+    }
+}
+
+/// Which of `len` values the numbered placeholders of `template` use, for the rest placeholder.
+fn consumed(template: &str, len: usize, style: PlaceholderStyle) -> Vec<bool> {
+    let mut consumed = vec![false; len];
+    for caps in style.regex().captures_iter(template) {
+        if let Token::Placeholder(Some(i)) = style.token(&caps) {
+            if let Some(used) = i.checked_sub(1).and_then(|i| consumed.get_mut(i)) {
+                *used = true;
+            }
+        }
+    }
+    consumed
+}
+
+/// Resolves the inline plurals of `template`: `{message|messages}` picks the singular when the
+/// first value that parses as a number is 1 and the plural otherwise (0 included), `{%2:a|b}` is
+/// driven by the second value instead. Without a numeric value to go by, the plural is used.
+fn select_plurals<'t, V: Display>(template: &'t str, vals: &[V]) -> Cow<'t, str> {
+    if !template.contains('|') {
+        return Cow::Borrowed(template);
+    }
+    let count = |v: &V| v.to_string().trim().parse::<f64>().ok();
+    PLURAL_REG.replace_all(template, |caps: &regex::Captures| {
+        let n = match caps.get(1) {
+            Some(i) => i
+                .as_str()
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| vals.get(i.checked_sub(1)?))
+                .and_then(count),
+            None => vals.iter().find_map(count),
+        };
+        match n {
+            Some(1.0) => caps[2].to_string(),
+            _ => caps[3].to_string(),
+        }
+    })
+}
+
+/// Writes the delimiter a doubled one, `raw`, stands for, or `raw` itself when interpolating
+/// without any value, like a placeholder.
+fn write_escape<W, V>(out: &mut W, raw: &str, vals: &[V], fill: Fill) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+{
+    match vals.is_empty() && !fill.without_values {
+        true => out.write_str(raw),
+        false => out.write_str(&raw[1..]),
+    }
+}
+
+/// Resolves the conditional sections of `template`: `%{2?，中间名 %2}` becomes `，中间名 %2` when
+/// value 2 is present, and nothing when it is missing or an absent [`Opt`]. `%{0?...}` is kept
+/// when any value is present. Without any value the sections are kept as written, like
+/// placeholders.
+fn select_sections<'t, V: Display>(template: &'t str, vals: &[V], fill: Fill) -> Cow<'t, str> {
+    if !template.contains('?') || (vals.is_empty() && !fill.without_values) {
+        return Cow::Borrowed(template);
+    }
+    SECTION_REG.replace_all(template, |caps: &regex::Captures| {
+        let present = match caps[1].parse::<usize>() {
+            Ok(0) => vals.iter().any(|v| !opt::is_absent(v)),
+            Ok(i) => vals.get(i - 1).is_some_and(|v| !opt::is_absent(v)),
+            Err(_) => false,
+        };
+        match present {
+            true => caps[2].to_string(),
+            false => String::new(),
+        }
+    })
+}
+
+/// Trims `key` and collapses internal whitespace runs into a single ASCII space.
+fn collapse_whitespace(key: &str) -> Cow<'_, str> {
+    let collapsed = key.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed == key {
+        Cow::Borrowed(key)
+    } else {
+        Cow::Owned(collapsed)
+    }
+}
+
+/// Brings `key` to the form used for storage and lookup.
+#[cfg(feature = "nfc")]
+fn normalize_key(key: &str) -> Cow<'_, str> {
+    use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+    match is_nfc_quick(key.chars()) {
+        IsNormalized::Yes => Cow::Borrowed(key),
+        _ => Cow::Owned(key.nfc().collect()),
+    }
+}
+
+#[cfg(not(feature = "nfc"))]
+fn normalize_key(key: &str) -> Cow<'_, str> {
+    Cow::Borrowed(key)
+}
+
+#[cfg(feature = "std")]
+fn load_source(
+    path: &Path,
+    options: &LoadOptions,
+    loaders: &Loaders,
+) -> (HashMap<String, Source>, Vec<LoadWarning>) {
+    load_source_with_progress(path, options, loaders, &mut |_, _| {})
+}
+
+/// [`load_source`] calling `progress(done, total)` after each language file, parsed or skipped.
+#[cfg(feature = "std")]
+fn load_source_with_progress(
+    path: &Path,
+    options: &LoadOptions,
+    loaders: &Loaders,
+    progress: &mut dyn FnMut(usize, usize),
+) -> (HashMap<String, Source>, Vec<LoadWarning>) {
+    #[cfg(feature = "tracing")]
+    let (span, started) = {
+        use tracing::field::Empty;
+        let span = tracing::info_span!(
+            "load_source",
+            path = %path.display(),
+            files = Empty,
+            languages = Empty,
+            elapsed_us = Empty
+        );
+        (span.entered(), std::time::Instant::now())
+    };
+    let mut map = HashMap::new();
+    let mut warnings = vec![];
+    let files = source_files(path, loaders);
+    #[cfg(feature = "tracing")]
+    span.record("files", files.len());
+    let total = files.len();
+    for (done, (lang, path)) in files.into_iter().enumerate() {
+        match loaders.load(&path, false) {
+            Ok(mut source) => {
+                warnings.extend(source.file_warnings(&lang, &path, options));
+                #[cfg(feature = "log")]
+                log_loaded(&lang, &path, &source);
+                map.insert(lang, source);
+            }
+            Err(_err) => {
+                #[cfg(feature = "log")]
+                log::warn!("skipped {}: {_err:#}", path.display());
+                #[cfg(feature = "tracing")]
+                tracing::warn!(path = %path.display(), error = %format!("{_err:#}"), "skipped a language file");
+            }
+        }
+        progress(done + 1, total);
+    }
+    #[cfg(feature = "tracing")]
+    {
+        span.record("languages", map.len());
+        span.record("elapsed_us", started.elapsed().as_micros() as u64);
+    }
+    (map, warnings)
+}
+
+/// The loading half of [`I18n::try_set_source`].
+#[cfg(feature = "std")]
+fn load_source_strict(
+    path: &Path,
+    options: &LoadOptions,
+    loaders: &Loaders,
+) -> Result<(HashMap<String, Source>, Vec<LoadWarning>)> {
+    fs::read_dir(path).with_context(|| {
+        format!(
+            "[source error]: can't read the directory {}.",
+            path.display()
+        )
+    })?;
+    let mut sources = HashMap::new();
+    let mut warnings = vec![];
+    for (lang, path) in source_files(path, loaders) {
+        let mut source = loaders.load(&path, true)?;
+        let duplicates = source.lint(&LintOptions::only(&[LintKind::DuplicateKey]));
+        if let Some(warning) = duplicates.first() {
+            bail!("[source error]: {} of {}.", warning.message, path.display());
+        }
+        warnings.extend(source.file_warnings(&lang, &path, options));
+        sources.insert(lang, source);
+    }
+    Ok((sources, warnings))
+}
+
+/// The loading half of [`I18n::set_source_recursive`].
+#[cfg(feature = "std")]
+fn load_source_recursive(
+    path: &Path,
+    extensions: &[&str],
+    options: &LoadOptions,
+    loaders: &Loaders,
+) -> (HashMap<String, Source>, Vec<LoadWarning>) {
+    let mut sources: HashMap<String, Source> = HashMap::new();
+    let mut warnings = vec![];
+    for (lang, file) in source_files_recursive(path, extensions) {
+        match loaders.load(&file, false) {
+            Ok(mut source) => {
+                warnings.extend(source.file_warnings(&lang, &file, options));
+                #[cfg(feature = "log")]
+                log_loaded(&lang, &file, &source);
+                sources.entry(lang).or_default().merge(source);
+            }
+            Err(_err) => {
+                #[cfg(feature = "log")]
+                log::warn!("skipped {}: {_err:#}", file.display());
+            }
+        }
+    }
+    (sources, warnings)
+}
+
+#[cfg(feature = "log")]
+fn log_loaded(lang: &str, path: &Path, source: &Source) {
+    let keys: usize = source
+        .namespaces
+        .values()
+        .map(|entries| entries.len())
+        .sum();
+    log::debug!(
+        "loaded {lang} from {}: {keys} keys in {} namespaces",
+        path.display(),
+        source.namespaces.len()
+    );
+}
+
+/// Lists the files under `path` with one of `extensions`, together with the language they hold,
+/// in a stable order.
+#[cfg(feature = "std")]
+fn source_files_recursive(path: &Path, extensions: &[&str]) -> Vec<(String, PathBuf)> {
+    let mut files = vec![];
+    let walker = walkdir::WalkDir::new(path).sort_by_file_name();
+    for entry in walker.into_iter().flatten() {
+        let file = entry.path();
+        let matches = file
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)));
+        if !entry.file_type().is_file() || !matches {
+            continue;
+        }
+        let Ok(relative) = file.strip_prefix(path) else {
+            continue;
+        };
+        let mut components = relative.components();
+        let first = components.next().and_then(|c| c.as_os_str().to_str());
+        let lang = match (first, components.next()) {
+            (Some(dir), Some(_)) => Some(dir),
+            _ => file.file_stem().and_then(|stem| stem.to_str()),
+        };
+        if let Some(lang) = lang {
+            files.push((lang.to_uppercase(), file.to_path_buf()));
+        }
+    }
+    files
+}
+
+/// Lists the files in `path` that one of `loaders` reads, together with the language they hold.
+#[cfg(feature = "std")]
+fn source_files(path: &Path, loaders: &Loaders) -> Vec<(String, PathBuf)> {
+    let mut files = vec![];
+    if let Ok(dir) = fs::read_dir(path) {
+        for entry in dir.flatten() {
+            let path = entry.path();
+            if !path.is_dir() && loaders.for_path(&path).is_some() {
+                if let Some(file_name) = path.file_stem().and_then(|f| f.to_str()) {
+                    files.push((file_name.to_uppercase(), path.clone()));
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Translates a text with the global translator.
+///
+/// Supported forms:
+/// - `i18n!(key)`
+/// - `i18n!(key, arg1, arg2, ...)` to fill `%1`, `%2`, ...
+/// - `i18n!(key, ns = namespace)` and `i18n!(key, ns = namespace, arg1, ...)`
+/// - `i18n!(key, name1 = arg1, name2 = arg2, ...)` to fill `%name1`, `%name2`, ..., see
+///   [`I18n::trans_with_named`], also after `ns = namespace`
+/// - `i18n!(key, ctx = context)`, also after `ns = namespace` and before positional arguments,
+///   to tell apart the meanings of a short key, see [`I18n::translate_ctx`]. `ctx` is therefore
+///   not available as a keyword name.
+/// - `i18n!(key, default = text)`, also before or after `ns = namespace`, to show `text` instead
+///   of the key when it has no translation, see [`I18n::translate_with_default`]. `default` is
+///   not available as a keyword name either.
+/// - `i18n!(plural key, count)`, `i18n!(plural key, count, ns = namespace)` and
+///   `i18n!(plural key, count, ns = namespace, arg1, ...)` to pick the inline plurals by `count`,
+///   which fills `%1`, see [`I18n::trans_plural`]. The namespace may come before `count` too.
+///
+/// `namespace` can be any expression whose value implements `ToString`: a literal, a `&str` or
+/// `String` variable, or a function call resolved at runtime.
+///
+/// The key, the namespace and every argument are evaluated before the global translator is
+/// locked, and the lock is released before the macro returns, so the guard is never held across
+/// an `.await`, even one written in an argument: `i18n!("%1", fetch_name().await)` is fine in
+/// async code. The same goes for every macro of this crate.
+/// ```
+/// use easy_i18n::{i18n, I18N};
+/// fn current_screen() -> String {
+///     "checkout".to_string()
+/// }
+/// let ns = current_screen();
+/// i18n!("确认订单", ns = ns);
+/// i18n!("确认订单", ns = current_screen(), 3);
+/// let unread = 3;
+/// i18n!(plural "你有%1条{消息|消息}", unread, ns = "inbox"); // You have 3 messages
+/// i18n!("他的成绩是，语文：%chinese, 数学：%math", chinese = 88, math = 100);
+/// i18n!("发布", ctx = "verb");
+/// i18n!("促销横幅", ns = "home", default = "Welcome");
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! i18n {
+    // the `plural` arms come first: `plural key` is not an expression, and `ns = ...` is one, so
+    // the arms with a namespace must be tried before the ones taking any arguments
+    (plural $key:expr, $count:expr, ns=$ns:expr $(, $args:expr)* $(,)?) => {
+        {
+            let (key, count, ns) = (&$key, $count, $ns.to_string());
+            let vals = vec![$($args.to_string()),*];
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_plural(::std::convert::AsRef::<str>::as_ref(key), count, vals, Some(ns))
+        }
+    };
+
+    (plural $key:expr, ns=$ns:expr, $count:expr $(, $args:expr)* $(,)?) => {
+        {
+            let (key, ns, count) = (&$key, $ns.to_string(), $count);
+            let vals = vec![$($args.to_string()),*];
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_plural(::std::convert::AsRef::<str>::as_ref(key), count, vals, Some(ns))
+        }
+    };
+
+    (plural $key:expr, $count:expr $(, $args:expr)* $(,)?) => {
+        {
+            let (key, count) = (&$key, $count);
+            let vals = vec![$($args.to_string()),*];
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_plural(::std::convert::AsRef::<str>::as_ref(key), count, vals, None)
+        }
+    };
+
+    // the context arms come before the keyword ones, `ctx` being an identifier too
+    ($key:expr, ns=$ns:expr, ctx=$ctx:expr $(, $args:expr)* $(,)?) => {
+        {
+            let (key, ns, ctx) = (&$key, $ns.to_string(), $ctx.to_string());
+            let vals = vec![$($args.to_string()),*];
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_with_inter_ctx(::std::convert::AsRef::<str>::as_ref(key), &ctx, vals, Some(ns))
+        }
+    };
+
+    ($key:expr, ctx=$ctx:expr $(, $args:expr)* $(,)?) => {
+        {
+            let (key, ctx) = (&$key, $ctx.to_string());
+            let vals = vec![$($args.to_string()),*];
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_with_inter_ctx(::std::convert::AsRef::<str>::as_ref(key), &ctx, vals, None)
+        }
+    };
+
+    // so do the default arms, `default` being an identifier as well
+    ($key:expr, ns=$ns:expr, default=$default:expr $(,)?) => {
+        {
+            let (key, ns, default) = (&$key, $ns.to_string(), $default.to_string());
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.translate_with_default(::std::convert::AsRef::<str>::as_ref(key), &default, Some(ns))
+        }
+    };
+
+    ($key:expr, default=$default:expr, ns=$ns:expr $(,)?) => {
+        $crate::i18n!($key, ns = $ns, default = $default)
+    };
+
+    ($key:expr, default=$default:expr $(,)?) => {
+        {
+            let (key, default) = (&$key, $default.to_string());
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.translate_with_default(::std::convert::AsRef::<str>::as_ref(key), &default, None)
+        }
+    };
+
+    // the keyword arms come before the positional ones, `name = value` being an expression too
+    ($key:expr, ns=$ns:expr, $($name:ident = $val:expr),+ $(,)?) => {
+        {
+            let (key, ns) = (&$key, $ns.to_string());
+            let vals: ::std::collections::HashMap<&str, String> =
+                [$((stringify!($name), $val.to_string())),+].into_iter().collect();
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_with_named(::std::convert::AsRef::<str>::as_ref(key), &vals, Some(&ns))
+        }
+    };
+
+    ($key:expr, ns=$ns:expr, $($args:expr),+ $(,)?) => {
+        {
+            let (key, ns) = (&$key, $ns.to_string());
+            let vals = vec![$($args.to_string()),+];
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_with_inter(::std::convert::AsRef::<str>::as_ref(key), vals, Some(ns))
+        }
+    };
+
+    ($key:expr, ns=$ns:expr $(,)?) => {
+        {
+            let (key, ns) = (&$key, $ns.to_string());
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.translate(::std::convert::AsRef::<str>::as_ref(key), Some(ns))
+        }
+    };
+
+    ($key:expr, $($name:ident = $val:expr),+ $(,)?) => {
+        {
+            let key = &$key;
+            let vals: ::std::collections::HashMap<&str, String> =
+                [$((stringify!($name), $val.to_string())),+].into_iter().collect();
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_with_named(::std::convert::AsRef::<str>::as_ref(key), &vals, None)
+        }
+    };
+
+    ($key:expr, $($args:expr),+) => {
+        {
+            let key = &$key;
+            let vals = vec![$($args.to_string()),+];
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.trans_with_inter(::std::convert::AsRef::<str>::as_ref(key), vals, None)
+        }
+    };
+
+    ($key:expr) => {
+        {
+            let key = &$key;
+            let _misses = $crate::MissEvents::defer();
+            let i18n = I18N.read().unwrap();
+            i18n.translate(::std::convert::AsRef::<str>::as_ref(key), None)
+        }
+    };
+}
+
+/// [`i18n!`] for HTML output: the values are escaped, the translation is not, see
+/// [`I18n::trans_with_inter_html`].
+/// ```
+/// use easy_i18n::i18n_html;
+/// let name = "<script>alert(1)</script>";
+/// let greeting = i18n_html!("欢迎，<b>%1</b>", name); // 欢迎，<b>&lt;script&gt;...</b>
+/// let title = i18n_html!("确认订单", ns = "checkout");
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! i18n_html {
+    ($key:expr, ns=$ns:expr, $($args:expr),+ $(,)?) => {{
+        let (key, ns) = (&$key, $ns.to_string());
+        let vals = vec![$($args.to_string()),+];
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.trans_with_inter_html(::std::convert::AsRef::<str>::as_ref(key), vals, Some(ns))
+    }};
+
+    ($key:expr, ns=$ns:expr $(,)?) => {{
+        let (key, ns) = (&$key, $ns.to_string());
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.translate(::std::convert::AsRef::<str>::as_ref(key), Some(ns))
+    }};
+
+    ($key:expr, $($args:expr),+ $(,)?) => {{
+        let key = &$key;
+        let vals = vec![$($args.to_string()),+];
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.trans_with_inter_html(::std::convert::AsRef::<str>::as_ref(key), vals, None)
+    }};
+
+    ($key:expr) => {{
+        let key = &$key;
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.translate(::std::convert::AsRef::<str>::as_ref(key), None)
+    }};
+}
+
+/// Builds the `Vec<String>` of interpolation values from a mix of single values and `..iterable`
+/// spreads, for [`t_args`] and [`I18n::trans_with_inter`].
+/// ```
+/// use easy_i18n::i18n_args;
+/// let extra = vec![3, 4];
+/// assert_eq!(i18n_args![1, "two", ..extra, 5], ["1", "two", "3", "4", "5"]);
+/// ```
+#[macro_export]
+macro_rules! i18n_args {
+    (@push $vals:ident;) => {};
+
+    (@push $vals:ident; .. $iter:expr $(, $($rest:tt)*)?) => {
+        $vals.extend(::core::iter::IntoIterator::into_iter($iter).map(|v| $crate::__private::ToString::to_string(&v)));
+        $crate::i18n_args!(@push $vals; $($($rest)*)?);
+    };
+
+    (@push $vals:ident; $arg:expr $(, $($rest:tt)*)?) => {
+        $vals.push($crate::__private::ToString::to_string(&$arg));
+        $crate::i18n_args!(@push $vals; $($($rest)*)?);
+    };
+
+    ($($args:tt)*) => {
+        {
+            #[allow(unused_mut)]
+            let mut vals: $crate::__private::Vec<$crate::__private::String> = $crate::__private::Vec::new();
+            $crate::i18n_args!(@push vals; $($args)*);
+            vals
+        }
+    };
+}
+
+/// Like [`i18n!`], but writes the translation into `buf`, any [`std::fmt::Write`] sink, and
+/// returns the [`std::fmt::Result`]. Arguments are formatted straight into the sink.
+/// ```
+/// use easy_i18n::i18n_write;
+/// let mut page = String::new();
+/// i18n_write!(page, "这是一个测试").unwrap();
+/// i18n_write!(page, "他的成绩是，语文：%1, 数学：%2", 88, 100).unwrap();
+/// i18n_write!(page, "确认订单", ns = "checkout").unwrap();
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! i18n_write {
+    ($buf:expr, $key:expr, ns=$ns:expr, $($args:expr),+ $(,)?) => {{
+        let (key, ns) = (&$key, $ns.to_string());
+        let vals = [$(&$args as &dyn ::std::fmt::Display),+];
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.trans_with_inter_into(&mut $buf, ::std::convert::AsRef::<str>::as_ref(key), &vals, Some(ns))
+    }};
+
+    ($buf:expr, $key:expr, ns=$ns:expr $(,)?) => {{
+        let (key, ns) = (&$key, $ns.to_string());
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.translate_into(&mut $buf, ::std::convert::AsRef::<str>::as_ref(key), Some(ns))
+    }};
+
+    ($buf:expr, $key:expr, $($args:expr),+ $(,)?) => {{
+        let key = &$key;
+        let vals = [$(&$args as &dyn ::std::fmt::Display),+];
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.trans_with_inter_into(&mut $buf, ::std::convert::AsRef::<str>::as_ref(key), &vals, None)
+    }};
+
+    ($buf:expr, $key:expr) => {{
+        let key = &$key;
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.translate_into(&mut $buf, ::std::convert::AsRef::<str>::as_ref(key), None)
+    }};
+}
+
+/// Like [`i18n!`] without interpolation, but returns `None` instead of the raw key when the
+/// text has no translation.
+/// ```
+/// use easy_i18n::i18n_opt;
+/// let tooltip: Option<String> = i18n_opt!("tooltip");
+/// let tooltip: Option<String> = i18n_opt!("tooltip", ns = "toolbar");
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! i18n_opt {
+    ($key:expr, ns=$ns:expr $(,)?) => {{
+        let (key, ns) = (&$key, $ns.to_string());
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.translate_opt(::std::convert::AsRef::<str>::as_ref(key), Some(ns))
+    }};
+
+    ($key:expr) => {{
+        let key = &$key;
+        let _misses = $crate::MissEvents::defer();
+        let i18n = $crate::I18N.read().unwrap();
+        i18n.translate_opt(::std::convert::AsRef::<str>::as_ref(key), None)
+    }};
+}
+
+/// [`i18n!`] for a translator of your own, an [`I18nHandle`] or an [`I18n`], instead of the
+/// global one.
+/// ```
+/// use easy_i18n::{i18n_in, I18n};
+/// let legal = I18n::new("en");
+/// i18n_in!(legal, "条款");
+/// i18n_in!(legal, "第%1条", ns = "terms", 3);
+/// ```
+#[macro_export]
+macro_rules! i18n_in {
+    ($i18n:expr, $key:expr, ns=$ns:expr, $($args:expr),+ $(,)?) => {
+        $i18n.trans_with_inter(::core::convert::AsRef::<str>::as_ref(&$key), $crate::__private::vec![$($crate::__private::ToString::to_string(&$args)),+], Some($crate::__private::ToString::to_string(&$ns)))
+    };
+
+    ($i18n:expr, $key:expr, ns=$ns:expr $(,)?) => {
+        $i18n.translate(::core::convert::AsRef::<str>::as_ref(&$key), Some($crate::__private::ToString::to_string(&$ns)))
+    };
+
+    ($i18n:expr, $key:expr, $($args:expr),+ $(,)?) => {
+        $i18n.trans_with_inter(::core::convert::AsRef::<str>::as_ref(&$key), $crate::__private::vec![$($crate::__private::ToString::to_string(&$args)),+], None)
+    };
+
+    ($i18n:expr, $key:expr) => {
+        $i18n.translate(::core::convert::AsRef::<str>::as_ref(&$key), None)
+    };
+}
+
+/// A [`Message`] for `key`, in a `static` or a `const`. The key and namespace must be literals or
+/// other `&'static str` constants.
+/// ```
+/// use easy_i18n::{msg, Message};
+/// static TITLE: Message = msg!("设置");
+/// static CONFIRM: Message = msg!("确认删除？", ns = "dialogs");
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! msg {
+    ($key:expr, ns=$ns:expr $(,)?) => {
+        $crate::Message::new($key, Some($ns))
+    };
+
+    ($key:expr) => {
+        $crate::Message::new($key, None)
+    };
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Serializes the tests that touch the global translator.
+    static GLOBAL: Mutex<()> = Mutex::new(());
+
+    /// A path of the temporary directory named after `name` and this process, so that test
+    /// runs in parallel don't share files.
+    pub(crate) fn temp_path(name: &str) -> PathBuf {
+        let id = std::process::id();
+        std::env::temp_dir().join(match name.split_once('.') {
+            Some((stem, extension)) => format!("{stem}_{id}.{extension}"),
+            None => format!("{name}_{id}"),
+        })
+    }
+
+    #[test]
+    fn it_works() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        set_lang("en");
+        dbg!(i18n!("这是一个测试"));
+        dbg!(i18n!("这是一个测试", ns = "namespace1"));
+    }
+
+    /// A catalog holding `entries` in `ns`.
+    pub(crate) fn source(ns: &str, entries: &[(&str, &str)]) -> Source {
+        let entries = entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), Entry::from(*v)))
+            .collect();
+        Source {
+            namespaces: HashMap::from([(ns.to_string(), entries)]),
+            ..Default::default()
+        }
+    }
+
+    static TEST: Message = msg!("这是一个测试");
+    static SCORES: Message = msg!("他的成绩是，语文：%1, 数学：%2");
+    static DIFFERENT: Message = msg!("这是一个测试", ns = "namespace1");
+
+    #[test]
+    fn lazy_messages() {
+        let _guard = GLOBAL.lock().unwrap();
+        *I18N.lock().unwrap() = I18n::new("en");
+        assert_eq!(TEST.get(), "这是一个测试");
+        assert_eq!(
+            SCORES.with_args(&[&88, &100]),
+            "他的成绩是，语文：88, 数学：100"
+        );
+
+        set_source(Path::new("./source"));
+        assert_eq!(TEST.get(), "This is a test");
+        assert_eq!(TEST.to_string(), "This is a test");
+        assert_eq!(
+            format!("{DIFFERENT}"),
+            "This is a test, but it is different"
+        );
+        assert_eq!(SCORES.with_args(&[&88, &100]), i18n!(SCORES.key, 88, 100));
+        set_lang("de");
+        assert_eq!(TEST.get(), "Das ist ein Test");
+        assert_eq!(format!("{TEST}"), "Das ist ein Test");
+
+        *I18N.lock().unwrap() = I18n::new("cn");
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn macro_defaults() {
+        let _guard = GLOBAL.lock().unwrap();
+        *I18N.lock().unwrap() = I18n::new("en");
+        set_source(Path::new("./source"));
+        assert_eq!(
+            i18n!("这是一个测试", ns = "namespace1", default = "Fallback"),
+            "This is a test, but it is different"
+        );
+        assert_eq!(
+            i18n!("这是一个测试", default = "Fallback", ns = "namespace1"),
+            "This is a test, but it is different"
+        );
+        assert_eq!(
+            i18n!("这是一个测试", default = "Fallback"),
+            "This is a test"
+        );
+        assert_eq!(
+            i18n!("没有翻译", ns = "namespace1", default = "Fallback"),
+            "Fallback"
+        );
+        // found through the fallback namespace
+        I18N.lock().unwrap().set_ns_fallback(&["common"]).unwrap();
+        I18N.lock()
+            .unwrap()
+            .set_override("en", "common", "仅通用", "Common only");
+        assert_eq!(
+            i18n!("仅通用", ns = "namespace1", default = "Fallback"),
+            "Common only"
+        );
+        let count = 3;
+        assert_eq!(
+            i18n!("没有翻译", default = format!("{count} left")),
+            "3 left"
+        );
+
+        *I18N.lock().unwrap() = I18n::new("cn");
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn init_once() {
+        let _guard = GLOBAL.lock().unwrap();
+        *I18N.lock().unwrap() = I18n::new("cn");
+        let threads: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(|| super::init_once(Path::new("./source"), "en")))
+            .collect();
+        let loaded: Vec<bool> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+        assert_eq!(loaded.iter().filter(|loaded| **loaded).count(), 1);
+        assert!(I18N.read().unwrap().is_initialized());
+        assert_eq!(i18n!("这是一个测试"), "This is a test");
+
+        // a later initializer leaves the language and the overrides alone
+        I18N.lock()
+            .unwrap()
+            .set_override("en", "common", "这是一个测试", "Probe");
+        assert!(!super::init_once(Path::new("./fixtures/duplicates"), "de"));
+        assert_eq!(current_lang(), "EN");
+        assert_eq!(i18n!("这是一个测试"), "Probe");
+
+        *I18N.lock().unwrap() = I18n::new("cn");
+        set_source(Path::new("./source"));
+        assert!(!I18N.read().unwrap().is_initialized());
+    }
+
+    #[test]
+    fn translate_many() {
+        let _guard = GLOBAL.lock().unwrap();
+        *I18N.lock().unwrap() = I18n::new("en");
+        set_source(Path::new("./source"));
+        let keys = ["这是一个测试", "没有翻译", "这是一个测试"];
+        assert_eq!(
+            super::translate_many(keys, None),
+            ["This is a test", "没有翻译", "This is a test"]
+        );
+        let different = "This is a test, but it is different";
+        assert_eq!(
+            super::translate_many(keys.iter().copied(), Some("namespace1")),
+            [different, "没有翻译", different]
+        );
+        let map = translate_many_map(keys, None);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["这是一个测试"], "This is a test");
+        assert_eq!(map["没有翻译"], "没有翻译");
+        assert!(I18N.read().unwrap().translate_many([], None).is_empty());
+
+        *I18N.lock().unwrap() = I18n::new("cn");
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn source_with_progress() {
+        let mut i18n = I18n::new("en");
+        let mut calls = vec![];
+        i18n.set_source_with_progress(Path::new("./source"), |done, total| {
+            calls.push((done, total))
+        });
+        assert_eq!(calls, [(1, 2), (2, 2)]);
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+
+        // a broken file is counted too
+        let dir = temp_path("easy_i18n_progress");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("en.json"), r#"{"common": {"保存": "Save"}}"#).unwrap();
+        fs::write(dir.join("fr.json"), "{").unwrap();
+        let handle = I18nHandle::new("en");
+        let mut calls = 0;
+        handle.set_source_with_progress(&dir, |done, total| {
+            calls += 1;
+            assert_eq!((done, total), (calls, 2));
+        });
+        assert_eq!(calls, 2);
+        assert_eq!(handle.translate("保存", None), "Save");
+        assert_eq!(handle.read().unwrap().languages(), ["EN"]);
+    }
+
+    #[test]
+    fn plural_variants() {
+        let json = r#"{"common": {
+            "%1条消息": {"one": "%1 message", "other": "%1 messages", "zero": "No messages"},
+            "%1个文件": {"value": "%1 files", "one": "a file", "comment": "no other"},
+            "保存": "Save"
+        }}"#;
+        let source: Source = serde_json::from_str(json).unwrap();
+        assert_eq!(source.get_val("%1条消息", None).unwrap(), "%1 messages");
+        let variant = |key, category| source.get_variant(key, None, category).unwrap();
+        assert_eq!(variant("%1条消息", "one"), "%1 message");
+        assert_eq!(variant("%1条消息", "few"), "%1 messages");
+        assert_eq!(variant("%1个文件", "two"), "%1 files");
+        assert_eq!(variant("保存", "one"), "Save");
+        assert_eq!(source.get_variant("没有", None, "one"), None);
+
+        let exported = serde_json::to_string(&source).unwrap();
+        let reloaded: Source = serde_json::from_str(&exported).unwrap();
+        assert_eq!(serde_json::to_string(&reloaded).unwrap(), exported);
+        for key in ["%1条消息", "%1个文件", "保存"] {
+            assert_eq!(
+                reloaded.get_entry(key, "common"),
+                source.get_entry(key, "common")
+            );
+        }
+
+        let mut i18n = I18n::new("en");
+        i18n.insert_source("en", source);
+        let plural = |count: i32| i18n.trans_plural("%1条消息", count, vec![], None);
+        assert_eq!(plural(1), "1 message");
+        assert_eq!(plural(3), "3 messages");
+        assert_eq!(plural(0), "No messages");
+        assert_eq!(i18n.trans_plural("%1个文件", 1, vec![], None), "a file");
+        assert_eq!(i18n.trans_plural("%1个文件", 2, vec![], None), "2 files");
+        // the other calls use the value
+        assert_eq!(
+            i18n.trans_with_inter("%1条消息", vec!["1".to_string()], None),
+            "1 messages"
+        );
+        assert_eq!(plural_category("1"), "one");
+        assert_eq!(plural_category(" 0 "), "zero");
+        assert_eq!(plural_category("1.5"), "other");
+        assert_eq!(plural_category("many"), "other");
+    }
+
+    #[test]
+    fn missing_decoration() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.insert_source("fr", source("common", &[("保存", "Enregistrer")]));
+        let vals = || vec!["88".to_string()];
+        // off by default
+        assert_eq!(i18n.translate("缺少的键", None), "缺少的键");
+
+        i18n.set_missing_decoration(Some(("⟦", "⟧")));
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        assert_eq!(i18n.translate("缺少的键", None), "⟦缺少的键⟧");
+        assert_eq!(
+            i18n.trans_with_inter("缺少的键%1", vals(), None),
+            "⟦缺少的键88⟧"
+        );
+        let checked = i18n.trans_with_inter_checked("成绩：%1", vals(), None);
+        assert_eq!(checked.unwrap(), "⟦成绩：88⟧");
+        let named = HashMap::from([("math", "100".to_string())]);
+        assert_eq!(
+            i18n.trans_with_named("数学%math", &named, None),
+            "⟦数学100⟧"
+        );
+        let mut out = String::new();
+        i18n.trans_with_inter_into(&mut out, "缺少的键%1", &[&1], None)
+            .unwrap();
+        assert_eq!(out, "⟦缺少的键1⟧");
+
+        // base-language hits are decorated on their own
+        i18n.set_lang("fr");
+        i18n.set_base_lang("en");
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        assert_eq!(i18n.translate("保存", None), "Enregistrer");
+        i18n.set_base_decoration(Some(("[{lang}→EN] ", "")));
+        assert_eq!(
+            i18n.translate("这是一个测试", None),
+            "[FR→EN] This is a test"
+        );
+        assert_eq!(i18n.translate("缺少的键", None), "⟦缺少的键⟧");
+        i18n.set_missing_decoration(Some(("[{lang}] ", "")));
+        assert_eq!(i18n.translate("缺少的键", None), "[FR] 缺少的键");
+
+        // pseudo-localization takes precedence
+        i18n.set_pseudo(true);
+        assert!(!i18n.translate("缺少的键", None).contains("[FR]"));
+        i18n.set_pseudo(false);
+        i18n.set_missing_decoration(None);
+        i18n.set_base_decoration(None);
+        assert_eq!(i18n.translate("缺少的键", None), "缺少的键");
+    }
+
+    #[test]
+    fn all_namespace_values() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_override("en", "dialog", "这是一个测试", "A dialog test");
+        i18n.set_override("en", "common", "这是一个测试", "This is a patched test");
+        assert_eq!(
+            i18n.all_namespace_values("这是一个测试"),
+            HashMap::from([
+                ("common".to_string(), "This is a patched test".to_string()),
+                (
+                    "namespace1".to_string(),
+                    "This is a test, but it is different".to_string()
+                ),
+                ("dialog".to_string(), "A dialog test".to_string()),
+            ])
+        );
+        assert!(i18n.all_namespace_values("不存在").is_empty());
+        i18n.set_lang("de");
+        assert_eq!(
+            i18n.all_namespace_values("这是一个测试")["common"],
+            "Das ist ein Test"
+        );
+        i18n.set_lang("ja");
+        assert!(i18n.all_namespace_values("这是一个测试").is_empty());
+    }
+
+    #[test]
+    fn install_translator() {
+        let _guard = GLOBAL.lock().unwrap();
+        let mut i18n = I18n::new("ja");
+        i18n.insert_source(
+            "ja",
+            source("common", &[("这是一个测试", "これはテストです")]),
+        );
+        let changed = Arc::new(Mutex::new(vec![]));
+        let seen = changed.clone();
+        let id = on_lang_change(move |old, new| seen.lock().unwrap().push(format!("{old}→{new}")));
+        set_lang("en");
+        changed.lock().unwrap().clear();
+        install(i18n);
+        assert_eq!(current_lang(), "JA");
+        assert_eq!(i18n!("这是一个测试"), "これはテストです");
+        assert_eq!(*changed.lock().unwrap(), ["EN→JA"]);
+        unsubscribe(id);
+        *I18N.lock().unwrap() = I18n::new("cn");
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn source_map() {
+        let mut i18n = I18n::new("fr");
+        i18n.set_source(Path::new("./source"));
+        let fr = Source::from_map(HashMap::from([
+            (
+                "common".to_string(),
+                HashMap::from([("保存".to_string(), "Enregistrer %1".to_string())]),
+            ),
+            (
+                "inbox".to_string(),
+                HashMap::from([("消息".to_string(), "Messages".to_string())]),
+            ),
+        ]));
+        assert_eq!(
+            fr.get_val("消息", Some("inbox".to_string())).unwrap(),
+            "Messages"
+        );
+        assert!(fr.has_namespace("inbox"));
+        assert!(!fr.has_namespace("typo_namespace"));
+        i18n.set_source_map(HashMap::from([("fr".to_string(), fr)]));
+        assert_eq!(i18n.source_path(), None);
+        assert_eq!(i18n.lang_count(), 1);
+        assert_eq!(
+            i18n.translate("消息", Some("inbox".to_string())),
+            "Messages"
+        );
+        assert_eq!(
+            i18n.trans_with_inter("保存", vec!["x".to_string()], None),
+            "Enregistrer x"
+        );
+        // the files loaded before are gone
+        i18n.set_lang("en");
+        assert_eq!(i18n.translate("这是一个测试", None), "这是一个测试");
+    }
+
+    #[test]
+    fn t_matches_macro() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        set_lang("en");
+        let key = "他的成绩是，语文：%1, 数学：%2";
+        let scores = [88, 100];
+        let args: Vec<&dyn Display> = scores.iter().map(|v| v as &dyn Display).collect();
+        assert_eq!(t(key, None, &args), i18n!(key, 88, 100));
+        assert_eq!(
+            t(key, Some("namespace1"), &args),
+            i18n!(key, ns = "namespace1", 88, 100)
+        );
+        assert_eq!(t("这是一个测试", None, &[]), i18n!("这是一个测试"));
+        // keys are anything `AsRef<str>`, e.g. generated constants or owned strings
+        let owned = String::from(key);
+        assert_eq!(i18n!(owned, 88, 100), i18n!(key, 88, 100));
+        assert_eq!(
+            i18n!(&owned, ns = "namespace1", 88, 100),
+            t(key, Some("namespace1"), &args)
+        );
+        assert_eq!(t("未翻译 %1", None, &[]), "未翻译 %1");
+    }
+
+    #[test]
+    fn opt_macro() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        set_lang("en");
+        assert_eq!(
+            i18n_opt!("这是一个测试"),
+            Some("This is a test".to_string())
+        );
+        assert_eq!(
+            i18n_opt!("这是一个测试", ns = "namespace1"),
+            Some("This is a test, but it is different".to_string())
+        );
+        assert_eq!(i18n_opt!("没有翻译"), None);
+        assert_eq!(i18n_opt!("这是一个测试", ns = "missing"), None);
+        assert!(I18N.lock().unwrap().has_translation("这是一个测试", None));
+        assert!(!I18N.lock().unwrap().has_translation("没有翻译", None));
+    }
+
+    #[test]
+    fn empty_as_missing() {
+        let mut i18n = I18n::new("en");
+        i18n.source.insert(
+            "EN".to_string(),
+            source("common", &[("保存", ""), ("取消", "  "), ("确定", "OK")]),
+        );
+        assert_eq!(i18n.translate("保存", None), "");
+        assert_eq!(i18n.translate_opt("取消", None), Some("  ".to_string()));
+
+        i18n.set_empty_as_missing(true);
+        assert_eq!(i18n.translate("保存", None), "保存");
+        assert_eq!(i18n.translate_opt("取消", None), None);
+        assert!(!i18n.has_translation("保存", None));
+        assert_eq!(i18n.translate("确定", None), "OK");
+    }
+
+    #[test]
+    fn empty_as_missing_in_fallback_chain() {
+        let mut en = source("common", &[("保存", "Save"), ("取消", "")]);
+        let namespace = |entries: &[(&str, Entry)]| {
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+        en.namespaces.insert(
+            "checkout.mobile".to_string(),
+            namespace(&[("保存", "".into()), ("取消", " ".into())]),
+        );
+        en.namespaces
+            .insert("checkout".to_string(), namespace(&[("保存", "".into())]));
+        en.namespaces.insert(
+            "shared".to_string(),
+            namespace(&[
+                ("保存", "Keep".into()),
+                (
+                    "继续",
+                    Entry {
+                        value: "Continue".to_string(),
+                        platforms: [("web".to_string(), "".to_string())].into(),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        );
+        let mut i18n = I18n::new("en");
+        i18n.source.insert("EN".to_string(), en);
+        i18n.set_ns_fallback(&["shared"]).unwrap();
+        i18n.set_common_fallback(true);
+        let mobile = || Some("checkout.mobile".to_string());
+
+        assert_eq!(i18n.translate("保存", mobile()), "");
+        assert_eq!(i18n.trans_platform("继续", "web", vec![], mobile()), "");
+
+        i18n.set_empty_as_missing(true);
+        let found = i18n.lookup("保存", mobile()).unwrap();
+        assert_eq!(
+            (found.namespace.as_str(), found.entry.value.as_str()),
+            ("shared", "Keep")
+        );
+        // blank in every namespace, common included
+        assert_eq!(i18n.translate("取消", mobile()), "取消");
+        assert_eq!(
+            i18n.trans_platform("继续", "web", vec![], mobile()),
+            "Continue"
+        );
+        assert!(i18n.languages_with_key("取消", None).is_empty());
+    }
+
+    #[test]
+    fn common_fallback() {
+        let mut en = source("common", &[("保存", "Save"), ("取消", "Cancel")]);
+        en.namespaces.insert(
+            "dialog".to_string(),
+            HashMap::from([("取消".to_string(), Entry::from("Dismiss"))]),
+        );
+        let mut i18n = I18n::new("en");
+        i18n.source.insert("EN".to_string(), en);
+        let dialog = || Some("dialog".to_string());
+
+        assert_eq!(i18n.translate("保存", dialog()), "保存");
+
+        i18n.set_common_fallback(true);
+        let found = i18n.lookup("保存", dialog()).unwrap();
+        assert_eq!(
+            (found.entry.value.as_str(), found.namespace.as_str()),
+            ("Save", "common")
+        );
+        let found = i18n.lookup("取消", dialog()).unwrap();
+        assert_eq!(
+            (found.entry.value.as_str(), found.namespace.as_str()),
+            ("Dismiss", "dialog")
+        );
+        assert_eq!(i18n.translate("确定", dialog()), "确定");
+    }
+
+    #[test]
+    fn runtime_namespace() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        set_lang("en");
+        let different = "This is a test, but it is different";
+        let ns: &str = "namespace1";
+        assert_eq!(i18n!("这是一个测试", ns = ns), different);
+        let ns: String = ns.to_string();
+        assert_eq!(i18n!("这是一个测试", ns = ns), different);
+        assert_eq!(i18n!("这是一个测试", ns = &ns), different);
+        let current_screen = || ns.clone();
+        assert_eq!(i18n!("这是一个测试", ns = current_screen()), different);
+        assert_eq!(
+            i18n!(
+                "他的成绩是，语文：%1, 数学：%2",
+                ns = current_screen(),
+                88,
+                100
+            ),
+            "His grades are Chinese: 88, Mathematics: 100, and the test is not bad."
+        );
+    }
+
+    #[test]
+    fn key_counts() {
+        let mut i18n = I18n::new("en");
+        assert_eq!((i18n.lang_count(), i18n.total_key_count()), (0, 0));
+        i18n.set_source(Path::new("./source"));
+        assert_eq!(i18n.lang_count(), 2);
+        assert_eq!(i18n.key_count("en"), 4);
+        assert_eq!(i18n.key_count("DE"), 1);
+        assert_eq!(i18n.key_count("fr"), 0);
+        assert_eq!(i18n.total_key_count(), 5);
+        i18n.set_override("en", "promo", "立即购买", "Buy now!");
+        assert_eq!(i18n.key_count("en"), 4);
+
+        i18n.set_source_lazy(Path::new("./source"));
+        assert_eq!(i18n.lang_count(), 2);
+        assert_eq!(i18n.total_key_count(), 5);
+
+        i18n.insert_source(
+            "fr",
+            source("common", &[("保存", "Enregistrer"), ("取消", " ")]),
+        );
+        assert_eq!(i18n.key_count("fr"), 2);
+        i18n.set_empty_as_missing(true);
+        assert_eq!(i18n.key_count("fr"), 1);
+        assert_eq!(i18n.total_key_count(), 6);
+    }
+
+    #[test]
+    fn languages_with_key() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        assert_eq!(i18n.languages_with_key("这是一个测试", None), ["DE", "EN"]);
+        assert_eq!(
+            i18n.languages_with_key("这是一个测试", Some("namespace1".to_string())),
+            ["EN"]
+        );
+        assert!(i18n.languages_with_key("没有翻译", None).is_empty());
+    }
+
+    #[test]
+    fn namespace_hierarchy() {
+        let mut en = source(
+            "common",
+            &[("a", "common a"), ("b", "common b"), ("c", "common c")],
+        );
+        let ns = |entries: &[(&str, &str)]| {
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), Entry::from(*v)))
+                .collect::<HashMap<_, _>>()
+        };
+        en.namespaces.insert(
+            "checkout".to_string(),
+            ns(&[("a", "checkout a"), ("b", "checkout b")]),
+        );
+        en.namespaces
+            .insert("checkout.mobile".to_string(), ns(&[("a", "mobile a")]));
+        en.namespaces.insert(
+            "shared".to_string(),
+            ns(&[("c", "shared c"), ("d", "shared d")]),
+        );
+        let mut i18n = I18n::new("en");
+        i18n.source.insert("EN".to_string(), en);
+        i18n.set_common_fallback(true);
+        let mobile = || Some("checkout.mobile".to_string());
+
+        assert_eq!(i18n.translate("a", mobile()), "mobile a");
+        assert_eq!(i18n.translate("b", mobile()), "checkout b");
+        assert_eq!(i18n.translate("c", mobile()), "common c");
+        assert_eq!(
+            i18n.matched_namespace("b", mobile()),
+            Some("checkout".to_string())
+        );
+        assert_eq!(
+            i18n.matched_namespace("c", mobile()),
+            Some("common".to_string())
+        );
+        assert_eq!(i18n.matched_namespace("d", mobile()), None);
+
+        i18n.set_ns_fallback(&["shared"]).unwrap();
+        assert_eq!(i18n.translate("d", mobile()), "shared d");
+        assert_eq!(
+            i18n.namespaces(mobile()),
+            ["checkout.mobile", "checkout", "shared", "common"]
+        );
+        assert!(i18n
+            .set_ns_fallback(&["shared", "common", "shared"])
+            .is_err());
+        assert_eq!(i18n.ns_fallback, ["shared"]);
+
+        // the chain decides where `common` comes
+        assert_eq!(i18n.translate("c", mobile()), "shared c");
+        i18n.set_ns_fallback(&["common", "shared"]).unwrap();
+        assert_eq!(i18n.translate("c", mobile()), "common c");
+        assert_eq!(i18n.translate("d", mobile()), "shared d");
+        assert_eq!(i18n.translate("e", mobile()), "e");
+    }
+
+    #[test]
+    fn meta_section() {
+        let json = r#"{
+            "_meta": {
+                "display_name": "Français",
+                "direction": "rtl",
+                "version": "3",
+                "team": {"owner": "l10n"}
+            },
+            "common": {"这是一个测试": "C'est un test"}
+        }"#;
+        let source: Source = serde_json::from_str(json).unwrap();
+        let meta = source.meta().unwrap();
+        assert_eq!(meta.display_name.as_deref(), Some("Français"));
+        assert_eq!(meta.direction, Some(Direction::Rtl));
+        assert_eq!(meta.version.as_deref(), Some("3"));
+        assert_eq!(meta.plural_rule, None);
+        for (version, read) in [("3", "3"), ("2.5", "2.5"), ("null", "")] {
+            let json = format!(r#"{{"_meta": {{"version": {version}}}}}"#);
+            let source: Source = serde_json::from_str(&json).unwrap();
+            assert_eq!(
+                source.meta().unwrap().version.as_deref().unwrap_or(""),
+                read
+            );
+        }
+        assert_eq!(meta.extra["team"], serde_json::json!({"owner": "l10n"}));
+        assert_eq!(
+            source.get_val("这是一个测试", None),
+            Some("C'est un test".to_string())
+        );
+        assert_eq!(
+            source.get_val("display_name", Some("_meta".to_string())),
+            None
+        );
+        assert_eq!(source.namespaces.len(), 1);
+
+        let round_trip: Source =
+            serde_json::from_value(serde_json::to_value(&source).unwrap()).unwrap();
+        assert_eq!(round_trip.meta(), source.meta());
+
+        // files without _meta load as before
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        assert_eq!(i18n.meta("en"), None);
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+    }
+
+    #[test]
+    fn source_from_env() {
+        let var = "EASY_I18N_TEST_SOURCE";
+        std::env::set_var(
+            var,
+            r#"{"en": {"common": {"这是一个测试": "This is an override"}, "extra": {"新": "New"}},
+                "fr": {"common": {"这是一个测试": "C'est un test"}}}"#,
+        );
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_source_from_env(var).unwrap();
+        assert_eq!(i18n.translate("这是一个测试", None), "This is an override");
+        assert_eq!(
+            i18n.translate("他的成绩是，语文：%1, 数学：%2", None),
+            "His grades are Chinese: %1, Mathematics: %2"
+        );
+        assert_eq!(i18n.translate("新", Some("extra".to_string())), "New");
+        i18n.set_lang("fr");
+        assert_eq!(i18n.translate("这是一个测试", None), "C'est un test");
+
+        assert!(i18n.set_source_from_env("EASY_I18N_TEST_UNSET").is_err());
+        std::env::set_var(var, "[1, 2]");
+        assert!(i18n.set_source_from_env(var).is_err());
+    }
+
+    #[test]
+    fn strict_loader() {
+        let dir = temp_path("easy_i18n_strict");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("en.json"), r#"{"common": {"a": "A"}}"#).unwrap();
+        fs::write(
+            dir.join("fr.json"),
+            r#"{"common": {"a": 1, "b": "B"}, "ns": []}"#,
+        )
+        .unwrap();
+
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        let err = i18n.try_set_source(&dir).unwrap_err().to_string();
+        assert!(
+            err.contains("fr.json doesn't match the source schema"),
+            "{err}"
+        );
+        assert!(
+            err.contains("/common/a: expected a string or an object, found a number"),
+            "{err}"
+        );
+        assert!(
+            err.contains("/ns: expected a namespace object, found an array"),
+            "{err}"
+        );
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+
+        fs::write(dir.join("fr.json"), r#"{"common": {"a": "Á"}}"#).unwrap();
+        i18n.try_set_source(&dir).unwrap();
+        assert_eq!(i18n.translate("a", None), "A");
+        assert_eq!(i18n.translate("这是一个测试", None), "这是一个测试");
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let dir = Path::new("./fixtures/duplicates");
+        let mut i18n = I18n::new("en");
+        i18n.set_source(dir);
+        assert_eq!(i18n.translate("保存", None), "Save changes");
+        let file = Some(dir.join("en.json"));
+        assert_eq!(
+            i18n.load_warnings(),
+            [
+                LoadWarning {
+                    lang: "EN".to_string(),
+                    file: file.clone(),
+                    namespace: "common".to_string(),
+                    kind: LoadWarningKind::DuplicateKey {
+                        key: "保存".to_string()
+                    },
+                },
+                LoadWarning {
+                    lang: "EN".to_string(),
+                    file,
+                    namespace: "common".to_string(),
+                    kind: LoadWarningKind::SameValueInNamespaces {
+                        key: "确定".to_string(),
+                        namespaces: vec!["common".to_string(), "dialog".to_string()],
+                    },
+                },
+            ]
+        );
+        let severities: Vec<Severity> = i18n
+            .load_warnings()
+            .iter()
+            .map(|w| w.kind.severity())
+            .collect();
+        assert_eq!(severities, [Severity::Warning, Severity::Info]);
+
+        let err = i18n.try_set_source(dir).unwrap_err().to_string();
+        assert!(
+            err.contains("key '保存' appears more than once in namespace 'common'"),
+            "{err}"
+        );
+
+        // no duplicates, no warnings
+        i18n.set_source(Path::new("./source"));
+        assert!(i18n.load_warnings().is_empty());
+    }
+
+    #[test]
+    fn platform_variants() {
+        let json = r#"{"common": {
+            "继续": {
+                "value": "Continue",
+                "platforms": {"web": "Click to continue", "mobile": "Tap to continue"}
+            },
+            "共%1项": {"value": "%1 items", "platforms": {"cli": "items: %1"}},
+            "保存": "Save"
+        }}"#;
+        let mut i18n = I18n::new("en");
+        i18n.source
+            .insert("EN".to_string(), serde_json::from_str(json).unwrap());
+        assert_eq!(
+            i18n.trans_platform("继续", "mobile", vec![], None),
+            "Tap to continue"
+        );
+        assert_eq!(
+            i18n.trans_platform("继续", "web", vec![], None),
+            "Click to continue"
+        );
+        assert_eq!(i18n.trans_platform("继续", "cli", vec![], None), "Continue");
+        assert_eq!(i18n.translate("继续", None), "Continue");
+        assert_eq!(
+            i18n.trans_platform("共%1项", "cli", vec!["3".to_string()], None),
+            "items: 3"
+        );
+        assert_eq!(i18n.trans_platform("保存", "web", vec![], None), "Save");
+        assert_eq!(i18n.trans_platform("未知", "web", vec![], None), "未知");
+    }
+
+    #[test]
+    fn interpolation_toggle() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        set_lang("en");
+        let key = "他的成绩是，语文：%1, 数学：%2";
+        set_interpolation_enabled(false);
+        let template = std::thread::spawn(move || i18n!(key, 88, 100))
+            .join()
+            .unwrap();
+        assert_eq!(template, "His grades are Chinese: %1, Mathematics: %2");
+        set_interpolation_enabled(true);
+        assert_eq!(
+            i18n!(key, 88, 100),
+            "His grades are Chinese: 88, Mathematics: 100"
+        );
+    }
+
+    #[test]
+    fn comments_round_trip() {
+        let json = r#"{"common": {
+            "发布": {"value": "Post", "comment": "verb — publish the draft"},
+            "帖子": {"value": "Post", "comment": "noun\n\"a forum post\" 📮"},
+            "保存": "Save",
+            "存档": {"value": "Save", "comment": "button label", "max_len": 12}
+        }}"#;
+        let source: Source = serde_json::from_str(json).unwrap();
+        assert_eq!(source.max_len("存档", "common"), Some(12));
+        assert_eq!(source.max_len("发布", "common"), None);
+        // key first, as in `get_entry`
+        assert_eq!(source.max_len("common", "存档"), None);
+        assert_eq!(source.comment("common", "发布"), None);
+        assert_eq!(source.get_val("存档", None), Some("Save".to_string()));
+        assert_eq!(
+            source.comment("发布", "common"),
+            Some("verb — publish the draft")
+        );
+        assert_eq!(source.comment("保存", "common"), None);
+        assert_eq!(source.get_val("帖子", None), Some("Post".to_string()));
+
+        let exported = serde_json::to_string(&source).unwrap();
+        let reloaded: Source = serde_json::from_str(&exported).unwrap();
+        for key in ["发布", "帖子", "保存", "存档"] {
+            assert_eq!(
+                reloaded.comment(key, "common"),
+                source.comment(key, "common")
+            );
+            assert_eq!(
+                reloaded.get_entry(key, "common"),
+                source.get_entry(key, "common")
+            );
+        }
+        assert_eq!(
+            reloaded.comment("帖子", "common"),
+            Some("noun\n\"a forum post\" 📮")
+        );
+    }
+
+    #[test]
+    fn recursive_source() {
+        let dir = temp_path("easy_i18n_recursive");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("fr/checkout")).unwrap();
+        fs::write(dir.join("en.json"), r#"{"common": {"保存": "Save"}}"#).unwrap();
+        fs::write(dir.join("notes.txt"), "not a catalog").unwrap();
+        fs::write(
+            dir.join("fr/common.json"),
+            r#"{"common": {"保存": "Enregistrer"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("fr/checkout/errors.json"),
+            r#"{"checkout": {"支付失败": "Échec du paiement"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("fr/checkout/extra.yaml"),
+            "checkout:\n  确认订单: Confirmer la commande\n",
+        )
+        .unwrap();
+
+        let mut i18n = I18n::new("fr");
+        i18n.set_source_recursive(&dir, &["json"]);
+        assert_eq!(i18n.languages_with_key("保存", None), ["EN", "FR"]);
+        assert_eq!(i18n.translate("保存", None), "Enregistrer");
+        let checkout = || Some("checkout".to_string());
+        assert_eq!(i18n.translate("支付失败", checkout()), "Échec du paiement");
+        assert_eq!(i18n.translate("确认订单", checkout()), "确认订单");
+
+        #[cfg(feature = "yaml")]
+        {
+            i18n.set_source_recursive(&dir, &["json", "yaml"]);
+            assert_eq!(
+                i18n.translate("确认订单", checkout()),
+                "Confirmer la commande"
+            );
+            assert_eq!(i18n.translate("支付失败", checkout()), "Échec du paiement");
+        }
+    }
+
+    #[test]
+    fn read_back_settings() {
+        let mut i18n = I18n::new("en-us");
+        assert_eq!(i18n.current_lang(), "EN-US");
+        assert_eq!(i18n.source_path(), None);
+        i18n.set_source(Path::new("./source"));
+        assert_eq!(i18n.source_path(), Some(Path::new("./source")));
+        let err = i18n
+            .try_set_source(Path::new("./fixtures/missing"))
+            .unwrap_err();
+        assert!(err.to_string().contains("can't read the directory"));
+        assert_eq!(i18n.source_path(), Some(Path::new("./source")));
+        i18n.set_lang("en");
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        i18n.set_source_lazy(Path::new("./source"));
+        assert_eq!(i18n.source_path(), Some(Path::new("./source")));
+
+        let _guard = GLOBAL.lock().unwrap();
+        set_lang("de");
+        assert_eq!(current_lang(), "DE");
+        set_lang("en");
+    }
+
+    #[test]
+    fn namespace_file() {
+        let file = temp_path("easy_i18n_errors.json");
+        fs::write(
+            &file,
+            r#"{"EN": {"未找到": "Not found"}, "fr": {"未找到": {"value": "Introuvable"}}}"#,
+        )
+        .unwrap();
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.add_namespace_file("errors", &file).unwrap();
+        let errors = || Some("errors".to_string());
+        assert_eq!(i18n.translate("未找到", errors()), "Not found");
+        assert_eq!(i18n.translate("未找到", None), "未找到");
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        i18n.set_lang("fr");
+        assert_eq!(i18n.translate("未找到", errors()), "Introuvable");
+
+        fs::write(&file, r#"{"EN": "Not found"}"#).unwrap();
+        assert!(i18n.add_namespace_file("errors", &file).is_err());
+    }
+
+    #[test]
+    fn overrides() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_override("en", "common", "这是一个测试", "This is an experiment");
+        i18n.set_override("en", "promo", "立即购买", "Buy now!");
+        i18n.set_override("fr", "common", "这是一个测试", "C'est un test");
+        assert_eq!(
+            i18n.translate("这是一个测试", None),
+            "This is an experiment"
+        );
+        assert_eq!(
+            i18n.translate("立即购买", Some("promo".to_string())),
+            "Buy now!"
+        );
+        assert_eq!(
+            i18n.translate("这是一个测试", Some("namespace1".to_string())),
+            "This is a test, but it is different"
+        );
+
+        // no file for FR, the override alone is used
+        i18n.set_lang("fr");
+        assert_eq!(i18n.translate("这是一个测试", None), "C'est un test");
+        i18n.set_lang("en");
+        assert!(i18n.clear_override("EN", "common", "这是一个测试"));
+        assert!(!i18n.clear_override("en", "common", "这是一个测试"));
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        assert_eq!(
+            i18n.translate("立即购买", Some("promo".to_string())),
+            "Buy now!"
+        );
+
+        i18n.set_source(Path::new("./source"));
+        assert_eq!(
+            i18n.translate("立即购买", Some("promo".to_string())),
+            "立即购买"
+        );
+        i18n.set_override("en", "promo", "立即购买", "Buy now!");
+        i18n.clear_all_overrides();
+        assert_eq!(
+            i18n.translate("立即购买", Some("promo".to_string())),
+            "立即购买"
+        );
+    }
+
+    #[test]
+    fn missing_key_suggestions() {
+        let mut i18n = I18n::new("en");
+        i18n.source.insert(
+            "EN".to_string(),
+            source(
+                "common",
+                &[
+                    ("确认删除?", "Delete?"),
+                    ("确认删除文件", "Delete the file"),
+                    ("保存", "Save"),
+                ],
+            ),
+        );
+        assert_eq!(
+            i18n.suggest("确认删除？", None, "en", 5),
+            [
+                ("确认删除?".to_string(), 0.99),
+                ("确认删除文件".to_string(), 1.0 - 2.0 / 6.0)
+            ]
+        );
+        assert_eq!(i18n.suggest("确认删除？", None, "en", 1).len(), 1);
+        assert!(i18n
+            .suggest("Something else entirely", None, "en", 5)
+            .is_empty());
+        assert!(i18n.suggest("保存", None, "en", 5).is_empty());
+        assert!(i18n
+            .suggest("保存", Some("dialog".to_string()), "en", 5)
+            .is_empty());
+
+        let misses = Arc::new(Mutex::new(vec![]));
+        let log = misses.clone();
+        i18n.set_missing_key_handler(move |miss| log.lock().unwrap().push(miss.to_string()));
+        assert_eq!(i18n.translate("确认删除？", None), "确认删除？");
+        assert_eq!(i18n.translate("保存", None), "Save");
+        assert_eq!(i18n.translate_opt("取消", None), None);
+        i18n.set_suggest_missing(true);
+        assert_eq!(
+            i18n.trans_with_inter("确认删除？", vec![], None),
+            "确认删除？"
+        );
+        i18n.trans_with_inter_into(&mut String::new(), "完全无关", &[], None)
+            .unwrap();
+        assert_eq!(
+            *misses.lock().unwrap(),
+            [
+                "key not found: `确认删除？` in namespace `common` (EN)",
+                "key not found: `确认删除？` in namespace `common` (EN); did you mean `确认删除?`, `确认删除文件`?",
+                "key not found: `完全无关` in namespace `common` (EN)",
+            ]
+        );
+    }
+
+    #[test]
+    fn key_transform() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_key_transform(|key| match key.rsplit_once("::") {
+            Some((_, key)) => key.to_string(),
+            None => key.to_string(),
+        });
+        let key = "report::这是一个测试";
+        assert_eq!(i18n.translate(key, None), "This is a test");
+        assert_eq!(
+            i18n.translate_opt(key, None).as_deref(),
+            Some("This is a test")
+        );
+        assert!(i18n.has_translation(key, None));
+        assert_eq!(i18n.languages_with_key(key, None), ["DE", "EN"]);
+        assert_eq!(
+            i18n.trans_with_inter(
+                "report::他的成绩是，语文：%1, 数学：%2",
+                vec!["1".into(), "2".into()],
+                None
+            ),
+            "His grades are Chinese: 1, Mathematics: 2"
+        );
+        assert_eq!(i18n.translate("report::缺少", None), "report::缺少");
+        i18n.clear_key_transform();
+        assert!(!i18n.has_translation(key, None));
+    }
+
+    #[test]
+    fn lazy_source() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source_lazy(Path::new("./source"));
+        assert!(i18n.source.is_empty());
+        assert!(i18n.lazy.values().all(|lazy| lazy.source.get().is_none()));
+
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        assert!(i18n.lazy["EN"].source.get().is_some());
+        assert!(i18n.lazy["DE"].source.get().is_none());
+
+        i18n.set_lang("de");
+        assert_eq!(i18n.translate("这是一个测试", None), "Das ist ein Test");
+
+        i18n.set_source(Path::new("./source"));
+        assert!(i18n.lazy.is_empty());
+        assert_eq!(i18n.translate("这是一个测试", None), "Das ist ein Test");
+    }
+
+    #[cfg(feature = "nfc")]
+    #[test]
+    fn nfc_keys() {
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        let json = format!(
+            r#"{{"common": {{"{composed}": "coffee", "{decomposed} noir": "black coffee"}}}}"#
+        );
+        let source: Source = serde_json::from_str(&json).unwrap();
+        assert_eq!(source.get_val(decomposed, None), Some("coffee".to_string()));
+        assert_eq!(source.get_val(composed, None), Some("coffee".to_string()));
+        assert_eq!(
+            source.get_val(&format!("{composed} noir"), None),
+            Some("black coffee".to_string())
+        );
+
+        // values are left as they are
+        let json = format!(r#"{{"common": {{"key": "{decomposed}"}}}}"#);
+        let source: Source = serde_json::from_str(&json).unwrap();
+        assert_eq!(source.get_val("key", None), Some(decomposed.to_string()));
+    }
+
+    #[test]
+    fn whitespace_normalization() {
+        let dir = temp_path("easy_i18n_whitespace");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("en.json"),
+            r#"{"common": {"确认\u00a0删除 ": "Confirm delete", " 确认　删除": "Delete?", "取消": "Cancel"}}"#,
+        )
+        .unwrap();
+
+        // off by default: keys must match exactly
+        let mut i18n = I18n::new("en");
+        i18n.set_source(&dir);
+        assert_eq!(i18n.translate("确认 删除", None), "确认 删除");
+        assert!(i18n.load_warnings().is_empty());
+
+        i18n.set_load_options(LoadOptions {
+            normalize_whitespace: true,
+        });
+        i18n.set_source(&dir);
+        assert_eq!(i18n.translate("  确认 \t删除", None), "Delete?");
+        assert_eq!(i18n.translate("取消", None), "Cancel");
+        assert_eq!(
+            i18n.load_warnings(),
+            [LoadWarning {
+                lang: "EN".to_string(),
+                file: Some(dir.join("en.json")),
+                namespace: "common".to_string(),
+                kind: LoadWarningKind::NormalizedDuplicate {
+                    normalized: "确认 删除".to_string(),
+                    keys: vec![" 确认　删除".to_string(), "确认\u{a0}删除 ".to_string()],
+                },
+            }]
+        );
+    }
+
+    #[cfg(feature = "intern")]
+    #[test]
+    fn interned_translations() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        let a = i18n.translate_interned("这是一个测试", None);
+        let b = i18n.translate_interned("这是一个测试", None);
+        assert_eq!(&*a, "This is a test");
+        assert!(Arc::ptr_eq(&a, &b));
+
+        i18n.set_lang("de");
+        let c = i18n.translate_interned("这是一个测试", None);
+        assert_eq!(&*c, "Das ist ein Test");
+        assert_eq!(i18n.interner.0.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn large_and_literal_indices() {
+        let mut i18n = I18n::new("en");
+        let inter = |i18n: &I18n, template: &str, vals: &[&str]| {
+            let vals = vals.iter().map(|v| v.to_string()).collect();
+            i18n.trans_with_inter(template, vals, None)
+        };
+        // no values: nothing is a placeholder, `%` and digits are literal text
+        assert_eq!(inter(&i18n, "%2024年报告", &[]), "%2024年报告");
+        assert_eq!(inter(&i18n, "%0 / %999", &[]), "%0 / %999");
+        assert_eq!(inter(&i18n, "%{1:upper}", &[]), "%{1:upper}");
+        assert_eq!(i18n.trans_with_inter_html("<%300>", vec![], None), "<%300>");
+        // with values, an index without one follows the missing-arg policy, beyond 255 too
+        assert_eq!(inter(&i18n, "%1：%2024年", &["a"]), "a：年");
+        assert_eq!(inter(&i18n, "%999|%0", &["a", "b"]), "|a, b");
+        assert_eq!(inter(&i18n, "%99999999999999999999999", &["a"]), "");
+        i18n.set_missing_arg(MissingArg::Keep);
+        assert_eq!(inter(&i18n, "%1：%2024年", &["a"]), "a：%2024年");
+        assert_eq!(inter(&i18n, "%999", &["a"]), "%999");
+        let vals: Vec<String> = (1..=300).map(|i| i.to_string()).collect();
+        assert_eq!(i18n.trans_with_inter("%256 %300", vals, None), "256 300");
+        // compiled templates follow the same rules
+        i18n.set_override("en", "common", "年份", "%2024年 %1");
+        assert_eq!(inter(&i18n, "年份", &[]), "%2024年 %1");
+        assert_eq!(inter(&i18n, "年份", &["x"]), "%2024年 x");
+    }
+
+    #[test]
+    fn disambiguation_context() {
+        let _guard = GLOBAL.lock().unwrap();
+        let en: Source = serde_json::from_str(
+            r#"{
+                "common": {
+                    "发布": "Post",
+                    "发布\u0004verb": "Publish",
+                    "发布于%1\u0004date": "Posted on %1"
+                },
+                "blog": { "发布\u0004noun": "Article" }
+            }"#,
+        )
+        .unwrap();
+        let mut i18n = I18n::new("en");
+        i18n.insert_source("en", en);
+        assert_eq!(context_key("发布", "verb"), "发布\u{4}verb");
+        // both present
+        assert_eq!(i18n.translate_ctx("发布", "verb", None), "Publish");
+        assert_eq!(i18n.translate("发布", None), "Post");
+        // no contextual entry: the plain one
+        assert_eq!(i18n.translate_ctx("发布", "noun", None), "Post");
+        assert_eq!(
+            i18n.translate_ctx("发布", "noun", Some("blog".to_string())),
+            "Article"
+        );
+        // neither: the plain key, without its context
+        assert_eq!(i18n.translate_ctx("删除", "verb", None), "删除");
+        assert_eq!(
+            i18n.trans_with_inter_ctx("发布于%1", "date", vec!["May 1".into()], None),
+            "Posted on May 1"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_ctx("发布于%1", "time", vec!["9:00".into()], None),
+            "发布于9:00"
+        );
+
+        *I18N.lock().unwrap() = i18n;
+        assert_eq!(i18n!("发布", ctx = "verb"), "Publish");
+        assert_eq!(i18n!("发布", ns = "blog", ctx = "noun"), "Article");
+        assert_eq!(i18n!("发布于%1", ctx = "date", "May 1"), "Posted on May 1");
+        assert_eq!(i18n!("发布", ctx = "adjective"), "Post");
+        *I18N.lock().unwrap() = I18n::new("cn");
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn preview() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_override(
+            "en",
+            "common",
+            "成绩",
+            "Chinese: %1, math: %{2:upper}, all: %0",
+        );
+        i18n.set_override(
+            "en",
+            "common",
+            "进度",
+            "%1 {file|files}, 100%%%{2?, %2 left}",
+        );
+        assert_eq!(
+            i18n.preview("成绩", None),
+            "Chinese: [1], math: [2:upper], all: [0]"
+        );
+        assert_eq!(
+            i18n.preview("进度", None),
+            "[1] {file|files}, 100%%{2?, [2] left}"
+        );
+        assert_eq!(i18n.preview("这是一个测试", None), "This is a test");
+        // a missing key is previewed as is
+        assert_eq!(i18n.preview("缺少%1", None), "缺少[1]");
+        i18n.insert_source(
+            "de",
+            source("common", &[("成绩", "Deutsch: {0}, {{x}} {1:upper}")]),
+        );
+        i18n.set_placeholder_style_for("de", PlaceholderStyle::Brace);
+        i18n.set_lang("de");
+        assert_eq!(i18n.preview("成绩", None), "Deutsch: [0], {x} [1:upper]");
+    }
+
+    #[test]
+    fn trans_with_inter_opt() {
+        let mut i18n = I18n::new("en");
+        i18n.set_override("en", "common", "姓名", "%1 %2 %3");
+        i18n.set_override("en", "common", "全名", "%1%{2? %2} %3");
+        let name = |middle: Option<&str>| {
+            vec![
+                Some("Ada".to_string()),
+                middle.map(str::to_string),
+                Some("King".to_string()),
+            ]
+        };
+        assert_eq!(
+            i18n.trans_with_inter_opt("姓名", name(Some("L.")), None),
+            "Ada L. King"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_opt("姓名", name(None), None),
+            "Ada  King"
+        );
+        // the section around an absent value goes with it
+        assert_eq!(
+            i18n.trans_with_inter_opt("全名", name(None), None),
+            "Ada King"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_opt("全名", name(Some("L.")), None),
+            "Ada L. King"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_opt("%0", name(None), None),
+            "Ada, King"
+        );
+        // a `None` follows the policy for its index only, like a value past the end
+        i18n.set_missing_arg(MissingArg::Keep);
+        assert_eq!(
+            i18n.trans_with_inter_opt("姓名", name(None), None),
+            "Ada %2 King"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_opt("姓名", vec![Some("Ada".into()), None], None),
+            "Ada %2 %3"
+        );
+        assert_eq!(i18n.trans_with_inter_opt("%1", vec![None], None), "%1");
+        i18n.set_missing_arg(MissingArg::Empty);
+        assert_eq!(i18n.trans_with_inter_opt("[%1]", vec![None], None), "[]");
+    }
+
+    #[test]
+    fn optional_values() {
+        let _guard = GLOBAL.lock().unwrap();
+        let mut i18n = I18n::new("en");
+        let name = |middle: Option<&str>| vec!["Ada".to_string(), Opt(middle).to_string()];
+        // a None follows the missing-arg policy
+        assert_eq!(
+            i18n.trans_with_inter("%1 %2", name(Some("King")), None),
+            "Ada King"
+        );
+        assert_eq!(i18n.trans_with_inter("%1 %2", name(None), None), "Ada ");
+        assert_eq!(i18n.trans_with_inter("%0", name(None), None), "Ada");
+        // a conditional section is dropped whole
+        let section = "%1%{2?（%{2:upper}）}";
+        assert_eq!(
+            i18n.trans_with_inter(section, name(Some("king")), None),
+            "Ada（KING）"
+        );
+        assert_eq!(i18n.trans_with_inter(section, name(None), None), "Ada");
+        assert_eq!(
+            i18n.trans_with_inter("%{3?，%3}", name(Some("x")), None),
+            ""
+        );
+        i18n.set_missing_arg(MissingArg::Keep);
+        assert_eq!(i18n.trans_with_inter("%1 %2", name(None), None), "Ada %2");
+        assert_eq!(i18n.trans_with_inter(section, name(None), None), "Ada");
+        // compiled values and the macro
+        i18n.set_override("en", "common", "总价", "%1 元%{2?，优惠 %2 元}");
+        assert_eq!(
+            i18n.trans_with_inter("总价", vec!["10".into(), Opt(Some(2)).to_string()], None),
+            "10 元，优惠 2 元"
+        );
+        assert_eq!(
+            i18n.trans_with_inter("总价", vec!["10".into(), Opt::<u8>(None).to_string()], None),
+            "10 元"
+        );
+        // without values, sections are kept as written
+        assert_eq!(i18n.translate("总价", None), "%1 元%{2?，优惠 %2 元}");
+        *I18N.lock().unwrap() = i18n;
+        let discount: Option<u32> = None;
+        assert_eq!(i18n!("总价", 10, Opt(discount)), "10 元");
+        *I18N.lock().unwrap() = I18n::new("cn");
+    }
+
+    #[test]
+    fn join_all_values() {
+        let mut i18n = I18n::new("en");
+        let vals = |vals: &[&str]| vals.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        // without values, even `%0` is kept as written
+        assert_eq!(
+            i18n.trans_with_inter("values: [%0]", vals(&[]), None),
+            "values: [%0]"
+        );
+        assert_eq!(
+            i18n.trans_with_inter("values: %0", vals(&["a"]), None),
+            "values: a"
+        );
+        assert_eq!(
+            i18n.trans_with_inter("values: %0 (first %1)", vals(&["a", "b", "c"]), None),
+            "values: a, b, c (first a)"
+        );
+        i18n.set_join_separator(" / ");
+        assert_eq!(
+            i18n.trans_with_inter("%{0:upper}", vals(&["a", "b"]), None),
+            "A / B"
+        );
+    }
+
+    #[test]
+    fn case_modifiers() {
+        let mut i18n = I18n::new("tr");
+        i18n.source.insert(
+            "TR".to_string(),
+            source("common", &[("欢迎，%1", "Hoş geldin, %{1:upper}!")]),
+        );
+        assert_eq!(
+            i18n.trans_with_inter("欢迎，%1", vec!["istanbul".to_string()], None),
+            "Hoş geldin, İSTANBUL!"
+        );
+        i18n.set_lang("en");
+        assert_eq!(
+            i18n.trans_with_inter(
+                "%{1:title} %{2:lower} %{1}",
+                vec!["jane doe".to_string(), "HI".to_string()],
+                None
+            ),
+            "Jane Doe hi jane doe"
+        );
+    }
+
+    #[test]
+    fn write_into_sink() {
+        let mut i18n = I18n::new("de");
+        i18n.source.insert(
+            "DE".to_string(),
+            source(
+                "common",
+                &[
+                    ("这是一个测试", "Das ist ein Test"),
+                    ("得分：%1", "Punkte: %{1:upper} (%0)"),
+                ],
+            ),
+        );
+        let mut out = String::from("> ");
+        i18n.translate_into(&mut out, "这是一个测试", None).unwrap();
+        i18n.translate_into(&mut out, " | untranslated", None)
+            .unwrap();
+        i18n.trans_with_inter_into(&mut out, " %2", &[&1, &2.5], None)
+            .unwrap();
+        assert_eq!(out, "> Das ist ein Test | untranslated 2.5");
+
+        out.clear();
+        i18n.trans_with_inter_into(&mut out, "得分：%1", &[&"a", &7], None)
+            .unwrap();
+        assert_eq!(out, "Punkte: A (a, 7)");
+        assert_eq!(
+            i18n.trans_with_inter("得分：%1", vec!["a".to_string(), "7".to_string()], None),
+            out
+        );
+    }
+
+    #[test]
+    fn stable_export() {
+        let dir = temp_path("easy_i18n_export");
+        let _ = fs::remove_dir_all(&dir);
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.export_to_dir(&dir).unwrap();
+        let first = fs::read_to_string(dir.join("en.json")).unwrap();
+
+        // same data, fresh maps and a re-export over the previous files
+        let mut again = I18n::new("en");
+        again.set_source(&dir);
+        again.export_to_dir(&dir).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("en.json")).unwrap(), first);
+        assert!(dir.join("de.json").exists());
+
+        // serde_json's own maps are sorted, so a sorted document survives a round trip as is
+        let value: serde_json::Value = serde_json::from_str(&first).unwrap();
+        assert_eq!(serde_json::to_string_pretty(&value).unwrap() + "\n", first);
+    }
+
+    #[test]
+    fn runtime_args() {
+        let _guard = GLOBAL.lock().unwrap();
+        let reasons = vec!["timeout".to_string(), "refused".to_string()];
+        let none: Vec<String> = vec![];
+        assert_eq!(
+            i18n_args![1, ..&reasons, 'x'],
+            ["1", "timeout", "refused", "x"]
+        );
+        assert_eq!(i18n_args![..none.iter(), ..1..3], ["1", "2"]);
+        assert!(i18n_args![].is_empty());
+
+        assert_eq!(
+            t_args("%1 / %2 [%0]", None, &i18n_args!["a", ..&reasons]),
+            "a / timeout [a, timeout, refused]"
+        );
+        assert_eq!(t_args("%1 and %2", None, &["a"]), "a and ");
+        assert_eq!(t_args("%1 and %2", None, &none), " and ");
+        set_missing_arg(MissingArg::Keep);
+        assert_eq!(
+            t_args("%1 and %{2:upper}", None, &["a"]),
+            "a and %{2:upper}"
+        );
+        assert_eq!(t_args("[%1] [%0]", None, &none), "[%1] []");
+        set_missing_arg(MissingArg::Empty);
+    }
+
+    #[test]
+    fn case_follows_the_context() {
+        let _tr = I18nContext::new("tr").enter();
+        assert_eq!(to_upper("istanbul"), "İSTANBUL");
+        assert_eq!(to_lower("ISPARTA"), "ısparta");
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn concurrent_metrics() {
+        let _guard = GLOBAL.lock().unwrap();
+        let previous = current_lang();
+        // a language no other test uses, so that parallel tests don't disturb its counters
+        I18N.lock()
+            .unwrap()
+            .set_override("xm", DEFAULT_NS, "命中", "Hit");
+        set_lang("xm");
+        let before = metrics();
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    for i in 0..500 {
+                        assert_eq!(i18n!("命中"), "Hit");
+                        assert_eq!(i18n!("缺少 %1", i), format!("缺少 {i}"));
+                    }
+                })
+            })
+            .collect();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        let after = metrics();
+        let expected = LangMetrics {
+            lookups: 8000,
+            misses: 4000,
+        };
+        assert_eq!(
+            after.langs["XM"].lookups - before.langs.get("XM").map_or(0, |m| m.lookups),
+            expected.lookups
+        );
+        assert_eq!(
+            after.langs["XM"].misses - before.langs.get("XM").map_or(0, |m| m.misses),
+            expected.misses
+        );
+        assert!(after.lookups - before.lookups >= expected.lookups);
+        assert!(after.misses - before.misses >= expected.misses);
+
+        // a context counts against its own language
+        let before = metrics().langs.get("XN").map_or(0, |m| m.lookups);
+        with_lang("xn", || i18n!("命中"));
+        assert_eq!(metrics().langs["XN"].lookups - before, 1);
+
+        reset_metrics();
+        assert!(!metrics().langs.contains_key("XM"));
+        I18N.lock()
+            .unwrap()
+            .clear_override("xm", DEFAULT_NS, "命中");
+        set_lang(&previous);
+    }
+
+    #[test]
+    fn compiled_templates() {
+        let templates = [
+            "Hi %{1:upper}, %2 of %300 [%0] %{0:title}!",
+            "%1%1%3",
+            "%2 {file|files} in %1",
+            "plain",
+            "",
+        ];
+        let vals = vec!["ab cd".to_string(), "2".to_string()];
+        for missing_arg in [MissingArg::Empty, MissingArg::Keep] {
+            let mut i18n = I18n::new("en");
+            i18n.set_missing_arg(missing_arg);
+            for template in templates {
+                i18n.set_override("en", DEFAULT_NS, "模板", template);
+                // a miss interpolates the raw text, without the compiled template
+                let expected = i18n.trans_with_inter(template, vals.clone(), Some("缺少".into()));
+                assert_eq!(i18n.trans_with_inter("模板", vals.clone(), None), expected);
+                let mut out = String::new();
+                i18n.trans_with_inter_into(&mut out, "模板", &[&"ab cd", &2], None)
+                    .unwrap();
+                assert_eq!(out, expected);
+            }
+        }
+        let mut i18n = I18n::new("en");
+        i18n.set_override("en", DEFAULT_NS, "模板", "%1!");
+        i18n.set_interpolation_enabled(false);
+        assert_eq!(i18n.trans_with_inter("模板", vals, None), "%1!");
+    }
+
+    #[test]
+    fn debug_keys() {
+        let _guard = GLOBAL.lock().unwrap();
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_debug_keys(true);
+        assert_eq!(
+            i18n.translate("这是一个测试", None),
+            "«common:这是一个测试» This is a test"
+        );
+        assert_eq!(
+            i18n.trans_with_inter(
+                "他的成绩是，语文：%1, 数学：%2",
+                vec!["1".into(), "2".into()],
+                None
+            ),
+            "«common:他的成绩是，语文：%1, 数学：%2» His grades are Chinese: 1, Mathematics: 2"
+        );
+        // a miss names the requested namespace, and its raw text is still interpolated
+        assert_eq!(
+            i18n.trans_with_inter("缺少 %1", vec!["x".into()], Some("checkout".into())),
+            "«checkout:缺少 %1» 缺少 x"
+        );
+        let mut out = String::new();
+        i18n.translate_into(&mut out, "这是一个测试", None).unwrap();
+        assert_eq!(out, "«common:这是一个测试» This is a test");
+        assert!(i18n.has_translation("这是一个测试", None));
+        assert_eq!(
+            i18n.translate_opt("这是一个测试", None).as_deref(),
+            Some("This is a test")
+        );
+        i18n.set_debug_keys(false);
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+
+        set_debug_keys(true);
+        let annotated = t_args("缺少 %1", Some("checkout"), &["x"]);
+        set_debug_keys(false);
+        assert_eq!(annotated, "«checkout:缺少 %1» 缺少 x");
+    }
+
+    #[test]
+    fn in_memory_catalogs() {
+        let mut i18n = I18n::new("fr");
+        let source =
+            Source::from_static(r#"{"common": {"保存": "Garder", "保存": "Enregistrer"}}"#)
+                .unwrap();
+        i18n.insert_source("fr", source);
+        assert_eq!(i18n.translate("保存", None), "Enregistrer");
+        assert_eq!(
+            i18n.load_warnings(),
+            [LoadWarning {
+                lang: "FR".to_string(),
+                file: None,
+                namespace: DEFAULT_NS.to_string(),
+                kind: LoadWarningKind::DuplicateKey {
+                    key: "保存".to_string()
+                },
+            }]
+        );
+        let err = Source::from_static("{").unwrap_err();
+        assert_eq!(err.to_string(), "[source error]: embedded catalog");
+    }
+
+    #[test]
+    fn named_values() {
+        let mut i18n = I18n::new("en");
+        i18n.source.insert(
+            "EN".to_string(),
+            source(
+                "common",
+                &[("你好，%{name}", "Hello, %{name:title}! %{count} new (%1)")],
+            ),
+        );
+        let vals = HashMap::from([
+            ("name", "jane doe".to_string()),
+            ("count", "3".to_string()),
+            ("unused", "x".to_string()),
+        ]);
+        assert_eq!(
+            i18n.trans_with_map("你好，%{name}", &vals, None),
+            "Hello, Jane Doe! 3 new (%1)"
+        );
+        let vals = HashMap::from([("name", "jane".to_string())]);
+        assert_eq!(
+            i18n.trans_with_map("你好，%{name}", &vals, None),
+            "Hello, Jane!  new (%1)"
+        );
+        i18n.set_missing_arg(MissingArg::Keep);
+        assert_eq!(
+            i18n.trans_with_map("你好，%{name}", &vals, None),
+            "Hello, Jane! %{count} new (%1)"
+        );
+    }
+
+    #[test]
+    fn change_subscriptions() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        let _guard = GLOBAL.lock().unwrap();
+        set_lang("en");
+        set_source(Path::new("./source"));
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        let log = seen.clone();
+        // translating from a callback must not deadlock
+        let translating = on_lang_change(move |old, new| {
+            let text = i18n!("这是一个测试");
+            log.lock().unwrap().push(format!("{old}->{new}: {text}"));
+        });
+        let panicking = on_lang_change(|_, _| panic!("subscriber bug"));
+        let once = Arc::new(Mutex::new(None));
+        let once_id = once.clone();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let id = on_lang_change(move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            if let Some(id) = once_id.lock().unwrap().take() {
+                assert!(unsubscribe(id));
+            }
+        });
+        *once.lock().unwrap() = Some(id);
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let reloaded = reloads.clone();
+        let source_sub = on_source_change(move || {
+            reloaded.fetch_add(1, Ordering::SeqCst);
+        });
+
+        set_lang("de");
+        set_lang("DE"); // unchanged, no notification
+        set_lang("en");
+        set_source(Path::new("./source"));
+        try_set_source(Path::new("./source")).unwrap();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            ["EN->DE: Das ist ein Test", "DE->EN: This is a test"]
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(reloads.load(Ordering::SeqCst), 2);
+        assert!(!I18N.is_poisoned());
+
+        for id in [translating, panicking, source_sub] {
+            assert!(unsubscribe(id));
+        }
+        assert!(!unsubscribe(id));
+        set_source(Path::new("./source"));
+        assert_eq!(reloads.load(Ordering::SeqCst), 2);
+    }
+
+    /// `key = value` lines, `[namespace]` headers switch the namespace.
+    struct KvLoader;
+
+    impl SourceLoader for KvLoader {
+        fn extensions(&self) -> &[&str] {
+            &["kv"]
+        }
+
+        fn load(&self, bytes: &[u8]) -> Result<Source> {
+            let mut source = Source::default();
+            let mut ns = DEFAULT_NS.to_string();
+            for line in std::str::from_utf8(bytes)?.lines().map(str::trim) {
+                if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                    ns = name.to_string();
+                } else if let Some((key, value)) = line.split_once('=') {
+                    let entries = source.namespaces.entry(ns.clone()).or_default();
+                    entries.insert(key.trim().to_string(), Entry::from(value.trim()));
+                } else if !line.is_empty() {
+                    bail!("not a `key = value` line: {line}");
+                }
+            }
+            Ok(source)
+        }
+    }
+
+    #[test]
+    fn custom_loader() {
+        let dir = temp_path("easy_i18n_kv");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("en.json"), r#"{"common": {"保存": "Save"}}"#).unwrap();
+        fs::write(
+            dir.join("fr.kv"),
+            "保存 = Enregistrer\n\n[dialog]\n取消 = Annuler\n",
+        )
+        .unwrap();
+        fs::write(dir.join("de.kv"), "kaputt").unwrap();
+
+        let mut i18n = I18n::new("fr");
+        i18n.set_source(&dir);
+        assert_eq!(i18n.languages_with_key("保存", None), ["EN"]);
+
+        i18n.register_loader(KvLoader);
+        i18n.set_source(&dir);
+        assert_eq!(i18n.languages_with_key("保存", None), ["EN", "FR"]);
+        assert_eq!(
+            i18n.translate("取消", Some("dialog".to_string())),
+            "Annuler"
+        );
+        let err = i18n.try_set_source(&dir).unwrap_err().to_string();
+        assert!(
+            err.contains("de.kv not a `key = value` line: kaputt"),
+            "{err}"
+        );
+
+        i18n.set_source_lazy(&dir);
+        assert_eq!(i18n.translate("保存", None), "Enregistrer");
+
+        // the last registered loader of an extension wins, built-in ones included
+        struct Shouting;
+        impl SourceLoader for Shouting {
+            fn extensions(&self) -> &[&str] {
+                &["JSON"]
+            }
+            fn load(&self, bytes: &[u8]) -> Result<Source> {
+                let text = std::str::from_utf8(bytes)?.to_uppercase();
+                Ok(serde_json::from_str(&text)?)
+            }
+        }
+        i18n.register_loader(Shouting);
+        i18n.set_source(&dir);
+        i18n.set_lang("en");
+        assert_eq!(i18n.translate("保存", Some("COMMON".to_string())), "SAVE");
+    }
+
+    #[test]
+    fn global_load_outside_lock() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static UNLOCKED: AtomicUsize = AtomicUsize::new(0);
+        /// Parses `.probe` files as json, counting the loads during which the global was free.
+        struct Probe;
+        impl SourceLoader for Probe {
+            fn extensions(&self) -> &[&str] {
+                &["probe"]
+            }
+            fn load(&self, bytes: &[u8]) -> Result<Source> {
+                if I18N.try_lock().is_ok() {
+                    UNLOCKED.fetch_add(1, Ordering::SeqCst);
+                }
+                JsonLoader.load(bytes)
+            }
+        }
+
+        let _guard = GLOBAL.lock().unwrap();
+        let dir = temp_path("easy_i18n_probe");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("fr")).unwrap();
+        fs::write(
+            dir.join("fr.probe"),
+            r#"{"common": {"保存": "Enregistrer"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("fr/dialog.probe"),
+            r#"{"dialog": {"取消": "Annuler"}}"#,
+        )
+        .unwrap();
+        register_loader(Probe);
+
+        set_source(&dir);
+        try_set_source(&dir).unwrap();
+        set_source_recursive(&dir, &["probe"]);
+        assert_eq!(UNLOCKED.load(Ordering::SeqCst), 4);
+        assert_eq!(
+            I18N.lock()
+                .unwrap()
+                .languages_with_key("取消", Some("dialog".into())),
+            ["FR"]
+        );
+
+        // the methods, in contrast, load with the translator borrowed
+        I18N.lock().unwrap().set_source(&dir);
+        assert_eq!(UNLOCKED.load(Ordering::SeqCst), 4);
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn macro_in_context() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        set_lang("cn");
+        let en = std::thread::spawn(|| {
+            let _en = I18nContext::new("en").enter();
+            i18n!("这是一个测试")
+        });
+        assert_eq!(en.join().unwrap(), "This is a test");
+        assert_eq!(i18n!("这是一个测试"), "这是一个测试");
+        assert_eq!(current_lang(), "CN");
+    }
+
+    #[test]
+    fn standalone_source() {
+        let en = Source::from_path(Path::new("./source/en.json")).unwrap();
+        assert_eq!(en.translate("这是一个测试", None), "This is a test");
+        assert_eq!(en.translate("缺少", None), "缺少");
+        assert_eq!(
+            en.trans_with_inter(
+                "他的成绩是，语文：%1, 数学：%2",
+                vec!["1".into(), "2".into()],
+                None
+            ),
+            "His grades are Chinese: 1, Mathematics: 2"
+        );
+        assert_eq!(
+            en.trans_with_inter("缺少 %1 %2 [%0]", vec!["a".into()], Some("x".into())),
+            "缺少 a  [a]"
+        );
+    }
+
+    #[test]
+    fn positional_defaults() {
+        let mut i18n = I18n::new("en");
+        i18n.set_override("en", DEFAULT_NS, "收件人", "To %1 (%{2:upper}) via %3");
+        let vals = |vals: &[&str]| vals.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let defaults = ["someone", "n/a"];
+        assert_eq!(
+            i18n.trans_with_inter_defaults("收件人", vals(&["Ann"]), &defaults, None),
+            "To Ann (N/A) via "
+        );
+        assert_eq!(
+            i18n.trans_with_inter_defaults("收件人", vals(&["Ann", "cc", "mail"]), &defaults, None),
+            "To Ann (CC) via mail"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_defaults("%0", vec![], &defaults, None),
+            "someone, n/a"
+        );
+        i18n.set_missing_arg(MissingArg::Keep);
+        assert_eq!(
+            i18n.trans_with_inter_defaults("收件人", vec![], &defaults, None),
+            "To someone (N/A) via %3"
+        );
+    }
+
+    #[test]
+    fn translate_cache() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_translate_cache(true);
+        let misses = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = misses.clone();
+        i18n.set_missing_key_handler(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        });
+
+        let ns1 = Some("namespace1".to_string());
+        for _ in 0..3 {
+            assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+            assert_eq!(
+                i18n.translate("这是一个测试", ns1.clone()),
+                "This is a test, but it is different"
+            );
+            assert_eq!(i18n.translate("缺少", None), "缺少");
+        }
+        assert_eq!(i18n.translate_cache.len(), 2);
+        // misses are not cached
+        assert_eq!(misses.load(std::sync::atomic::Ordering::Relaxed), 3);
+
+        i18n.set_override("en", "common", "这是一个测试", "This is an experiment");
+        assert_eq!(
+            i18n.translate("这是一个测试", None),
+            "This is an experiment"
+        );
+        let _guard = I18nContext::new("de").enter();
+        assert_eq!(i18n.translate("这是一个测试", None), "Das ist ein Test");
+        drop(_guard);
+        assert_eq!(i18n.translate_cache.len(), 2);
+
+        i18n.set_lang("de");
+        assert_eq!(i18n.translate_cache.len(), 0);
+        assert_eq!(i18n.translate("这是一个测试", None), "Das ist ein Test");
+        i18n.set_source(Path::new("./source"));
+        assert_eq!(i18n.translate_cache.len(), 0);
+
+        i18n.set_translate_cache(false);
+        i18n.translate("这是一个测试", None);
+        assert_eq!(i18n.translate_cache.len(), 0);
+    }
+
+    #[test]
+    fn html_escaping() {
+        let mut i18n = I18n::new("en");
+        let entries = [
+            ("欢迎", "Welcome, <b>%1</b>!"),
+            ("重复", "%1 <i>(%1)</i>"),
+            ("部分", "<a title=\"%{1:html}\">%2</a>"),
+        ];
+        i18n.insert_source("en", source("common", &entries));
+        let evil = "<script>alert('x')</script>".to_string();
+
+        assert_eq!(
+            i18n.trans_with_inter_html("欢迎", vec![evil.clone()], None),
+            "Welcome, <b>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;</b>!"
+        );
+        // plain text stays the default
+        assert_eq!(
+            i18n.trans_with_inter("欢迎", vec!["<i>Tom</i>".into()], None),
+            "Welcome, <b><i>Tom</i></b>!"
+        );
+        // each use of a value is escaped once
+        assert_eq!(
+            i18n.trans_with_inter_html("重复", vec!["a & b".into()], None),
+            "a &amp; b <i>(a &amp; b)</i>"
+        );
+        // the modifier escapes one value, and is not applied again in html mode
+        let vals = vec![r#"say "hi""#.to_string(), "<b>ok</b>".to_string()];
+        assert_eq!(
+            i18n.trans_with_inter("部分", vals.clone(), None),
+            r#"<a title="say &quot;hi&quot;"><b>ok</b></a>"#
+        );
+        assert_eq!(
+            i18n.trans_with_inter_html("部分", vals, None),
+            r#"<a title="say &quot;hi&quot;">&lt;b&gt;ok&lt;/b&gt;</a>"#
+        );
+        assert_eq!(
+            i18n.trans_with_inter_html("<%1>", vec!["&".into()], None),
+            "<&amp;>"
+        );
+    }
+
+    #[test]
+    fn base_lang() {
+        let mut i18n = I18n::new("en");
+        i18n.insert_source(
+            "cn",
+            source(
+                "common",
+                &[("checkout.confirm", "确认"), ("cart.items", "%1 件商品")],
+            ),
+        );
+        i18n.insert_source("en", source("common", &[("checkout.confirm", "Confirm")]));
+        let misses = Arc::new(Mutex::new(vec![]));
+        let seen = misses.clone();
+        i18n.set_missing_key_handler(move |miss| {
+            seen.lock()
+                .unwrap()
+                .push((miss.lang.clone(), miss.key.clone()))
+        });
+        i18n.set_base_lang("cn");
+
+        assert_eq!(i18n.translate("checkout.confirm", None), "Confirm");
+        assert_eq!(
+            i18n.trans_with_inter("cart.items", vec!["3".into()], None),
+            "3 件商品"
+        );
+        assert_eq!(i18n.translate("checkout.pay", None), "checkout.pay");
+        assert_eq!(i18n.translate_opt("cart.items", None), None);
+        // the misses of EN are still reported
+        assert_eq!(
+            *misses.lock().unwrap(),
+            [
+                ("EN".to_string(), "cart.items".to_string()),
+                ("EN".to_string(), "checkout.pay".to_string())
+            ]
+        );
+
+        i18n.set_translate_cache(true);
+        for _ in 0..2 {
+            assert_eq!(i18n.translate("cart.items", None), "%1 件商品");
+        }
+        assert_eq!(misses.lock().unwrap().len(), 4);
+
+        i18n.clear_base_lang();
+        assert_eq!(i18n.translate("cart.items", None), "cart.items");
+    }
+
+    #[test]
+    fn export_missing_keys() {
+        let dir = temp_path("easy_i18n_missing");
+        let _ = fs::remove_dir_all(&dir);
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.insert_source("fr", i18n.lang_source("EN").unwrap().clone());
+        let mut ja = source("common", &[("这是一个测试", "")]);
+        ja.namespaces.insert("namespace1".into(), HashMap::new());
+        i18n.insert_source("ja", ja);
+
+        let summary = i18n.export_missing("en", &dir).unwrap();
+        assert_eq!(
+            summary.missing,
+            BTreeMap::from([("DE".into(), 3), ("FR".into(), 0), ("JA".into(), 3)])
+        );
+        assert_eq!(summary.files, [dir.join("de.json"), dir.join("ja.json")]);
+        assert!(!dir.join("fr.json").exists());
+        assert_eq!(summary.behind(2), ["DE", "JA"]);
+        let de: Source =
+            serde_json::from_str(&fs::read_to_string(&summary.files[0]).unwrap()).unwrap();
+        assert_eq!(
+            de.get("他的成绩是，语文：%1, 数学：%2", "common"),
+            Some("His grades are Chinese: %1, Mathematics: %2")
+        );
+        assert_eq!(de.get("这是一个测试", "common"), None);
+
+        // a blank value is missing with the policy on
+        i18n.set_empty_as_missing(true);
+        let summary = i18n.export_missing("en", &dir).unwrap();
+        assert_eq!(summary.missing["JA"], 4);
+        assert!(i18n.export_missing("ko", &dir).is_err());
+    }
+
+    #[test]
+    fn source_sets() {
+        let dir = temp_path("easy_i18n_source_sets");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("en.json"),
+            r#"{
+                "_meta": {"placeholder_style": "brace"},
+                "common": {"这是一个测试": "Hey, it's a test!", "你好%1": "Hey {0}!"}
+            }"#,
+        )
+        .unwrap();
+        let mut i18n = I18n::new("en");
+        i18n.set_translate_cache(true);
+        i18n.set_source(Path::new("./source"));
+        i18n.add_source_set("casual", &dir);
+        assert_eq!(i18n.active_source_set(), "default");
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+
+        i18n.set_active_source_set("casual").unwrap();
+        assert_eq!(i18n.translate("这是一个测试", None), "Hey, it's a test!");
+        assert_eq!(
+            i18n.trans_with_inter("你好%1", vec!["Ann".into()], None),
+            "Hey Ann!"
+        );
+        // nothing else is in the casual set
+        assert_eq!(i18n.lang_count(), 1);
+        assert!(i18n.set_active_source_set("formal").is_err());
+        assert_eq!(i18n.active_source_set(), "casual");
+
+        i18n.set_active_source_set(DEFAULT_SOURCE_SET).unwrap();
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        i18n.set_lang("de");
+        assert_eq!(i18n.translate("这是一个测试", None), "Das ist ein Test");
+
+        // only the active set reports its warnings, those of its last load
+        i18n.add_source_set("duplicates", Path::new("./fixtures/duplicates"));
+        assert!(i18n.load_warnings().is_empty());
+        i18n.set_active_source_set("duplicates").unwrap();
+        i18n.add_source_set("duplicates", Path::new("./fixtures/duplicates"));
+        let warnings = i18n.load_warnings().to_vec();
+        assert!(!warnings.is_empty());
+        i18n.add_source_set("duplicates", Path::new("./fixtures/duplicates"));
+        assert_eq!(i18n.load_warnings(), warnings);
+    }
+
+    #[test]
+    fn pseudo_localization() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_pseudo(true);
+        i18n.set_pseudo_expansion(1.0);
+        assert_eq!(i18n.translate("这是一个测试", None), "⟦Ŧħīş īş ȧ ŧḗşŧ⟧");
+        // values are filled in untouched
+        assert_eq!(
+            i18n.trans_with_inter(
+                "他的成绩是，语文：%1, 数学：%2",
+                vec!["88".into(), "ab".into()],
+                None
+            ),
+            "⟦Ħīş ɠřȧḓḗş ȧřḗ Ƈħīƞḗşḗ: 88, Ḿȧŧħḗḿȧŧīƈş: ab⟧"
+        );
+        let mut out = String::new();
+        i18n.trans_with_inter_into(&mut out, "他的成绩是，语文：%1, 数学：%2", &[&1, &2], None)
+            .unwrap();
+        assert_eq!(out, "⟦Ħīş ɠřȧḓḗş ȧřḗ Ƈħīƞḗşḗ: 1, Ḿȧŧħḗḿȧŧīƈş: 2⟧");
+        // misses are wrapped too, so hardcoded strings stand out
+        assert_eq!(i18n.translate("Cancel", None), "⟦Ƈȧƞƈḗŀ⟧");
+        assert_eq!(
+            i18n.trans_with_map(
+                "Hi %{name}",
+                &HashMap::from([("name", "jo".to_string())]),
+                None
+            ),
+            "⟦Ħī jo⟧"
+        );
+        i18n.set_pseudo_expansion(2.0);
+        assert_eq!(i18n.translate("Ok", None), "⟦Ǿķ~~⟧");
+        assert!(i18n.has_translation("这是一个测试", None));
+        i18n.set_pseudo(false);
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+
+        // the global calls with runtime arguments too
+        let _guard = GLOBAL.lock().unwrap();
+        let lang = current_lang();
+        I18N.lock()
+            .unwrap()
+            .set_override(&lang, DEFAULT_NS, "伪本地化 %1", "Pseudo %1");
+        set_pseudo(true);
+        let hit = t_args("伪本地化 %1", None, &["ab"]);
+        let miss = t_args("Save %1", None, &["cd"]);
+        set_pseudo(false);
+        assert_eq!(hit, "⟦Ƥşḗũḓǿ ab~~⟧");
+        assert_eq!(miss, "⟦Şȧṽḗ cd~⟧");
+    }
+
+    #[test]
+    fn checked_interpolation() {
+        let mut i18n = I18n::new("en");
+        i18n.source.insert(
+            "EN".to_string(),
+            source(
+                "common",
+                &[("订单%1已发货", "Order %1 shipped to %{2:title}")],
+            ),
+        );
+        let vals = |vals: &[&str]| vals.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            i18n.trans_with_inter_checked("订单%1已发货", vals(&["#7", "jane"]), None),
+            Ok("Order #7 shipped to Jane".to_string())
+        );
+        let err = i18n
+            .trans_with_inter_checked("订单%1已发货", vals(&["#7"]), None)
+            .unwrap_err();
+        assert_eq!(err.missing, [2]);
+        assert_eq!(err.to_string(), "no value for %2");
+        let err = i18n
+            .trans_with_inter_checked("%3 %1 %3", vals(&["a", "b"]), None)
+            .unwrap_err();
+        assert_eq!(
+            (err.missing.as_slice(), err.unused.as_slice()),
+            (&[3][..], &[2][..])
+        );
+        assert_eq!(err.to_string(), "no value for %3; no placeholder uses %2");
+        let err = i18n
+            .trans_with_inter_checked("%1 %99999999999999999999", vals(&["a"]), None)
+            .unwrap_err();
+        assert!(err.missing.is_empty() && err.unused.is_empty());
+        assert_eq!(err.invalid, ["%99999999999999999999"]);
+        assert_eq!(err.to_string(), "invalid index in %99999999999999999999");
+        assert!(i18n
+            .trans_with_inter_checked("%0", vals(&["a", "b"]), None)
+            .is_ok());
+    }
+
+    #[test]
+    fn reverse_lookups() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        let ns1 = |key: &str| ("namespace1".to_string(), key.to_string());
+        let common = |key: &str| ("common".to_string(), key.to_string());
+
+        assert_eq!(
+            i18n.reverse_lookup("This is a test", "en"),
+            [common("这是一个测试")]
+        );
+        assert_eq!(
+            i18n.reverse_lookup_contains("but it is different", "en"),
+            [ns1("这是一个测试")]
+        );
+        assert!(i18n.reverse_lookup("This is a", "en").is_empty());
+        assert!(i18n.reverse_lookup("This is a test", "fr").is_empty());
+        assert_eq!(
+            i18n.reverse_lookup_contains("grades", "EN"),
+            [
+                common("他的成绩是，语文：%1, 数学：%2"),
+                ns1("他的成绩是，语文：%1, 数学：%2")
+            ]
+        );
+
+        let grades = [common("他的成绩是，语文：%1, 数学：%2")];
+        assert_eq!(
+            i18n.reverse_lookup_fuzzy("His grades are Chinese: 88, Mathematics: 100", "en"),
+            grades
+        );
+        // a wildcard may match an empty value but never the literal text around it
+        assert_eq!(
+            i18n.reverse_lookup_fuzzy("His grades are Chinese: , Mathematics: ", "en"),
+            grades
+        );
+        assert!(i18n
+            .reverse_lookup_fuzzy("His grades are Chinese: 88", "en")
+            .is_empty());
+        assert!(i18n
+            .reverse_lookup_fuzzy("Her grades are Chinese: 88, Mathematics: 1", "en")
+            .is_empty());
+
+        i18n.source.insert(
+            "FR".to_string(),
+            source(
+                "common",
+                &[("订单", "Commande %{id} (%{2:upper}) [x]"), ("点", "a.b*c")],
+            ),
+        );
+        assert_eq!(
+            i18n.reverse_lookup_fuzzy("Commande 42 (DHL) [x]", "fr"),
+            [common("订单")]
+        );
+        assert!(i18n
+            .reverse_lookup_fuzzy("Commande 42 (DHL) x", "fr")
+            .is_empty());
+        assert_eq!(i18n.reverse_lookup_fuzzy("a.b*c", "fr"), [common("点")]);
+        assert!(i18n.reverse_lookup_fuzzy("aXbbc", "fr").is_empty());
+
+        i18n.source.insert(
+            "DE".to_string(),
+            source("common", &[("进度", "{0} zu 100%"), ("集合", "{{{0}}}")]),
+        );
+        i18n.set_placeholder_style_for("de", PlaceholderStyle::Brace);
+        assert_eq!(
+            i18n.reverse_lookup_fuzzy("42 zu 100%", "de"),
+            [common("进度")]
+        );
+        assert_eq!(i18n.reverse_lookup_fuzzy("{a, b}", "de"), [common("集合")]);
+        assert!(i18n.reverse_lookup_fuzzy("a, b", "de").is_empty());
+    }
+
+    #[test]
+    fn plural_macro() {
+        let _guard = GLOBAL.lock().unwrap();
+        let inbox = source(
+            "common",
+            &[
+                ("你有%1条消息", "You have %1 {message|messages}"),
+                ("%2有%1个文件", "%2 has %1 {file|files} in the inbox"),
+            ],
+        );
+        let inbox = Source {
+            namespaces: HashMap::from([("inbox".to_string(), inbox.namespaces["common"].clone())]),
+            ..Default::default()
+        };
+        let common = source(
+            "common",
+            &[
+                ("你有%1条消息", "%1 {message|messages}"),
+                ("%2有%1个文件", "%2 has %1 {file|files}"),
+            ],
+        );
+        let mut en = common;
+        en.merge(inbox);
+        I18N.lock().unwrap().insert_source("en", en);
+        set_lang("en");
+
+        let key = "你有%1条消息";
+        assert_eq!(i18n!(plural key, 1), "1 message");
+        assert_eq!(i18n!(plural key, 2,), "2 messages");
+        assert_eq!(i18n!(plural key, 1, ns = "inbox"), "You have 1 message");
+        assert_eq!(i18n!(plural key, ns = "inbox", 5), "You have 5 messages");
+        // the count drives the plural whatever the other values
+        assert_eq!(i18n!(plural "%2有%1个文件", 1, 3), "3 has 1 file");
+        assert_eq!(
+            i18n!(plural "%2有%1个文件", 4, ns = "inbox", "Jane"),
+            "Jane has 4 files in the inbox"
+        );
+        assert_eq!(i18n!(plural key.to_string(), 1), "1 message");
+        // the other arms are unchanged
+        assert_eq!(i18n!(key, 1), "1 message");
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn macro_arguments_before_lock() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        // an argument taking the write lock would deadlock if evaluated under the read guard
+        let switch = |lang: &str, value: &str| {
+            set_lang(lang);
+            value.to_string()
+        };
+        assert_eq!(i18n!("%1", switch("en", "x")), "x");
+        assert_eq!(
+            i18n!("这是一个测试", ns = switch("en", "namespace1")),
+            "This is a test, but it is different"
+        );
+        assert_eq!(i18n!(switch("de", "这是一个测试")), "Das ist ein Test");
+        assert_eq!(i18n!(plural "%1 %2", 1, ns = "x", switch("en", "y")), "1 y");
+        assert_eq!(i18n!("%a", a = switch("en", "z")), "z");
+        assert_eq!(i18n_html!("<%1>", switch("en", "<b>")), "<&lt;b&gt;>");
+        let mut out = String::new();
+        i18n_write!(out, "%1", switch("de", "w")).unwrap();
+        assert_eq!(out, "w");
+        assert_eq!(
+            i18n_opt!(switch("en", "这是一个测试")).unwrap(),
+            "This is a test"
+        );
+        set_lang("cn");
+    }
+
+    #[test]
+    fn poisoned_global() {
+        let _guard = GLOBAL.lock().unwrap();
+        set_source(Path::new("./source"));
+        set_lang("en");
+        let panicked = std::thread::spawn(|| {
+            let _i18n = I18N.write().unwrap();
+            panic!("a bug while holding the translator");
+        })
+        .join();
+        assert!(panicked.is_err());
+        assert!(I18N.is_poisoned());
+        let translated = std::thread::spawn(|| i18n!("这是一个测试")).join().unwrap();
+        assert_eq!(translated, "This is a test");
+        assert!(!I18N.is_poisoned());
+        set_lang("de");
+        assert_eq!(i18n!("这是一个测试"), "Das ist ein Test");
+        assert!(I18N.try_lock().is_ok());
+    }
+
+    #[test]
+    fn keyword_macro() {
+        let _guard = GLOBAL.lock().unwrap();
+        let scores = "他的成绩是，语文：%chinese, 数学：%math分";
+        let mut en = source(
+            "common",
+            &[(scores, "Scores: Chinese %chinese, maths %{math:upper}")],
+        );
+        en.merge(Source::from_map(HashMap::from([(
+            "report".to_string(),
+            HashMap::from([(scores.to_string(), "Report: %math/%chinese".to_string())]),
+        )])));
+        I18N.lock().unwrap().insert_source("en", en);
+        set_lang("en");
+
+        assert_eq!(
+            i18n!(scores, chinese = 88, math = "a+"),
+            "Scores: Chinese 88, maths A+"
+        );
+        assert_eq!(
+            i18n!(scores, ns = "report", math = 100, chinese = 88,),
+            "Report: 100/88"
+        );
+        // untranslated, names end at the first non-ASCII character
+        assert_eq!(
+            i18n!("%name的%count个文件", name = "Jane", count = 3),
+            "Jane的3个文件"
+        );
+        // positional arms are unchanged
+        let math = 100;
+        assert_eq!(i18n!("%1 / %2", math, math == 100), "100 / true");
+        assert_eq!(i18n!(scores, ns = "report", math), "Report: %math/%chinese");
+        // a trailing comma after the namespace is not a keyword value named `ns`
+        assert_eq!(i18n!(scores, ns = "report",), "Report: %math/%chinese");
+        assert_eq!(
+            i18n_opt!(scores, ns = "report",).unwrap(),
+            "Report: %math/%chinese"
+        );
+        // a missing name follows the policy, `%1` is untouched
+        let vals = HashMap::from([("math", "100".to_string())]);
+        assert_eq!(
+            I18N.trans_with_named("%math %chinese %1", &vals, None),
+            "100  %1"
+        );
+        set_source(Path::new("./source"));
+    }
+
+    #[test]
+    fn inline_plurals() {
+        let i18n = I18n::new("en");
+        let inter = |template: &str, vals: &[&str]| {
+            let vals = vals.iter().map(|v| v.to_string()).collect();
+            i18n.trans_with_inter(template, vals, None)
+        };
+        let template = "%1 new {message|messages}";
+        assert_eq!(inter(template, &["0"]), "0 new messages");
+        assert_eq!(inter(template, &["1"]), "1 new message");
+        assert_eq!(inter(template, &["7"]), "7 new messages");
+        assert_eq!(inter(template, &["1.5"]), "1.5 new messages");
+        // the first numeric value drives the selection
+        assert_eq!(inter("%1: %2 {file|files}", &["Jane", "1"]), "Jane: 1 file");
+        assert_eq!(
+            inter("%1 {reply|replies} in %2 {thread|threads}", &["3", "1"]),
+            "3 replies in 1 threads"
+        );
+        assert_eq!(
+            inter(
+                "%1 {%1:reply|replies} in %2 {%2:thread|threads}",
+                &["3", "1"]
+            ),
+            "3 replies in 1 thread"
+        );
+        // nothing to count with: plural
+        assert_eq!(inter("{a|b} %1", &["x"]), "b x");
+        assert_eq!(inter("{%3:a|b}", &["1"]), "b");
+        assert_eq!(inter("%{1:upper}", &["a|b"]), "A|B");
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn log_events() {
+        struct Capture;
+        static RECORDS: Mutex<Vec<(log::Level, String)>> = Mutex::new(vec![]);
+        impl log::Log for Capture {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+            fn log(&self, record: &log::Record) {
+                let entry = (record.level(), record.args().to_string());
+                RECORDS.lock().unwrap().push(entry);
+            }
+            fn flush(&self) {}
+        }
+        let _ = log::set_logger(&Capture);
+        log::set_max_level(log::LevelFilter::Trace);
+        // other tests log too, only look at what mentions this test's files and keys
+        let records = |needle: &str| {
+            RECORDS
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(_, msg)| msg.contains(needle))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let dir = temp_path("easy_i18n_log");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("en.json"),
+            r#"{"common": {"a": "A", "b": "B"}, "x": {"a": "A"}}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("fr.json"), "{ not json").unwrap();
+        let mut i18n = I18n::new("en");
+        i18n.set_source(&dir);
+        let fr = records(&dir.join("fr.json").display().to_string());
+        assert_eq!(fr.len(), 1);
+        assert_eq!(fr[0].0, log::Level::Warn);
+        assert!(fr[0].1.starts_with("skipped "), "{}", fr[0].1);
+        let en = records(&dir.join("en.json").display().to_string());
+        assert_eq!(en.len(), 1);
+        assert_eq!(en[0].0, log::Level::Debug);
+        assert!(en[0].1.ends_with(": 3 keys in 2 namespaces"), "{}", en[0].1);
+
+        i18n.translate("缺少的键", None);
+        i18n.trans_with_inter("缺少的键", vec![], None);
+        i18n.translate("缺少的键", Some("x".to_string()));
+        assert_eq!(
+            records("缺少的键"),
+            [
+                (
+                    log::Level::Warn,
+                    "missing translation of `缺少的键` in namespace `common` (EN)".to_string()
+                ),
+                (
+                    log::Level::Warn,
+                    "missing translation of `缺少的键` in namespace `x` (EN)".to_string()
+                ),
+            ]
+        );
+        i18n.set_source(&dir);
+        i18n.translate("缺少的键", None);
+        assert_eq!(records("缺少的键").len(), 3);
+
+        // a namespace the language doesn't have is reported once, whatever the key
+        i18n.translate("a", Some("typo_namespace".to_string()));
+        i18n.translate("b", Some("typo_namespace".to_string()));
+        assert_eq!(
+            records("typo_namespace"),
+            [(
+                log::Level::Warn,
+                "namespace `typo_namespace` not found for lang EN".to_string()
+            )]
+        );
+        i18n.set_override("en", "typo_namespace", "c", "C");
+        i18n.translate("d", Some("typo_namespace".to_string()));
+        assert_eq!(
+            records("typo_namespace")[1].1,
+            "missing translation of `d` in namespace `typo_namespace` (EN)"
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_events() {
+        use tracing::field::{Field, Visit};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+
+        type Event = Vec<(String, String)>;
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<Event>>>);
+        struct Fields<'a>(&'a mut Vec<(String, String)>);
+        impl Visit for Fields<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0
+                    .push((field.name().to_string(), format!("{value:?}")));
+            }
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.push((field.name().to_string(), value.to_string()));
+            }
+        }
+        impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Capture {
+            fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+                let mut fields = vec![];
+                event.record(&mut Fields(&mut fields));
+                self.0.lock().unwrap().push(fields);
+            }
+        }
+
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let mut i18n = I18n::new("en");
+            i18n.set_source(Path::new("./source"));
+            i18n.translate("这是一个测试", Some("namespace1".to_string()));
+            i18n.translate("缺少", Some("checkout".to_string()));
+        });
+        let field = |name: &str, value: &str| (name.to_string(), value.to_string());
+        assert_eq!(
+            *capture.0.lock().unwrap(),
+            [vec![
+                field("message", "missing translation"),
+                field("lang", "EN"),
+                field("ns", "checkout"),
+                field("key", "缺少"),
+            ]]
+        );
+
+        // the events of a shared translator come once its lock is released
+        #[derive(Clone)]
+        struct Relock(I18nHandle, Arc<Mutex<Vec<bool>>>);
+        impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Relock {
+            fn on_event(&self, _: &tracing::Event<'_>, _: Context<'_, S>) {
+                self.1.lock().unwrap().push(self.0.try_lock().is_ok());
+            }
+        }
+        let handle = I18nHandle::new("en");
+        let relock = Relock(handle.clone(), Default::default());
+        let subscriber = tracing_subscriber::registry().with(relock.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            handle.translate("缺少", None);
+            handle.trans_with_inter("缺少 %1", vec!["x".to_string()], None);
+        });
+        assert_eq!(*relock.1.lock().unwrap(), [true, true]);
+    }
+}
+
+/// Tests of what is left without `std`; they run with `--no-default-features` too.
+#[cfg(test)]
+mod core_tests {
+    use super::*;
+
+    #[test]
+    fn static_catalogs() {
+        let mut i18n = I18n::new("en");
+        let source = Source::from_static(
+            r#"{"common": {"你好": "Hello", "你好": "Hi", "成绩：%1": "Grade: %1"},
+                "menu": {"你好": "Welcome"}}"#,
+        )
+        .unwrap();
+        i18n.insert_source("en", source);
+        assert_eq!(i18n.translate("你好", None), "Hi");
+        assert_eq!(i18n.translate("你好", Some("menu".to_string())), "Welcome");
+        assert_eq!(i18n.translate("再见", None), "再见");
+        assert_eq!(
+            i18n.trans_with_inter("成绩：%1", vec!["88".to_string()], None),
+            "Grade: 88"
+        );
+        let kinds: Vec<_> = i18n.load_warnings().iter().map(|w| &w.kind).collect();
+        assert_eq!(
+            kinds,
+            [&LoadWarningKind::DuplicateKey {
+                key: "你好".to_string()
+            }]
+        );
     }
 }
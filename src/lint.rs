@@ -0,0 +1,835 @@
+//! Static checks over the entries of a catalog.
+use crate::prelude::*;
+use crate::style::Token;
+use crate::{
+    collapse_whitespace, I18n, Namespace, PlaceholderStyle, Severity, Source, NAMED_REG,
+    SECTION_REG,
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt;
+use regex::Regex;
+use serde::Serialize;
+
+static TAG_REG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<(/?)([A-Za-z][\w-]*)(?:\s[^<>]*)?(/?)>").unwrap());
+
+/// Elements that never have a closing tag.
+const VOID_TAGS: &[&str] = &["br", "hr", "img", "input", "meta", "link", "wbr"];
+
+/// What a [`LintWarning`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// The value doesn't have the placeholders of its key. Only checked for keys holding
+    /// placeholders, as keys that are identifiers (`order.confirm`) have none to compare with.
+    PlaceholderMismatch,
+    /// The value is empty or whitespace only.
+    EmptyValue,
+    /// The key appeared more than once in the namespace of the file.
+    DuplicateKey,
+    /// Several keys differ only by whitespace or Unicode normalization, so they are (or would
+    /// be, with [`LoadOptions::normalize_whitespace`](crate::LoadOptions)) merged into one.
+    NormalizationCollision,
+    /// The value is the key itself, possibly copied over without being translated. Expected in
+    /// the language the keys are written in, hence [`Severity::Info`].
+    IdenticalToKey,
+    /// The value has a `{` or a tag that is never closed, or closes one that was never opened.
+    Unbalanced,
+}
+
+impl LintKind {
+    /// Every kind, in the order they are reported for a key.
+    pub const ALL: &'static [LintKind] = &[
+        LintKind::PlaceholderMismatch,
+        LintKind::EmptyValue,
+        LintKind::DuplicateKey,
+        LintKind::NormalizationCollision,
+        LintKind::IdenticalToKey,
+        LintKind::Unbalanced,
+    ];
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            LintKind::IdenticalToKey => Severity::Info,
+            _ => Severity::Warning,
+        }
+    }
+
+    /// The machine-readable name, as serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintKind::PlaceholderMismatch => "placeholder_mismatch",
+            LintKind::EmptyValue => "empty_value",
+            LintKind::DuplicateKey => "duplicate_key",
+            LintKind::NormalizationCollision => "normalization_collision",
+            LintKind::IdenticalToKey => "identical_to_key",
+            LintKind::Unbalanced => "unbalanced",
+        }
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which checks [`Source::lint`] runs, every one by default.
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    pub kinds: BTreeSet<LintKind>,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        LintOptions::only(LintKind::ALL)
+    }
+}
+
+impl LintOptions {
+    /// Runs the `kinds` checks and no other.
+    pub fn only(kinds: &[LintKind]) -> Self {
+        LintOptions {
+            kinds: kinds.iter().copied().collect(),
+        }
+    }
+
+    fn runs(&self, kind: LintKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+}
+
+/// A problem found in a catalog by [`Source::lint`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintWarning {
+    pub kind: LintKind,
+    pub severity: Severity,
+    pub namespace: Namespace,
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{severity}[{}] {}/{}: {}",
+            self.kind, self.namespace, self.key, self.message
+        )
+    }
+}
+
+/// A translation whose positional placeholders don't run from `%1` without a gap, found by
+/// [`I18n::check_placeholder_continuity`]. The value a skipped index stands for is silently
+/// dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlaceholderGap {
+    pub lang: String,
+    pub namespace: Namespace,
+    pub key: String,
+    /// The platform of the variant, `None` for the base value.
+    pub platform: Option<String>,
+    /// The plural category of the variant, `None` for the base value.
+    pub plural: Option<String>,
+    /// The indices used, sorted, numbered `%N` style whatever the placeholder style of the
+    /// language: `{0}` is 1.
+    pub indices: Vec<usize>,
+    /// The indices from 1 to the largest used that are not.
+    pub missing: Vec<usize>,
+}
+
+impl fmt::Display for PlaceholderGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |indices: &[usize]| {
+            let indices: Vec<String> = indices.iter().map(|i| format!("%{i}")).collect();
+            indices.join(", ")
+        };
+        write!(f, "{} {}/{}", self.lang, self.namespace, self.key)?;
+        if let Some(platform) = &self.platform {
+            write!(f, " (platform '{platform}')")?;
+        }
+        if let Some(plural) = &self.plural {
+            write!(f, " (plural '{plural}')")?;
+        }
+        write!(
+            f,
+            ": uses {} but not {}",
+            list(&self.indices),
+            list(&self.missing)
+        )
+    }
+}
+
+/// The positional indices of `value` written in `style` but `%0`, numbered `%N` style,
+/// conditional sections included.
+fn indices(value: &str, style: PlaceholderStyle) -> BTreeSet<usize> {
+    let indexed = style
+        .regex()
+        .captures_iter(value)
+        .filter_map(|caps| match style.token(&caps) {
+            Token::Placeholder(index) => index,
+            Token::Escape | Token::Rest => None,
+        });
+    let sections = SECTION_REG
+        .captures_iter(value)
+        .filter_map(|caps| caps[1].parse().ok());
+    indexed.chain(sections).filter(|i| *i > 0).collect()
+}
+
+/// A placeholder of a template, whatever the style it is written in.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Placeholder {
+    /// Numbered `%N` style, `{0}` being 1.
+    Indexed(usize),
+    Named(String),
+    Rest,
+}
+
+impl Placeholder {
+    /// The placeholder as written in `style`.
+    fn show(&self, style: PlaceholderStyle) -> String {
+        match (self, style) {
+            (Placeholder::Indexed(i), PlaceholderStyle::Percent) => format!("%{i}"),
+            (Placeholder::Indexed(i), PlaceholderStyle::Brace) => {
+                format!("{{{}}}", i.saturating_sub(1))
+            }
+            (Placeholder::Named(name), _) => format!("%{{{name}}}"),
+            (Placeholder::Rest, PlaceholderStyle::Percent) => "%*".to_string(),
+            (Placeholder::Rest, PlaceholderStyle::Brace) => "{*}".to_string(),
+        }
+    }
+}
+
+/// The placeholders of `text` written in `style`, `%{1:upper}` counted as `%1` and `{0}` as
+/// well in the brace style. Escaped delimiters are not placeholders: `%%1` has none.
+fn placeholders(text: &str, style: PlaceholderStyle) -> BTreeSet<Placeholder> {
+    let positional =
+        style
+            .regex()
+            .captures_iter(text)
+            .filter_map(|caps| match style.token(&caps) {
+                Token::Placeholder(index) => index.map(Placeholder::Indexed),
+                Token::Rest => Some(Placeholder::Rest),
+                Token::Escape => None,
+            });
+    let named = NAMED_REG
+        .captures_iter(text)
+        .map(|caps| Placeholder::Named(caps[1].to_string()));
+    positional.chain(named).collect()
+}
+
+fn list(placeholders: &BTreeSet<Placeholder>, style: PlaceholderStyle) -> String {
+    let shown: Vec<String> = placeholders.iter().map(|p| p.show(style)).collect();
+    shown.join(", ")
+}
+
+/// Compares the placeholders of `key`, written in `key_style`, with the ones of `value`, written
+/// in `style`, in which the message names them.
+fn placeholder_mismatch(
+    key: &str,
+    key_style: PlaceholderStyle,
+    value: &str,
+    style: PlaceholderStyle,
+) -> Option<String> {
+    let expected = placeholders(key, key_style);
+    if expected.is_empty() {
+        return None;
+    }
+    let found = placeholders(value, style);
+    let missing: BTreeSet<Placeholder> = expected.difference(&found).cloned().collect();
+    let extra: BTreeSet<Placeholder> = found.difference(&expected).cloned().collect();
+    let list = |placeholders: &BTreeSet<Placeholder>| list(placeholders, style);
+    match (missing.is_empty(), extra.is_empty()) {
+        (true, true) => None,
+        (false, true) => Some(format!("missing {}", list(&missing))),
+        (true, false) => Some(format!("unexpected {}", list(&extra))),
+        (false, false) => Some(format!(
+            "missing {}, unexpected {}",
+            list(&missing),
+            list(&extra)
+        )),
+    }
+}
+
+/// Describes the first `{` or tag of `value` that doesn't pair up.
+fn unbalanced(value: &str) -> Option<String> {
+    let mut depth = 0usize;
+    for c in value.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some("'}' without a matching '{'".to_string()),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return Some("'{' is never closed".to_string());
+    }
+    let mut open: Vec<String> = vec![];
+    for caps in TAG_REG.captures_iter(value) {
+        let name = caps[2].to_lowercase();
+        if !caps[3].is_empty() || VOID_TAGS.contains(&name.as_str()) {
+            continue;
+        }
+        if caps[1].is_empty() {
+            open.push(name);
+        } else if open.last() == Some(&name) {
+            open.pop();
+        } else {
+            return Some(format!("</{name}> without a matching <{name}>"));
+        }
+    }
+    open.pop().map(|name| format!("<{name}> is never closed"))
+}
+
+impl Source {
+    /// Runs the checks of `opts` over every entry, platform variants included. Warnings are
+    /// sorted by namespace, key and kind.
+    ///
+    /// Duplicate keys and normalization collisions are remembered from the file the source was
+    /// read from; a collision of keys that differ by whitespace is also found among the keys
+    /// kept apart because whitespace normalization was off.
+    ///
+    /// Values are read in the placeholder style of the `_meta` of the source, keys in the
+    /// default style; [`I18n::lint_all`] follows the styles set on the translator instead.
+    pub fn lint(&self, opts: &LintOptions) -> Vec<LintWarning> {
+        let style = self.meta.as_ref().and_then(|meta| meta.placeholder_style);
+        self.lint_in(opts, PlaceholderStyle::default(), style.unwrap_or_default())
+    }
+
+    /// [`Source::lint`] with keys written in `key_style` and values in `style`.
+    pub(crate) fn lint_in(
+        &self,
+        opts: &LintOptions,
+        key_style: PlaceholderStyle,
+        style: PlaceholderStyle,
+    ) -> Vec<LintWarning> {
+        let mut warnings = vec![];
+        let mut warn = |kind: LintKind, namespace: &str, key: &str, message: String| {
+            if opts.runs(kind) {
+                warnings.push(LintWarning {
+                    kind,
+                    severity: kind.severity(),
+                    namespace: namespace.to_string(),
+                    key: key.to_string(),
+                    message,
+                });
+            }
+        };
+        for (ns, key) in &self.duplicates {
+            let message = format!("key '{key}' appears more than once in namespace '{ns}'");
+            warn(LintKind::DuplicateKey, ns, key, message);
+        }
+        let mut collisions: BTreeSet<(Namespace, String, Vec<String>)> =
+            self.collapsed.iter().cloned().collect();
+        for (ns, entries) in &self.namespaces {
+            let mut by_collapsed: HashMap<_, Vec<&str>> = HashMap::new();
+            for key in entries.keys() {
+                by_collapsed
+                    .entry(collapse_whitespace(key))
+                    .or_default()
+                    .push(key);
+            }
+            for (collapsed, mut keys) in by_collapsed {
+                if keys.len() > 1 {
+                    keys.sort();
+                    // reported under the collapsed form when one of the keys has it
+                    let key = keys
+                        .iter()
+                        .find(|key| **key == collapsed)
+                        .unwrap_or(&keys[0]);
+                    let keys = keys.iter().map(|key| key.to_string()).collect();
+                    collisions.insert((ns.clone(), key.to_string(), keys));
+                }
+            }
+
+            for (key, entry) in entries {
+                let platforms = entry
+                    .platforms
+                    .iter()
+                    .map(|(p, v)| (format!(" (platform '{p}')"), v.as_str()));
+                // the `other` variant is the value itself
+                let plurals = entry
+                    .plurals
+                    .iter()
+                    .filter(|(_, v)| **v != entry.value)
+                    .map(|(c, v)| (format!(" (plural '{c}')"), v.as_str()));
+                let values = core::iter::once((String::new(), entry.value.as_str()))
+                    .chain(platforms)
+                    .chain(plurals);
+                for (variant, value) in values {
+                    let on = |message: String| format!("{message}{variant}");
+                    if let Some(message) = placeholder_mismatch(key, key_style, value, style) {
+                        warn(LintKind::PlaceholderMismatch, ns, key, on(message));
+                    }
+                    if value.trim().is_empty() {
+                        warn(LintKind::EmptyValue, ns, key, on("empty value".to_string()));
+                    }
+                    if value == key && key.chars().any(char::is_alphabetic) {
+                        let message = on("value is the key, is it translated?".to_string());
+                        warn(LintKind::IdenticalToKey, ns, key, message);
+                    }
+                    if let Some(message) = unbalanced(value) {
+                        warn(LintKind::Unbalanced, ns, key, on(message));
+                    }
+                }
+            }
+        }
+        for (ns, key, keys) in collisions {
+            let message = format!("keys {keys:?} differ only by whitespace or normalization");
+            warn(LintKind::NormalizationCollision, &ns, &key, message);
+        }
+        warnings
+            .sort_by(|a, b| (&a.namespace, &a.key, a.kind).cmp(&(&b.namespace, &b.key, b.kind)));
+        warnings
+    }
+}
+
+/// What a [`StringLint`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StringIssue {
+    /// A control character other than a line feed or a tab.
+    ControlCharacter,
+    /// A character that takes no room: zero-width space, word joiner or byte order mark. The
+    /// joiners needed by some scripts and emoji (U+200C, U+200D) are fine.
+    InvisibleCharacter,
+    /// A bidi embedding or override (U+202A to U+202E), which reorders the text up to the end of
+    /// the paragraph when its closing U+202C is lost.
+    BidiOverride,
+    /// Bidi isolates (U+2066 to U+2068) and closing U+2069 that don't pair up.
+    UnbalancedIsolate,
+    /// The value ends with whitespace.
+    TrailingWhitespace,
+    /// Two spaces in a row.
+    DoubleSpace,
+}
+
+/// A suspicious character found in a translation by [`I18n::lint_strings`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StringLint {
+    pub lang: String,
+    pub namespace: Namespace,
+    pub key: String,
+    /// The platform or plural category of the variant, `None` for the base value.
+    pub variant: Option<String>,
+    pub issue: StringIssue,
+    pub message: String,
+}
+
+impl fmt::Display for StringLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}/{}", self.lang, self.namespace, self.key)?;
+        if let Some(variant) = &self.variant {
+            write!(f, " ({variant})")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// The issues of `value`, with the first character concerned for each, counted in chars.
+fn string_issues(value: &str) -> Vec<(StringIssue, String)> {
+    let mut issues = vec![];
+    if value.trim().is_empty() {
+        return issues;
+    }
+    let mut found = |issue: StringIssue, message: String| {
+        if !issues.iter().any(|(i, _)| *i == issue) {
+            issues.push((issue, message));
+        }
+    };
+    let mut isolates = 0usize;
+    let mut previous = None;
+    for (i, c) in value.chars().enumerate() {
+        let code = c as u32;
+        match c {
+            '\n' | '\t' => {}
+            c if c.is_control() => {
+                found(
+                    StringIssue::ControlCharacter,
+                    format!("control character U+{code:04X} at {i}"),
+                );
+            }
+            '\u{200B}' | '\u{2060}' | '\u{FEFF}' => found(
+                StringIssue::InvisibleCharacter,
+                format!("invisible character U+{code:04X} at {i}"),
+            ),
+            '\u{202A}'..='\u{202E}' => found(
+                StringIssue::BidiOverride,
+                format!("bidi override U+{code:04X} at {i}"),
+            ),
+            '\u{2066}'..='\u{2068}' => isolates += 1,
+            '\u{2069}' if isolates == 0 => found(
+                StringIssue::UnbalancedIsolate,
+                format!("U+2069 at {i} closes no isolate"),
+            ),
+            '\u{2069}' => isolates -= 1,
+            ' ' if previous == Some(' ') => found(
+                StringIssue::DoubleSpace,
+                format!("double space at {}", i - 1),
+            ),
+            _ => {}
+        }
+        previous = Some(c);
+    }
+    if isolates > 0 {
+        found(
+            StringIssue::UnbalancedIsolate,
+            "an isolate is never closed by U+2069".to_string(),
+        );
+    }
+    if value.trim_end() != value {
+        found(
+            StringIssue::TrailingWhitespace,
+            "trailing whitespace".to_string(),
+        );
+    }
+    issues.sort();
+    issues
+}
+
+impl I18n {
+    /// [`Source::lint`] over every language, lazily registered ones included (they are parsed),
+    /// keyed by language. Languages without any warning are left out.
+    pub fn lint_all(&self, opts: &LintOptions) -> BTreeMap<String, Vec<LintWarning>> {
+        self.languages()
+            .into_iter()
+            .filter_map(|lang| {
+                let source = self.lang_source(&lang)?;
+                let warnings =
+                    source.lint_in(opts, self.placeholder_style, self.stored_style(&lang));
+                (!warnings.is_empty()).then_some((lang, warnings))
+            })
+            .collect()
+    }
+
+    /// Scans the translations of every language, lazily registered ones included, platform and
+    /// plural variants too, for characters that render wrongly or not at all, often picked up by
+    /// copy-paste: see [`StringIssue`]. Each issue is reported once per value, sorted by
+    /// language, namespace, key, variant and issue. Values made of whitespace only are left to
+    /// [`LintKind::EmptyValue`].
+    pub fn lint_strings(&self) -> Vec<StringLint> {
+        let mut lints = vec![];
+        for lang in self.languages() {
+            let Some(source) = self.lang_source(&lang) else {
+                continue;
+            };
+            for (ns, entries) in &source.namespaces {
+                for (key, entry) in entries {
+                    let platforms = entry.platforms.iter().map(|(p, v)| (Some(p), v));
+                    let plurals = entry
+                        .plurals
+                        .iter()
+                        .filter(|(_, v)| **v != entry.value)
+                        .map(|(c, v)| (Some(c), v));
+                    let values = core::iter::once((None, &entry.value))
+                        .chain(platforms)
+                        .chain(plurals);
+                    for (variant, value) in values {
+                        for (issue, message) in string_issues(value) {
+                            lints.push(StringLint {
+                                lang: lang.clone(),
+                                namespace: ns.clone(),
+                                key: key.clone(),
+                                variant: variant.cloned(),
+                                issue,
+                                message,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        lints.sort_by(|a, b| {
+            (&a.lang, &a.namespace, &a.key, &a.variant, a.issue).cmp(&(
+                &b.lang,
+                &b.namespace,
+                &b.key,
+                &b.variant,
+                b.issue,
+            ))
+        });
+        lints
+    }
+
+    /// Scans the translations of every language, lazily registered ones included, for positional
+    /// placeholders that skip an index or don't start at `%1`, e.g. `%1` and `%3` without `%2`,
+    /// or `{0}` and `{2}` in a language of the brace style. Values without positional
+    /// placeholders, or using `%0` alone, are fine. Gaps are sorted by language, namespace and
+    /// key.
+    pub fn check_placeholder_continuity(&self) -> Vec<PlaceholderGap> {
+        let mut gaps = vec![];
+        for lang in self.languages() {
+            let Some(source) = self.lang_source(&lang) else {
+                continue;
+            };
+            let style = self.stored_style(&lang);
+            for (ns, entries) in &source.namespaces {
+                for (key, entry) in entries {
+                    let platforms = entry.platforms.iter().map(|(p, v)| (Some(p), None, v));
+                    // the `other` variant is the value itself
+                    let plurals = entry
+                        .plurals
+                        .iter()
+                        .filter(|(_, v)| **v != entry.value)
+                        .map(|(c, v)| (None, Some(c), v));
+                    let values = core::iter::once((None, None, &entry.value))
+                        .chain(platforms)
+                        .chain(plurals);
+                    for (platform, plural, value) in values {
+                        let used = indices(value, style);
+                        let Some(&max) = used.last() else {
+                            continue;
+                        };
+                        let missing: Vec<usize> = (1..max).filter(|i| !used.contains(i)).collect();
+                        if !missing.is_empty() {
+                            gaps.push(PlaceholderGap {
+                                lang: lang.clone(),
+                                namespace: ns.clone(),
+                                key: key.clone(),
+                                platform: platform.cloned(),
+                                plural: plural.cloned(),
+                                indices: used.into_iter().collect(),
+                                missing,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        gaps.sort_by(|a, b| {
+            (&a.lang, &a.namespace, &a.key, &a.platform, &a.plural).cmp(&(
+                &b.lang,
+                &b.namespace,
+                &b.key,
+                &b.platform,
+                &b.plural,
+            ))
+        });
+        gaps
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn kinds(warnings: &[LintWarning]) -> Vec<(LintKind, &str)> {
+        warnings.iter().map(|w| (w.kind, w.key.as_str())).collect()
+    }
+
+    #[test]
+    fn clean_catalog() {
+        let source = Source::from_path(Path::new("./fixtures/lint/clean.json")).unwrap();
+        assert_eq!(source.lint(&LintOptions::default()), []);
+    }
+
+    #[test]
+    fn every_kind() {
+        let source = Source::from_path(Path::new("./fixtures/lint/flagged.json")).unwrap();
+        let warnings = source.lint(&LintOptions::default());
+        assert_eq!(
+            kinds(&warnings),
+            [
+                (LintKind::IdenticalToKey, "Settings"),
+                (LintKind::PlaceholderMismatch, "你好，%1"),
+                (LintKind::DuplicateKey, "保存"),
+                (LintKind::PlaceholderMismatch, "共%1页，第%{page}页"),
+                (LintKind::Unbalanced, "删除%1条消息"),
+                (LintKind::EmptyValue, "提交"),
+                (LintKind::NormalizationCollision, "确认 删除"),
+                (LintKind::Unbalanced, "警告"),
+            ]
+        );
+        assert_eq!(warnings[0].severity, Severity::Info);
+        assert_eq!(warnings[1].message, "missing %1, unexpected %2");
+        assert_eq!(
+            warnings[2].message,
+            "key '保存' appears more than once in namespace 'common'"
+        );
+        assert_eq!(warnings[3].message, "missing %{page} (platform 'mobile')");
+        assert_eq!(warnings[4].message, "'{' is never closed");
+        assert_eq!(
+            warnings[6].message,
+            r#"keys ["确认 删除", "确认\u{3000}删除"] differ only by whitespace or normalization"#
+        );
+        assert_eq!(
+            warnings[7].to_string(),
+            "warning[unbalanced] common/警告: <b> is never closed"
+        );
+        assert_eq!(
+            serde_json::to_value(&warnings[5]).unwrap(),
+            serde_json::json!({
+                "kind": "empty_value",
+                "severity": "warning",
+                "namespace": "common",
+                "key": "提交",
+                "message": "empty value"
+            })
+        );
+
+        let only = LintOptions::only(&[LintKind::EmptyValue]);
+        assert_eq!(kinds(&source.lint(&only)), [(LintKind::EmptyValue, "提交")]);
+    }
+
+    #[cfg(feature = "nfc")]
+    #[test]
+    fn nfc_collision() {
+        // "e\u{301}" is "é" decomposed
+        let json = "{\"common\": {\"caf\u{e9}\": \"1\", \"cafe\u{301}\": \"2\"}}";
+        let source = Source::from_static(json).unwrap();
+        let warnings = source.lint(&LintOptions::default());
+        assert_eq!(
+            kinds(&warnings),
+            [(LintKind::NormalizationCollision, "caf\u{e9}")]
+        );
+    }
+
+    #[test]
+    fn placeholder_continuity() {
+        let json = r#"{"common": {
+            "a": "%1 and %3",
+            "b": "%2 only",
+            "c": "%0, %1 %2 and %{3:upper}",
+            "d": "no placeholder, 100%",
+            "e": { "value": "%1 %2", "platforms": { "mobile": "%2%{4?，%4}" } },
+            "f": "%2 %1",
+            "g": { "few": "%2 pliki", "one": "%1 plik", "other": "%1 plików" }
+        }}"#;
+        let mut i18n = I18n::new("en");
+        i18n.insert_source("en", Source::from_static(json).unwrap());
+        i18n.insert_source(
+            "fr",
+            Source::from_static(r#"{"common": {"a": "%1 %2 %3"}}"#).unwrap(),
+        );
+        let gaps = i18n.check_placeholder_continuity();
+        let found: Vec<(&str, &str, &[usize])> = gaps
+            .iter()
+            .map(|gap| (gap.lang.as_str(), gap.key.as_str(), gap.missing.as_slice()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("EN", "a", &[2][..]),
+                ("EN", "b", &[1]),
+                ("EN", "e", &[1, 3]),
+                ("EN", "g", &[1])
+            ]
+        );
+        assert_eq!(gaps[0].to_string(), "EN common/a: uses %1, %3 but not %2");
+        assert_eq!(
+            gaps[2].to_string(),
+            "EN common/e (platform 'mobile'): uses %2, %4 but not %1, %3"
+        );
+        assert_eq!(
+            gaps[3].to_string(),
+            "EN common/g (plural 'few'): uses %2 but not %1"
+        );
+    }
+
+    #[test]
+    fn placeholder_styles() {
+        let mut i18n = I18n::new("en");
+        let de = r#"{"common": {"你好%1，%2": "Hallo {0}, {1:upper}", "已保存%1": "{0} und {2}"}}"#;
+        i18n.insert_source("de", Source::from_static(de).unwrap());
+        i18n.set_placeholder_style_for("de", PlaceholderStyle::Brace);
+        let fr = r#"{"common": {"进度%1": "%%1 fait", "你好%1，%2": "Salut %2, %1"}}"#;
+        i18n.insert_source("fr", Source::from_static(fr).unwrap());
+        let report = i18n.lint_all(&LintOptions::default());
+        let messages = |lang: &str| -> Vec<(&str, &str)> {
+            let warnings = report[lang].iter();
+            warnings
+                .map(|w| (w.key.as_str(), w.message.as_str()))
+                .collect()
+        };
+        assert_eq!(messages("DE"), [("已保存%1", "unexpected {2}")]);
+        assert_eq!(messages("FR"), [("进度%1", "missing %1")]);
+
+        let gaps = i18n.check_placeholder_continuity();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(
+            (gaps[0].key.as_str(), &gaps[0].missing[..]),
+            ("已保存%1", &[2][..])
+        );
+
+        // a source alone follows its `_meta`
+        let source = Source::from_static(
+            r#"{"_meta": {"placeholder_style": "brace"}, "common": {"你好%1": "Hallo {0}"}}"#,
+        )
+        .unwrap();
+        assert_eq!(source.lint(&LintOptions::default()), []);
+    }
+
+    #[test]
+    fn lint_by_language() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./fixtures/lint"));
+        let report = i18n.lint_all(&LintOptions::default());
+        assert_eq!(report.keys().collect::<Vec<_>>(), ["FLAGGED"]);
+        assert_eq!(report["FLAGGED"].len(), 8);
+    }
+
+    #[test]
+    fn plural_variants() {
+        let json = r#"{"common": {
+            "%1条消息": {"one": "One message", "other": "%1 messages"},
+            "%1个文件": {"one": "%1 file", "other": "%1 files"}
+        }}"#;
+        let source: Source = serde_json::from_str(json).unwrap();
+        let warnings = source.lint(&LintOptions::default());
+        assert_eq!(
+            kinds(&warnings),
+            [(LintKind::PlaceholderMismatch, "%1条消息")]
+        );
+        assert_eq!(warnings[0].message, "missing %1 (plural 'one')");
+    }
+
+    #[test]
+    fn suspicious_characters() {
+        let mut i18n = I18n::new("en");
+        let en: Source = serde_json::from_str(
+            r#"{"common": {
+                "保存": "Save\u0007",
+                "删除": "Delete\u200b  this ",
+                "确定": {"value": "OK", "platforms": {"web": "\u202eOK"}},
+                "名字": "\u2068Ann\u2069, \u2069Bob and \u2067Eve",
+                "空": "   ",
+                "多行": "Line one\nLine two\twith a tab, Zoë\u200d"
+            }}"#,
+        )
+        .unwrap();
+        i18n.insert_source("en", en);
+        let ar = HashMap::from([(
+            "common".to_string(),
+            HashMap::from([(
+                "%1条消息".to_string(),
+                "\u{202B}%1 رسائل\u{202C}".to_string(),
+            )]),
+        )]);
+        i18n.insert_source("ar", Source::from_map(ar));
+        let lints = i18n.lint_strings();
+        let found: Vec<String> = lints.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            found,
+            [
+                "AR common/%1条消息: bidi override U+202B at 0",
+                "EN common/保存: control character U+0007 at 4",
+                "EN common/删除: invisible character U+200B at 6",
+                "EN common/删除: trailing whitespace",
+                "EN common/删除: double space at 7",
+                "EN common/名字: U+2069 at 7 closes no isolate",
+                "EN common/确定 (web): bidi override U+202E at 0",
+            ]
+        );
+        assert_eq!(lints[0].issue, StringIssue::BidiOverride);
+        assert_eq!(
+            serde_json::to_value(&lints[1]).unwrap()["issue"],
+            "control_character"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Locale-aware joining of lists: `a, b, and c`, `a, b und c`, `a、b和c`.
+//!
+//! Separators follow the CLDR "standard" list patterns for the common languages; everything else
+//! uses the English punctuation without the serial comma. The conjunction itself is read from
+//! the catalog key `and` of the reserved [`LIST_NS`] namespace, so translators can change it.
+use crate::case::primary;
+use crate::prelude::*;
+use crate::I18n;
+
+/// The namespace of the list words.
+pub const LIST_NS: &str = "_list";
+
+struct ListStyle {
+    separator: &'static str,
+    and: &'static str,
+    /// Whether words are separated by spaces, i.e. the conjunction needs them too.
+    spaced: bool,
+    /// Whether a separator also precedes the conjunction of three or more items.
+    serial_comma: bool,
+}
+
+fn style(lang: &str) -> ListStyle {
+    let (separator, and, spaced, serial_comma) = match primary(lang).as_str() {
+        "EN" => (", ", "and", true, true),
+        "DE" => (", ", "und", true, false),
+        "FR" => (", ", "et", true, false),
+        "ES" => (", ", "y", true, false),
+        "IT" | "PT" => (", ", "e", true, false),
+        "NL" => (", ", "en", true, false),
+        "RU" => (", ", "и", true, false),
+        "TR" => (", ", "ve", true, false),
+        "KO" => (", ", "및", true, false),
+        "CN" | "ZH" => ("、", "和", false, false),
+        "JA" => ("、", "、", false, false),
+        _ => (", ", "and", true, false),
+    };
+    ListStyle {
+        separator,
+        and,
+        spaced,
+        serial_comma,
+    }
+}
+
+/// The separator of the items of a list in `lang`, `, ` or `、`, also written between the values
+/// of the rest placeholder `%*`.
+pub(crate) fn separator(lang: &str) -> &'static str {
+    style(lang).separator
+}
+
+impl I18n {
+    /// Joins `items` into a phrase with the separators of `lang` and its conjunction before the
+    /// last item: `a, b, and c`. The conjunction is the translation of `and` in namespace
+    /// [`LIST_NS`] of `lang` when the catalog has one, a built-in word otherwise.
+    pub fn join_list(&self, items: &[String], lang: &str) -> String {
+        let lang = lang.to_uppercase();
+        let style = style(&lang);
+        let catalog = self
+            .overrides
+            .get(&lang)
+            .into_iter()
+            .chain(self.lang_source(&lang))
+            .filter_map(|source| source.get_entry("and", LIST_NS))
+            .map(|entry| entry.value.as_str())
+            .find(|value| self.is_translated(value));
+        let and = catalog.unwrap_or(style.and);
+        let last = match (style.spaced, items.len()) {
+            (false, _) => and.to_string(),
+            (true, n) if n == 2 || !style.serial_comma => format!(" {and} "),
+            (true, _) => format!("{}{and} ", style.separator),
+        };
+        let mut out = String::new();
+        for (i, item) in items.iter().enumerate() {
+            match i {
+                0 => {}
+                _ if i + 1 == items.len() => out.push_str(&last),
+                _ => out.push_str(style.separator),
+            }
+            out.push_str(item);
+        }
+        out
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn items(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn builtin_styles() {
+        let i18n = I18n::new("en");
+        let three = items(&["a", "b", "c"]);
+        assert_eq!(i18n.join_list(&three, "en"), "a, b, and c");
+        assert_eq!(i18n.join_list(&items(&["a", "b"]), "en-US"), "a and b");
+        assert_eq!(i18n.join_list(&items(&["a"]), "en"), "a");
+        assert_eq!(i18n.join_list(&[], "en"), "");
+        assert_eq!(i18n.join_list(&three, "de"), "a, b und c");
+        assert_eq!(i18n.join_list(&three, "cn"), "a、b和c");
+        assert_eq!(i18n.join_list(&three, "ja"), "a、b、c");
+        assert_eq!(i18n.join_list(&three, "xx"), "a, b and c");
+    }
+
+    #[test]
+    fn catalog_conjunction() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_override("en", LIST_NS, "and", "&");
+        let three = items(&["a", "b", "c"]);
+        assert_eq!(i18n.join_list(&three, "en"), "a, b, & c");
+        i18n.set_override("en", LIST_NS, "and", "or");
+        assert_eq!(i18n.join_list(&three, "en"), "a, b, or c");
+        assert_eq!(i18n.join_list(&items(&["a", "b"]), "EN"), "a or b");
+        i18n.set_override("cn", LIST_NS, "and", "或");
+        assert_eq!(i18n.join_list(&three, "cn"), "a、b或c");
+    }
+}
@@ -0,0 +1,203 @@
+//! Parsers of language files, chosen by file extension.
+use crate::prelude::*;
+use crate::Source;
+use alloc::sync::Arc;
+use anyhow::{bail, Context, Result};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Parses the language files with one of its [`extensions`](SourceLoader::extensions).
+///
+/// Register one with [`I18n::register_loader`](crate::I18n::register_loader) to read a format
+/// of your own. JSON (and YAML, with the `yaml` feature) are built-in loaders.
+pub trait SourceLoader: Send + Sync {
+    /// Extensions handled, without the dot and compared case-insensitively: `["json"]`.
+    fn extensions(&self) -> &[&str];
+
+    /// Parses the content of a file.
+    fn load(&self, bytes: &[u8]) -> Result<Source>;
+
+    /// Parses the content of a file for `try_set_source`, rejecting anything suspicious.
+    /// Defaults to [`SourceLoader::load`].
+    fn load_strict(&self, bytes: &[u8]) -> Result<Source> {
+        self.load(bytes)
+    }
+}
+
+/// The built-in loader of `.json` files.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonLoader;
+
+impl SourceLoader for JsonLoader {
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+
+    fn load(&self, bytes: &[u8]) -> Result<Source> {
+        #[cfg(feature = "json-comments")]
+        let bytes = &strip_comments(bytes);
+        serde_json::from_slice(bytes).context("source parse error.")
+    }
+
+    /// Checks the document against [`source_schema`](crate::source_schema) first so that errors
+    /// point at the offending values instead of being raw serde messages.
+    fn load_strict(&self, bytes: &[u8]) -> Result<Source> {
+        #[cfg(feature = "json-comments")]
+        let bytes = &strip_comments(bytes);
+        let value: serde_json::Value =
+            serde_json::from_slice(bytes).context("is not valid json.")?;
+        let violations = Source::validate_json(&value);
+        if !violations.is_empty() {
+            let violations: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            bail!(
+                "doesn't match the source schema:\n{}",
+                violations.join("\n")
+            );
+        }
+        self.load(bytes)
+    }
+}
+
+/// Blanks out the `//` line comments of a json document, leaving string contents alone.
+/// Line breaks are kept so that parse errors still point at the right line.
+#[cfg(feature = "json-comments")]
+fn strip_comments(bytes: &[u8]) -> alloc::borrow::Cow<'_, [u8]> {
+    if !bytes.windows(2).any(|w| w == b"//") {
+        return alloc::borrow::Cow::Borrowed(bytes);
+    }
+    let mut out = Vec::with_capacity(bytes.len());
+    let (mut in_string, mut escaped, mut in_comment) = (false, false, false);
+    for (i, &b) in bytes.iter().enumerate() {
+        if in_comment {
+            if b == b'\n' {
+                in_comment = false;
+                out.push(b);
+            }
+            continue;
+        }
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+        } else if b == b'"' {
+            in_string = true;
+        } else if b == b'/' && bytes.get(i + 1) == Some(&b'/') {
+            in_comment = true;
+            continue;
+        }
+        out.push(b);
+    }
+    alloc::borrow::Cow::Owned(out)
+}
+
+/// The built-in loader of `.yaml` and `.yml` files.
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YamlLoader;
+
+#[cfg(feature = "yaml")]
+impl SourceLoader for YamlLoader {
+    fn extensions(&self) -> &[&str] {
+        &["yaml", "yml"]
+    }
+
+    fn load(&self, bytes: &[u8]) -> Result<Source> {
+        serde_yaml::from_slice(bytes).context("source parse error.")
+    }
+}
+
+/// The registered loaders, built-in ones first. When several handle an extension, the one
+/// registered last is used.
+#[derive(Clone)]
+pub(crate) struct Loaders(Vec<Arc<dyn SourceLoader>>);
+
+impl Default for Loaders {
+    fn default() -> Self {
+        Loaders(vec![
+            Arc::new(JsonLoader),
+            #[cfg(feature = "yaml")]
+            Arc::new(YamlLoader),
+        ])
+    }
+}
+
+impl fmt::Debug for Loaders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|loader| loader.extensions()))
+            .finish()
+    }
+}
+
+impl Loaders {
+    pub(crate) fn register(&mut self, loader: Arc<dyn SourceLoader>) {
+        self.0.push(loader);
+    }
+
+    /// The loader of `path`, by its extension.
+    #[cfg(feature = "std")]
+    pub(crate) fn for_path(&self, path: &Path) -> Option<&Arc<dyn SourceLoader>> {
+        let ext = path.extension()?.to_str()?;
+        self.0.iter().rev().find(|loader| {
+            loader
+                .extensions()
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        })
+    }
+
+    /// Reads and parses `path`.
+    #[cfg(feature = "std")]
+    pub(crate) fn load(&self, path: &Path, strict: bool) -> Result<Source> {
+        let Some(loader) = self.for_path(path) else {
+            bail!("[source error]: no loader for {}.", path.display());
+        };
+        load_file(loader.as_ref(), path, strict)
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn load_file(loader: &dyn SourceLoader, path: &Path, strict: bool) -> Result<Source> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("[source error]: can't open {}.", path.display()))?;
+    let source = match strict {
+        true => loader.load_strict(&bytes),
+        false => loader.load(&bytes),
+    };
+    source.map_err(|e| anyhow::anyhow!("[source error]: {} {e}", path.display()))
+}
+
+#[cfg(all(test, feature = "json-comments"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commented_json() {
+        let json = r#"{
+  // shown on the checkout button
+  "common": {
+    "保存": "Save", // a verb
+    "链接": "https://example.com/a//b",
+    "引号": "say \"hi\" // not a comment"
+  }
+}"#;
+        let source = JsonLoader.load(json.as_bytes()).unwrap();
+        assert_eq!(source.get("保存", "common"), Some("Save"));
+        assert_eq!(
+            source.get("链接", "common"),
+            Some("https://example.com/a//b")
+        );
+        assert_eq!(
+            source.get("引号", "common"),
+            Some(r#"say "hi" // not a comment"#)
+        );
+        JsonLoader.load_strict(json.as_bytes()).unwrap();
+
+        let err = JsonLoader.load(b"{\n// note\n\"common\": 1}").unwrap_err();
+        assert!(format!("{err:#}").contains("line 3"), "{err:#}");
+    }
+}
@@ -0,0 +1,43 @@
+//! Messages declared once at module scope and translated each time they are shown.
+use crate::{MissEvents, I18N};
+use std::fmt::{self, Display};
+
+/// A key and its namespace, translated by the global translator at each use, so a `static`
+/// follows language switches and catalog reloads. Built in const context with [`msg!`](crate::msg):
+/// ```
+/// use easy_i18n::{msg, Message};
+/// static CONFIRM: Message = msg!("确认删除%1个文件？", ns = "dialogs");
+/// static SAVED: Message = msg!("已保存");
+/// let count = 3;
+/// println!("{SAVED} {}", CONFIRM.with_args(&[&count]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Message {
+    pub key: &'static str,
+    pub ns: Option<&'static str>,
+}
+
+impl Message {
+    pub const fn new(key: &'static str, ns: Option<&'static str>) -> Message {
+        Message { key, ns }
+    }
+
+    /// The translation in the current global language, the key itself when there is none.
+    pub fn get(&self) -> String {
+        crate::t(self.key, self.ns, &[])
+    }
+
+    /// The translation interpolated with `args`, as [`i18n!`](crate::i18n) with values gives it.
+    pub fn with_args(&self, args: &[&dyn Display]) -> String {
+        crate::t(self.key, self.ns, args)
+    }
+}
+
+/// Writes [`Message::get`] without building the string first.
+impl Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let _misses = MissEvents::defer();
+        let i18n = I18N.read().unwrap();
+        i18n.translate_into(f, self.key, self.ns.map(str::to_string))
+    }
+}
@@ -0,0 +1,106 @@
+//! Process-wide lookup and miss counters.
+//!
+//! The totals are plain atomics. Each [`I18n`](crate::I18n) also holds the counters of its active
+//! language, fetched from the registry by `set_lang`, and so does each
+//! [`I18nContext`](crate::I18nContext) when it is created, so a lookup only increments atomics and
+//! never takes a lock.
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+static LOOKUPS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static LANGS: Lazy<Mutex<BTreeMap<String, Arc<Counters>>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    lookups: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// The counters of one language, shared with the registry.
+#[derive(Debug, Clone)]
+pub(crate) struct LangCounters(Arc<Counters>);
+
+impl LangCounters {
+    pub(crate) fn for_lang(lang: &str) -> LangCounters {
+        let mut langs = LANGS.lock().unwrap();
+        LangCounters(langs.entry(lang.to_string()).or_default().clone())
+    }
+
+    pub(crate) fn record(&self, hit: bool) {
+        LOOKUPS.fetch_add(1, Ordering::Relaxed);
+        self.0.lookups.fetch_add(1, Ordering::Relaxed);
+        if !hit {
+            MISSES.fetch_add(1, Ordering::Relaxed);
+            self.0.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The registry holds one set of counters per language, so sharing them means the same language.
+impl PartialEq for LangCounters {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for LangCounters {}
+
+impl Default for LangCounters {
+    fn default() -> Self {
+        LangCounters::for_lang("")
+    }
+}
+
+/// Lookups and misses of one language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LangMetrics {
+    pub lookups: u64,
+    pub misses: u64,
+}
+
+/// A snapshot of the counters, see [`metrics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I18nMetrics {
+    /// Lookups made by the translating calls (`translate`, `trans_with_inter`, `i18n!`, ...).
+    pub lookups: u64,
+    /// Lookups that fell back to the raw text.
+    pub misses: u64,
+    /// The same counts by language, for the languages that were looked up at least once.
+    pub langs: BTreeMap<String, LangMetrics>,
+}
+
+/// The lookup and miss counts of every translator in the process since start or the last
+/// [`reset_metrics`]. Counters are read one by one, so a snapshot taken while other threads
+/// translate may be off by the lookups in flight.
+pub fn metrics() -> I18nMetrics {
+    let langs = LANGS.lock().unwrap();
+    I18nMetrics {
+        lookups: LOOKUPS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        langs: langs
+            .iter()
+            .map(|(lang, counters)| {
+                let lang_metrics = LangMetrics {
+                    lookups: counters.lookups.load(Ordering::Relaxed),
+                    misses: counters.misses.load(Ordering::Relaxed),
+                };
+                (lang.clone(), lang_metrics)
+            })
+            .filter(|(_, lang_metrics)| lang_metrics.lookups > 0)
+            .collect(),
+    }
+}
+
+/// Sets every counter back to zero.
+pub fn reset_metrics() {
+    let langs = LANGS.lock().unwrap();
+    LOOKUPS.store(0, Ordering::Relaxed);
+    MISSES.store(0, Ordering::Relaxed);
+    for counters in langs.values() {
+        counters.lookups.store(0, Ordering::Relaxed);
+        counters.misses.store(0, Ordering::Relaxed);
+    }
+}
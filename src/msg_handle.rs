@@ -0,0 +1,163 @@
+//! Translations looked up by index, for strings translated every frame of a game loop.
+use crate::prelude::*;
+use crate::{I18n, Namespace};
+use alloc::sync::Arc;
+
+/// A key registered with [`I18n::key_handle`]: an index into the translations of the active
+/// language, resolved once instead of hashing the key on every lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MsgHandle(u32);
+
+/// The keys registered by [`I18n::key_handle`], kept across catalog and language changes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MsgKeys {
+    keys: Vec<(String, Option<Namespace>)>,
+    index: HashMap<(String, Option<Namespace>), MsgHandle>,
+}
+
+/// The translations of every [`MsgHandle`] in the active language, see [`I18n::msg_table`].
+/// Cheap to clone, and read without any lock.
+#[derive(Debug, Clone)]
+pub struct MsgTable {
+    generation: u64,
+    values: Arc<[Box<str>]>,
+}
+
+impl MsgTable {
+    /// The translation of `handle`, `""` for a handle of another translator.
+    pub fn get(&self, handle: MsgHandle) -> &str {
+        self.values.get(handle.0 as usize).map_or("", |value| value)
+    }
+
+    /// The generation of the translator this table was built from, see
+    /// [`I18n::msg_generation`]: when they differ, the table is out of date.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl I18n {
+    /// Registers `key` in `ns` for [`I18n::translate_handle`], once at setup time. Registering a
+    /// key again returns the same handle. Handles stay valid for the life of the translator:
+    /// after `set_source` or `set_lang` they give the translation of the new catalogs.
+    pub fn key_handle(&mut self, key: &str, ns: Option<&str>) -> MsgHandle {
+        let id = (key.to_string(), ns.map(str::to_string));
+        if let Some(handle) = self.msg_keys.index.get(&id) {
+            return *handle;
+        }
+        let handle = MsgHandle(self.msg_keys.keys.len() as u32);
+        self.msg_keys.keys.push(id.clone());
+        self.msg_keys.index.insert(id, handle);
+        self.translate_cache.clear_handles();
+        handle
+    }
+
+    /// The translation of `handle` in the language set by [`I18n::set_lang`], or its key when
+    /// the language, and the base language if any, miss it. No key is hashed and nothing is
+    /// allocated, except by the first call after a change of catalogs, language or settings,
+    /// which resolves every handle again. [`I18nContext`](crate::I18nContext)s are not followed,
+    /// and a handle of another translator gives `""`.
+    pub fn translate_handle(&self, handle: MsgHandle) -> &str {
+        self.handle_table().get(handle)
+    }
+
+    /// A snapshot of the translations of every handle, to read without holding the lock of a
+    /// shared translator. It is not updated by later changes, compare its
+    /// [`MsgTable::generation`] with [`I18n::msg_generation`] to find out.
+    pub fn msg_table(&self) -> MsgTable {
+        self.handle_table().clone()
+    }
+
+    /// A counter increased by every change that may change a translation.
+    pub fn msg_generation(&self) -> u64 {
+        self.translate_cache.generation
+    }
+
+    fn handle_table(&self) -> &MsgTable {
+        self.translate_cache.handles.get_or_init(|| {
+            let values = self
+                .msg_keys
+                .keys
+                .iter()
+                .map(|(key, ns)| {
+                    // the namespace a handle was registered in, never that of a context
+                    let found = self.lookup_exact(&self.lang, key, ns.clone()).or_else(|| {
+                        let base = self.base_lang.as_deref()?;
+                        self.lookup_exact(base, key, ns.clone())
+                    });
+                    found
+                        .map_or(key.as_str(), |found| &found.entry.value)
+                        .into()
+                })
+                .collect();
+            MsgTable {
+                generation: self.translate_cache.generation,
+                values,
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn handles_follow_catalog_changes() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        let test = i18n.key_handle("这是一个测试", None);
+        let other = i18n.key_handle("这是一个测试", Some("namespace1"));
+        let missing = i18n.key_handle("没有翻译", None);
+        assert_eq!(i18n.key_handle("这是一个测试", None), test);
+        assert_eq!(i18n.translate_handle(test), "This is a test");
+        assert_eq!(
+            i18n.translate_handle(other),
+            "This is a test, but it is different"
+        );
+        assert_eq!(i18n.translate_handle(missing), "没有翻译");
+
+        let table = i18n.msg_table();
+        assert_eq!(table.generation(), i18n.msg_generation());
+        i18n.set_lang("de");
+        assert_eq!(i18n.translate_handle(test), "Das ist ein Test");
+        // the snapshot is left as it was
+        assert_eq!(table.get(test), "This is a test");
+        assert_ne!(table.generation(), i18n.msg_generation());
+
+        i18n.set_override("de", "common", "没有翻译", "Keine Übersetzung");
+        assert_eq!(i18n.translate_handle(missing), "Keine Übersetzung");
+        i18n.set_source(Path::new("./fixtures/duplicates"));
+        assert_eq!(i18n.translate_handle(test), "这是一个测试");
+        assert_eq!(i18n.translate_handle(MsgHandle(99)), "");
+    }
+
+    #[test]
+    fn registering_outdates_the_snapshots() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        let test = i18n.key_handle("这是一个测试", None);
+        let table = i18n.msg_table();
+        assert_eq!(table.generation(), i18n.msg_generation());
+        let other = i18n.key_handle("这是一个测试", Some("namespace1"));
+        assert_ne!(table.generation(), i18n.msg_generation());
+        assert_eq!(table.get(other), "");
+        assert_eq!(i18n.msg_table().get(test), "This is a test");
+        // registering a key again changes nothing
+        let table = i18n.msg_table();
+        i18n.key_handle("这是一个测试", None);
+        assert_eq!(table.generation(), i18n.msg_generation());
+    }
+
+    #[test]
+    fn handles_ignore_the_context_namespace() {
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        let test = i18n.key_handle("这是一个测试", None);
+        let _context = crate::I18nContext::new("en")
+            .with_namespace("namespace1")
+            .enter();
+        assert_eq!(i18n.translate_handle(test), "This is a test");
+    }
+}
@@ -0,0 +1,174 @@
+//! Picking a loaded language from the languages a user accepts.
+use crate::prelude::*;
+use crate::I18n;
+
+/// The form language tags are compared in: trimmed, uppercased, `_` read as `-`.
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().replace('_', "-").to_uppercase()
+}
+
+/// The languages of an HTTP `Accept-Language` header, most preferred first, for
+/// [`I18n::negotiate_preferences`]: `"fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5"` gives
+/// `["FR-CH", "FR", "EN"]`.
+///
+/// Tags are normalized as catalog names are, uppercased with `_` read as `-`, and sorted by
+/// quality, the order of the header breaking ties. A tag repeated keeps its first place; tags
+/// with `q=0` or a malformed quality are left out, and so is the `*` wildcard, which stands for
+/// the language used when nothing matches anyway. Region subtags are kept: the negotiation
+/// falls back from `PT-BR` to a loaded `PT`.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut weighted: Vec<(String, f32)> = vec![];
+    for item in header.split(',') {
+        let mut parts = item.split(';');
+        let tag = normalize_tag(parts.next().unwrap_or_default());
+        let mut quality = Some(1.0);
+        for param in parts {
+            let Some((name, value)) = param.split_once('=') else {
+                continue;
+            };
+            if name.trim().eq_ignore_ascii_case("q") {
+                quality = value
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|q| (0.0..=1.0).contains(q));
+            }
+        }
+        let Some(quality) = quality else {
+            continue;
+        };
+        if tag.is_empty() || tag == "*" || quality == 0.0 {
+            continue;
+        }
+        if !weighted.iter().any(|(seen, _)| *seen == tag) {
+            weighted.push((tag, quality));
+        }
+    }
+    // stable, so equal qualities keep the order of the header
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    weighted.into_iter().map(|(tag, _)| tag).collect()
+}
+
+impl I18n {
+    /// The loaded language matching `tag`: the one whose normalized name equals the normalized
+    /// tag, or failing that the closest parent obtained by dropping subtags from the end, so
+    /// `pt-BR` matches a loaded `PT` when there is no `PT-BR`. The name is returned as loaded.
+    ///
+    /// Every negotiation of this crate goes through here.
+    pub(crate) fn match_language(&self, tag: &str) -> Option<String> {
+        let mut tag = normalize_tag(tag);
+        let languages = self.languages();
+        loop {
+            if tag.is_empty() {
+                return None;
+            }
+            if let Some(lang) = languages.iter().find(|lang| normalize_tag(lang) == tag) {
+                return Some(lang.clone());
+            }
+            match tag.rfind('-') {
+                Some(dash) => tag.truncate(dash),
+                None => return None,
+            }
+        }
+    }
+
+    /// The first language of `prefs`, in order of preference, that matches a loaded language, or
+    /// `None` when none does. Tags are compared case-insensitively with `_` read as `-`, and a
+    /// tag without a catalog of its own falls back to its region-less parent: `pt-BR` matches a
+    /// loaded `PT` when there is no `PT-BR`. The name is returned as loaded.
+    pub fn negotiate_preferences(&self, prefs: &[&str]) -> Option<String> {
+        prefs.iter().find_map(|pref| self.match_language(pref))
+    }
+
+    /// Switches to [`I18n::negotiate_preferences`] of `prefs` and returns it. The language is
+    /// left unchanged when no preference matches.
+    pub fn set_lang_from_preferences(&mut self, prefs: &[&str]) -> Option<String> {
+        let lang = self.negotiate_preferences(prefs)?;
+        self.set_lang(&lang);
+        Some(lang)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::Source;
+
+    fn i18n(langs: &[&str]) -> I18n {
+        let mut i18n = I18n::new("en");
+        for lang in langs {
+            i18n.insert_source(lang, Source::default());
+        }
+        i18n
+    }
+
+    #[test]
+    fn preference_order_and_region_fallback() {
+        let i18n = i18n(&["en", "pt", "zh_Hant", "es-MX"]);
+        assert_eq!(i18n.negotiate_preferences(&[]), None);
+        assert_eq!(i18n.negotiate_preferences(&["fr", "de-DE"]), None);
+        // only the region fallback matches
+        assert_eq!(
+            i18n.negotiate_preferences(&["pt-BR", "es", "en"])
+                .as_deref(),
+            Some("PT")
+        );
+        // a parent doesn't match a child: `es` is not `es-MX`
+        assert_eq!(
+            i18n.negotiate_preferences(&["es", "en"]).as_deref(),
+            Some("EN")
+        );
+        assert_eq!(
+            i18n.negotiate_preferences(&[" zh-hant-TW ", "en"])
+                .as_deref(),
+            Some("ZH_HANT")
+        );
+        assert_eq!(
+            i18n.negotiate_preferences(&["", "es_mx"]).as_deref(),
+            Some("ES-MX")
+        );
+        // duplicates change nothing
+        assert_eq!(
+            i18n.negotiate_preferences(&["fr", "fr", "en", "en", "pt"])
+                .as_deref(),
+            Some("EN")
+        );
+    }
+
+    #[test]
+    fn accept_language() {
+        assert_eq!(
+            parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"),
+            ["FR-CH", "FR", "EN", "DE"]
+        );
+        assert_eq!(
+            parse_accept_language("en;q=0.5, pt_br , es;Q=1.0;level=1, en-GB;q=0.5, ja;q=0.6"),
+            ["PT-BR", "ES", "JA", "EN", "EN-GB"]
+        );
+        // refused, malformed and repeated tags
+        assert_eq!(
+            parse_accept_language("de;q=0, it;q=x, nl;q=1.5, en, , EN;q=0.1"),
+            ["EN"]
+        );
+        assert_eq!(parse_accept_language("*"), Vec::<String>::new());
+        assert_eq!(parse_accept_language(""), Vec::<String>::new());
+
+        let i18n = i18n(&["en", "pt"]);
+        let prefs = parse_accept_language("pt-BR;q=0.9, fr");
+        let prefs: Vec<&str> = prefs.iter().map(String::as_str).collect();
+        assert_eq!(i18n.negotiate_preferences(&prefs).as_deref(), Some("PT"));
+    }
+
+    #[test]
+    fn set_lang_from_preferences() {
+        let mut i18n = i18n(&["en", "pt"]);
+        i18n.set_lang("cn");
+        assert_eq!(i18n.set_lang_from_preferences(&["de"]), None);
+        assert_eq!(i18n.current_lang(), "CN");
+        assert_eq!(
+            i18n.set_lang_from_preferences(&["pt-BR"]).as_deref(),
+            Some("PT")
+        );
+        assert_eq!(i18n.current_lang(), "PT");
+    }
+}
@@ -0,0 +1,90 @@
+//! Callbacks run when the global translator changes language or catalogs.
+//!
+//! Subscribers are called after the change is applied and after [`I18N`](crate::I18N) is unlocked,
+//! so they are free to translate, or to subscribe and unsubscribe. A panicking subscriber is
+//! isolated: its panic is caught, the others still run and no lock is poisoned.
+use once_cell::sync::Lazy;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+/// Identifies a subscription, to [`unsubscribe`] it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type LangCallback = Arc<dyn Fn(&str, &str) + Send + Sync>;
+type SourceCallback = Arc<dyn Fn() + Send + Sync>;
+
+#[derive(Clone)]
+enum Callback {
+    Lang(LangCallback),
+    Source(SourceCallback),
+}
+
+#[derive(Default)]
+struct Subscribers {
+    next: u64,
+    callbacks: Vec<(SubscriptionId, Callback)>,
+}
+
+static SUBSCRIBERS: Lazy<Mutex<Subscribers>> = Lazy::new(Default::default);
+
+fn subscribe(callback: Callback) -> SubscriptionId {
+    let mut subscribers = SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner());
+    subscribers.next += 1;
+    let id = SubscriptionId(subscribers.next);
+    subscribers.callbacks.push((id, callback));
+    id
+}
+
+/// Calls `f(old, new)` with the uppercased language codes whenever [`set_lang`](crate::set_lang)
+/// switches to another language.
+pub fn on_lang_change(f: impl Fn(&str, &str) + Send + Sync + 'static) -> SubscriptionId {
+    subscribe(Callback::Lang(Arc::new(f)))
+}
+
+/// Calls `f` whenever the global catalogs are replaced or extended: `set_source`,
+/// `set_source_lazy`, `set_source_recursive`, and successful `try_set_source` and
+/// `set_source_from_env` calls.
+pub fn on_source_change(f: impl Fn() + Send + Sync + 'static) -> SubscriptionId {
+    subscribe(Callback::Source(Arc::new(f)))
+}
+
+/// Removes a subscription. Returns whether it existed.
+/// A subscriber removed while a notification is running is still called by that notification.
+pub fn unsubscribe(id: SubscriptionId) -> bool {
+    let mut subscribers = SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner());
+    let len = subscribers.callbacks.len();
+    subscribers.callbacks.retain(|(sub, _)| *sub != id);
+    subscribers.callbacks.len() != len
+}
+
+/// Runs every subscriber on a snapshot taken without holding any lock during the calls.
+fn notify(call: impl Fn(&Callback)) {
+    let callbacks: Vec<Callback> = {
+        let subscribers = SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers
+            .callbacks
+            .iter()
+            .map(|(_, cb)| cb.clone())
+            .collect()
+    };
+    for callback in &callbacks {
+        let _ = catch_unwind(AssertUnwindSafe(|| call(callback)));
+    }
+}
+
+pub(crate) fn lang_changed(old: &str, new: &str) {
+    notify(|callback| {
+        if let Callback::Lang(f) = callback {
+            f(old, new)
+        }
+    });
+}
+
+pub(crate) fn source_changed() {
+    notify(|callback| {
+        if let Callback::Source(f) = callback {
+            f()
+        }
+    });
+}
@@ -0,0 +1,67 @@
+//! Optional interpolation values.
+use core::fmt::{self, Display};
+
+/// What an absent [`Opt`] is written as: a Unicode noncharacter, reserved for internal use and
+/// never found in interchanged text, so the interpolation can tell it from any real value.
+pub(crate) const ABSENT: &str = "\u{fdd0}";
+
+/// An interpolation value that may be absent, e.g. a middle name:
+/// `i18n!("%1 %2 %3", first, Opt(middle), last)`.
+///
+/// A `None` counts as a missing value: its placeholder follows the missing-arg policy, it is
+/// left out of `%0`, and a conditional section `%{2?（%2）}` that depends on it is dropped whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Opt<T>(pub Option<T>);
+
+impl<T> From<Option<T>> for Opt<T> {
+    fn from(value: Option<T>) -> Self {
+        Opt(value)
+    }
+}
+
+impl<T: Display> Display for Opt<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str(ABSENT),
+        }
+    }
+}
+
+/// Matches what is written against [`ABSENT`], stopping at the first difference.
+struct AbsentProbe {
+    matched: usize,
+}
+
+impl fmt::Write for AbsentProbe {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match ABSENT.as_bytes()[self.matched..].starts_with(s.as_bytes()) {
+            true => {
+                self.matched += s.len();
+                Ok(())
+            }
+            false => Err(fmt::Error),
+        }
+    }
+}
+
+/// Whether `value` is an absent [`Opt`], without formatting more of it than needed to tell.
+pub(crate) fn is_absent(value: &impl Display) -> bool {
+    let mut probe = AbsentProbe { matched: 0 };
+    fmt::write(&mut probe, format_args!("{value}")).is_ok() && probe.matched == ABSENT.len()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absent_values() {
+        assert!(is_absent(&Opt::<&str>(None)));
+        assert!(is_absent(&Opt::<u8>(None).to_string()));
+        assert!(!is_absent(&Opt(Some("Maria"))));
+        assert!(!is_absent(&""));
+        assert!(!is_absent(&format!("{ABSENT}!")));
+        assert_eq!(Opt(Some(3)).to_string(), "3");
+    }
+}
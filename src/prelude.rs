@@ -0,0 +1,73 @@
+//! The `alloc` items the modules use, and the stand-ins for those of `std` when it is off, so
+//! that the core types build either way.
+pub(crate) use alloc::boxed::Box;
+pub(crate) use alloc::string::{String, ToString};
+pub(crate) use alloc::vec::Vec;
+pub(crate) use alloc::{format, vec};
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+pub(crate) use once_cell::sync::{Lazy, OnceCell};
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap, HashSet};
+
+/// A cell written once, as `once_cell::sync::OnceCell` is with `std`. Racing threads may each
+/// compute its value, one result is kept.
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub(crate) struct OnceCell<T>(once_cell::race::OnceBox<T>);
+
+#[cfg(not(feature = "std"))]
+impl<T> OnceCell<T> {
+    pub(crate) const fn new() -> OnceCell<T> {
+        OnceCell(once_cell::race::OnceBox::new())
+    }
+
+    pub(crate) fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        self.0.get_or_init(|| Box::new(f()))
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        OnceCell::new()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: Clone> Clone for OnceCell<T> {
+    fn clone(&self) -> Self {
+        let cell = OnceCell::new();
+        if let Some(value) = self.0.get() {
+            let _ = cell.0.set(Box::new(value.clone()));
+        }
+        cell
+    }
+}
+
+/// A value computed on first use, as `once_cell::sync::Lazy` is with `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) struct Lazy<T> {
+    cell: OnceCell<T>,
+    init: fn() -> T,
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> Lazy<T> {
+    pub(crate) const fn new(init: fn() -> T) -> Lazy<T> {
+        Lazy {
+            cell: OnceCell::new(),
+            init,
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> core::ops::Deref for Lazy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.cell.get_or_init(self.init)
+    }
+}
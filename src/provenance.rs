@@ -0,0 +1,124 @@
+//! Where a translation came from, for diagnostics once fallbacks are in play.
+use crate::prelude::*;
+use crate::{I18n, Namespace, DEFAULT_NS};
+use serde::Serialize;
+
+/// A translation along with the lookup that produced it, see [`I18n::translate_checked`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TranslationResult {
+    /// The text [`I18n::translate`] returns.
+    pub text: String,
+    /// The language the translation was found in, `None` for a miss.
+    pub lang: Option<String>,
+    /// The namespace asked for, once the context and the default namespace are applied.
+    pub requested_namespace: Namespace,
+    /// The namespace the translation was found in, `None` for a miss.
+    pub namespace: Option<Namespace>,
+    /// Found in a parent namespace, the fallback chain or `common` rather than the requested one.
+    pub namespace_fallback: bool,
+    /// Found in the base language after a miss in the active one.
+    pub base_lang_fallback: bool,
+    /// Either fallback was used.
+    pub fallback: bool,
+    /// Neither the active language nor the base language translate the key: `text` is the key.
+    pub miss: bool,
+}
+
+impl I18n {
+    /// [`I18n::translate`], telling which language and namespace satisfied the lookup and
+    /// whether a fallback or nothing at all did, e.g. to find out why a string comes out in the
+    /// language of the keys. Meant for logging and debug overlays; the translation itself is
+    /// unchanged, and a miss is reported to the missing-key handler as usual.
+    pub fn translate_checked(&self, text: &str, ns: Option<Namespace>) -> TranslationResult {
+        let requested = self.requested_namespace(ns.clone());
+        let lang = self.active_lang();
+        let found = self
+            .lookup_in(&lang, text, requested.clone())
+            .map(|found| (lang.to_string(), found))
+            .or_else(|| {
+                let base = self.base_lang.as_deref().filter(|base| *base != lang)?;
+                let found = self.lookup_in(base, text, requested.clone())?;
+                Some((base.to_string(), found))
+            });
+        let requested = requested.unwrap_or(DEFAULT_NS.to_string());
+        let namespace_fallback = found
+            .as_ref()
+            .is_some_and(|(_, found)| found.namespace != requested);
+        let base_lang_fallback = found
+            .as_ref()
+            .is_some_and(|(found_lang, _)| *found_lang != lang);
+        let (lang, namespace) = found.map(|(lang, found)| (lang, found.namespace)).unzip();
+        TranslationResult {
+            text: self.translate(text, ns),
+            miss: lang.is_none(),
+            lang,
+            requested_namespace: requested,
+            namespace,
+            namespace_fallback,
+            base_lang_fallback,
+            fallback: namespace_fallback || base_lang_fallback,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::Source;
+
+    #[test]
+    fn provenance() {
+        let mut i18n = I18n::new("de");
+        let de: Source = serde_json::from_str(
+            r#"{
+                "common": {"保存": "Speichern"},
+                "checkout": {"支付": "Bezahlen"},
+                "shared": {"取消": "Abbrechen"}
+            }"#,
+        )
+        .unwrap();
+        i18n.insert_source("de", de);
+        let en: Source = serde_json::from_str(r#"{"checkout": {"退款": "Refund"}}"#).unwrap();
+        i18n.insert_source("en", en);
+
+        let hit = i18n.translate_checked("保存", None);
+        assert_eq!(hit.text, "Speichern");
+        assert_eq!(hit.lang.as_deref(), Some("DE"));
+        assert_eq!(hit.namespace.as_deref(), Some("common"));
+        assert!(!hit.fallback && !hit.miss);
+
+        // a parent namespace
+        let parent = i18n.translate_checked("支付", Some("checkout.mobile".to_string()));
+        assert_eq!(parent.text, "Bezahlen");
+        assert_eq!(parent.requested_namespace, "checkout.mobile");
+        assert_eq!(parent.namespace.as_deref(), Some("checkout"));
+        assert!(parent.namespace_fallback && parent.fallback && !parent.base_lang_fallback);
+
+        // the fallback chain, then `common`
+        i18n.set_ns_fallback(&["shared"]).unwrap();
+        let chain = i18n.translate_checked("取消", Some("checkout".to_string()));
+        assert_eq!(chain.namespace.as_deref(), Some("shared"));
+        assert!(chain.namespace_fallback);
+        i18n.set_common_fallback(true);
+        let common = i18n.translate_checked("保存", Some("checkout".to_string()));
+        assert_eq!(common.text, "Speichern");
+        assert_eq!(common.namespace.as_deref(), Some("common"));
+        assert!(common.namespace_fallback);
+
+        // a miss, found in the base language once there is one
+        let miss = i18n.translate_checked("退款", Some("checkout".to_string()));
+        assert!(miss.miss && !miss.fallback);
+        assert_eq!(miss.text, "退款");
+        assert_eq!((miss.lang, miss.namespace), (None, None));
+        i18n.set_base_lang("en");
+        let base = i18n.translate_checked("退款", Some("checkout".to_string()));
+        assert_eq!(base.text, "Refund");
+        assert_eq!(base.lang.as_deref(), Some("EN"));
+        assert!(base.base_lang_fallback && base.fallback && !base.namespace_fallback);
+
+        assert_eq!(
+            serde_json::to_string(&i18n.translate_checked("没有", None)).unwrap(),
+            r#"{"text":"没有","lang":null,"requested_namespace":"common","namespace":null,"namespace_fallback":false,"base_lang_fallback":false,"fallback":false,"miss":true}"#
+        );
+    }
+}
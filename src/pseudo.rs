@@ -0,0 +1,84 @@
+//! Pseudo-localization: `Save %1` becomes `⟦Şȧṽḗ %1~~⟧`.
+//!
+//! ASCII letters are swapped for accented look-alikes so hardcoded strings stand out, the text is
+//! padded to simulate the expansion of longer languages, and brackets show where it was cut.
+//! Placeholders are copied as they are, so interpolation still fills them.
+use crate::prelude::*;
+use crate::{PlaceholderStyle, KEYWORD_REG};
+
+const LOWER: [char; 26] = [
+    'ȧ', 'ƀ', 'ƈ', 'ḓ', 'ḗ', 'ƒ', 'ɠ', 'ħ', 'ī', 'ĵ', 'ķ', 'ŀ', 'ḿ', 'ƞ', 'ǿ', 'ƥ', 'ɋ', 'ř', 'ş',
+    'ŧ', 'ũ', 'ṽ', 'ẇ', 'ẋ', 'ẏ', 'ẑ',
+];
+const UPPER: [char; 26] = [
+    'Ȧ', 'Ɓ', 'Ƈ', 'Ḓ', 'Ḗ', 'Ƒ', 'Ɠ', 'Ħ', 'Ī', 'Ĵ', 'Ķ', 'Ŀ', 'Ḿ', 'Ƞ', 'Ǿ', 'Ƥ', 'Ɋ', 'Ř', 'Ş',
+    'Ŧ', 'Ũ', 'Ṽ', 'Ẇ', 'Ẋ', 'Ẏ', 'Ẑ',
+];
+
+fn accent(c: char) -> char {
+    match c {
+        'a'..='z' => LOWER[c as usize - 'a' as usize],
+        'A'..='Z' => UPPER[c as usize - 'A' as usize],
+        _ => c,
+    }
+}
+
+/// The pseudo-localized form of `template`, whose placeholders and escaped delimiters are
+/// written in `style`, padded to about `expansion` times its length.
+pub(crate) fn pseudo(template: &str, style: PlaceholderStyle, expansion: f32) -> String {
+    let mut placeholders: Vec<_> = style
+        .regex()
+        .find_iter(template)
+        .chain(KEYWORD_REG.find_iter(template))
+        .map(|m| m.range())
+        .collect();
+    placeholders.sort_by_key(|range| range.start);
+    let mut out = String::with_capacity(template.len() * 2 + 8);
+    out.push('⟦');
+    let mut last = 0;
+    let mut chars = 0;
+    let mut text = |out: &mut String, text: &str| {
+        for c in text.chars() {
+            chars += 1;
+            out.push(accent(c));
+        }
+    };
+    for range in placeholders {
+        // a match nested in one already copied
+        if range.start < last {
+            continue;
+        }
+        text(&mut out, &template[last..range.start]);
+        out.push_str(&template[range.clone()]);
+        last = range.end;
+    }
+    text(&mut out, &template[last..]);
+    // rounded to the nearest count, a negative one saturates to 0
+    let padding = (chars as f32 * (expansion - 1.0) + 0.5) as usize;
+    out.extend(core::iter::repeat_n('~', padding));
+    out.push('⟧');
+    out
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_survive() {
+        let percent = |template| pseudo(template, PlaceholderStyle::Percent, 1.0);
+        assert_eq!(pseudo("Save", PlaceholderStyle::Percent, 1.5), "⟦Şȧṽḗ~~⟧");
+        assert_eq!(
+            percent("Hi %{1:upper}, %2 of %{name}!"),
+            "⟦Ħī %{1:upper}, %2 ǿƒ %{name}!⟧"
+        );
+        assert_eq!(percent("{%1:file|files}"), "⟦{%1:ƒīŀḗ|ƒīŀḗş}⟧");
+        assert_eq!(percent("这是一个测试"), "⟦这是一个测试⟧");
+        assert_eq!(percent("%*, 100%%"), "⟦%*, 100%%⟧");
+        assert_eq!(pseudo("", PlaceholderStyle::Percent, 2.0), "⟦⟧");
+        assert_eq!(
+            pseudo("Hi {0:upper}, {{x}} {*}", PlaceholderStyle::Brace, 1.0),
+            "⟦Ħī {0:upper}, {{ẋ}} {*}⟧"
+        );
+    }
+}
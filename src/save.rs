@@ -0,0 +1,207 @@
+//! Writing the in-memory catalogs, runtime overrides included, back to language files.
+use crate::{load_source, I18n, Source};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+impl I18n {
+    /// The catalog of `lang` as it is looked up: the loaded one with the overrides on top.
+    fn merged_source(&self, lang: &str) -> Option<Source> {
+        let lang = lang.to_uppercase();
+        let loaded = self.lang_source(&lang);
+        let overrides = self.overrides.get(&lang);
+        if loaded.is_none() && overrides.is_none() {
+            return None;
+        }
+        let mut source = loaded.cloned().unwrap_or_default();
+        if let Some(overrides) = overrides {
+            source.merge(overrides.clone());
+        }
+        Some(source)
+    }
+
+    /// The languages with a catalog or overrides, sorted.
+    fn saved_languages(&self) -> Vec<String> {
+        let mut langs = self.languages();
+        langs.extend(self.overrides.keys().cloned());
+        langs.sort();
+        langs.dedup();
+        langs
+    }
+
+    /// Writes the catalog of `lang`, with the overrides set by [`I18n::set_override`], to `path`
+    /// in the sorted, pretty-printed json of [`I18n::export_to_dir`], so saving unchanged
+    /// catalogs gives byte-identical files.
+    ///
+    /// The json is written to a temporary file next to `path` then renamed over it, so an
+    /// interrupted save leaves the previous file whole.
+    pub fn save_lang(&self, lang: &str, path: &Path) -> Result<()> {
+        let Some(source) = self.merged_source(lang) else {
+            bail!("[save error]: there is no catalog for {lang}.");
+        };
+        write_atomic(path, &to_json(&source)?)
+    }
+
+    /// [`I18n::save_lang`] for every language, to `dir` as `<lang>.json`, creating the
+    /// directory if needed.
+    pub fn save_all(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        for lang in self.saved_languages() {
+            if let Some(source) = self.merged_source(&lang) {
+                let path = dir.join(format!("{}.json", lang.to_lowercase()));
+                write_atomic(&path, &to_json(&source)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes to `dir`, as `<lang>.json`, only the entries that differ from the language files
+    /// of `original`: the keys overridden or merged at runtime. Loading `original` then
+    /// [`I18n::merge_source`] of `dir` gives back the current catalogs. Languages without any
+    /// change get no file.
+    pub fn save_delta(&self, original: &Path, dir: &Path) -> Result<()> {
+        let (originals, _) = load_source(original, &self.load_options, &self.loaders);
+        fs::create_dir_all(dir)?;
+        for lang in self.saved_languages() {
+            let Some(source) = self.merged_source(&lang) else {
+                continue;
+            };
+            let original = originals.get(&lang);
+            let mut delta = Source::default();
+            for (ns, entries) in source.namespaces {
+                let changed: HashMap<_, _> = entries
+                    .into_iter()
+                    .filter(|(key, entry)| {
+                        original.and_then(|o| o.get_entry(key, &ns)) != Some(entry)
+                    })
+                    .collect();
+                if !changed.is_empty() {
+                    delta.namespaces.insert(ns, changed);
+                }
+            }
+            if delta.namespaces.is_empty() {
+                continue;
+            }
+            let path = dir.join(format!("{}.json", lang.to_lowercase()));
+            write_atomic(&path, &to_json(&delta)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn to_json(source: &Source) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(source)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Where [`write_atomic`] writes before renaming: a hidden file in the same directory, so the
+/// rename never crosses file systems.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.tmp"))
+}
+
+/// Replaces the content of `path` with `contents` through a synced temporary file and a rename.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let temp = temp_path(path);
+    let written = fs::File::create(&temp).and_then(|mut file| {
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(err) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(err).with_context(|| format!("[save error]: can't write {}.", temp.display()));
+    }
+    fs::rename(&temp, path).with_context(|| {
+        let _ = fs::remove_file(&temp);
+        format!("[save error]: can't replace {}.", path.display())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = crate::tests::temp_path(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn save_with_overrides() {
+        let dir = temp_dir("easy_i18n_save_all");
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_override("en", "common", "这是一个测试", "This is a patched test");
+        i18n.set_override("fr", "common", "保存", "Enregistrer");
+        i18n.save_all(&dir).unwrap();
+        let first = fs::read(dir.join("en.json")).unwrap();
+        i18n.save_all(&dir).unwrap();
+        assert_eq!(fs::read(dir.join("en.json")).unwrap(), first);
+        assert!(!temp_path(&dir.join("en.json")).exists());
+
+        let mut saved = I18n::new("en");
+        saved.set_source(&dir);
+        assert_eq!(
+            saved.translate("这是一个测试", None),
+            "This is a patched test"
+        );
+        assert_eq!(
+            saved.translate("这是一个测试", Some("namespace1".to_string())),
+            "This is a test, but it is different"
+        );
+        saved.set_lang("fr");
+        assert_eq!(saved.translate("保存", None), "Enregistrer");
+        assert!(i18n.save_lang("ja", &dir.join("ja.json")).is_err());
+    }
+
+    #[test]
+    fn interrupted_save_keeps_the_live_file() {
+        let dir = temp_dir("easy_i18n_save_atomic");
+        let path = dir.join("en.json");
+        fs::write(&path, "{}\n").unwrap();
+        let mut i18n = I18n::new("en");
+        i18n.set_override("en", "common", "保存", "Save");
+        // the temporary file can't be created
+        fs::create_dir(temp_path(&path)).unwrap();
+        assert!(i18n.save_lang("en", &path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}\n");
+        fs::remove_dir(temp_path(&path)).unwrap();
+        i18n.save_lang("en", &path).unwrap();
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("\"保存\": \"Save\""));
+        assert!(!temp_path(&path).exists());
+    }
+
+    #[test]
+    fn delta_overlay() {
+        let dir = temp_dir("easy_i18n_save_delta");
+        let mut i18n = I18n::new("en");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_override("en", "namespace1", "这是一个测试", "Patched");
+        i18n.set_override("en", "common", "这是一个测试", "This is a test");
+        i18n.save_delta(Path::new("./source"), &dir).unwrap();
+        // the unchanged override and the untouched languages are left out
+        assert!(!dir.join("de.json").exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("en.json")).unwrap(),
+            "{\n  \"namespace1\": {\n    \"这是一个测试\": \"Patched\"\n  }\n}\n"
+        );
+
+        let mut layered = I18n::new("en");
+        layered.set_source(Path::new("./source"));
+        let (overlay, _) = load_source(&dir, &layered.load_options, &layered.loaders);
+        layered.merge_source(overlay);
+        assert_eq!(
+            layered.translate("这是一个测试", Some("namespace1".to_string())),
+            "Patched"
+        );
+        assert_eq!(layered.translate("这是一个测试", None), "This is a test");
+    }
+}
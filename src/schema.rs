@@ -0,0 +1,291 @@
+//! JSON Schema of the language files and a validator giving pointer-accurate errors.
+use crate::entry::{FIELDS, PLURAL_CATEGORIES};
+use crate::prelude::*;
+use crate::Source;
+use core::fmt;
+use serde_json::{json, Value};
+
+/// A place where a document doesn't match the expected file shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer (RFC 6901) to the offending value, `""` for the document itself.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+/// The JSON Schema (draft 2020-12) of a language file: namespaces mapping keys to strings or
+/// [`Entry`](crate::Entry) objects, plus an optional `_meta` object.
+pub fn source_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "easy_i18n language file",
+        "type": "object",
+        "properties": {
+            "_meta": {
+                "type": "object",
+                "properties": {
+                    "display_name": { "type": "string" },
+                    "direction": { "enum": ["ltr", "rtl"] },
+                    "plural_rule": { "type": "string" },
+                    "version": { "type": "string" },
+                    "last_updated": { "type": "string" },
+                    "placeholder_style": { "enum": ["percent", "brace"] }
+                }
+            }
+        },
+        "additionalProperties": {
+            "description": "A namespace",
+            "type": "object",
+            "additionalProperties": { "$ref": "#/$defs/value" }
+        },
+        "$defs": {
+            "value": {
+                "oneOf": [
+                    { "type": "string" },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "value": { "type": "string" },
+                            "platforms": {
+                                "description": "Variants by platform",
+                                "type": "object",
+                                "additionalProperties": { "type": "string" }
+                            },
+                            "comment": {
+                                "description": "Context note for translators",
+                                "type": "string"
+                            },
+                            "max_len": {
+                                "description": "The most characters the translation may have",
+                                "type": "integer",
+                                "minimum": 0
+                            },
+                            "zero": { "type": "string" },
+                            "one": { "type": "string" },
+                            "two": { "type": "string" },
+                            "few": { "type": "string" },
+                            "many": { "type": "string" },
+                            "other": {
+                                "description": "The plural variant used by default, and the value when there is none",
+                                "type": "string"
+                            }
+                        },
+                        "anyOf": [{ "required": ["value"] }, { "required": ["other"] }],
+                        "additionalProperties": false
+                    }
+                ]
+            }
+        }
+    })
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+struct Validator {
+    violations: Vec<SchemaViolation>,
+}
+
+impl Validator {
+    fn violation(&mut self, pointer: String, message: String) {
+        self.violations.push(SchemaViolation { pointer, message });
+    }
+
+    fn expect_string(&mut self, pointer: String, value: &Value) {
+        if !value.is_string() {
+            self.violation(pointer, format!("expected a string, found {}", kind(value)));
+        }
+    }
+
+    fn meta(&mut self, pointer: String, meta: &Value) {
+        let Some(meta) = meta.as_object() else {
+            let message = format!("expected an object, found {}", kind(meta));
+            return self.violation(pointer, message);
+        };
+        for (field, value) in meta {
+            let pointer = format!("{pointer}/{}", escape(field));
+            match field.as_str() {
+                "display_name" | "plural_rule" | "version" | "last_updated" => {
+                    self.expect_string(pointer, value)
+                }
+                "direction" if !matches!(value.as_str(), Some("ltr" | "rtl")) => {
+                    let message = format!(r#"expected "ltr" or "rtl", found {value}"#);
+                    self.violation(pointer, message);
+                }
+                "placeholder_style" if !matches!(value.as_str(), Some("percent" | "brace")) => {
+                    let message = format!(r#"expected "percent" or "brace", found {value}"#);
+                    self.violation(pointer, message);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn value(&mut self, pointer: String, value: &Value) {
+        let object = match value {
+            Value::String(_) => return,
+            Value::Object(object) => object,
+            _ => {
+                let message = format!("expected a string or an object, found {}", kind(value));
+                return self.violation(pointer, message);
+            }
+        };
+        if !object.contains_key("value") && !object.contains_key("other") {
+            self.violation(pointer.clone(), r#"missing field "value""#.to_string());
+        }
+        for (field, value) in object {
+            let pointer = format!("{pointer}/{}", escape(field));
+            match field.as_str() {
+                "value" | "comment" => self.expect_string(pointer, value),
+                category if PLURAL_CATEGORIES.contains(&category) => {
+                    self.expect_string(pointer, value)
+                }
+                "platforms" => self.strings(pointer, value),
+                "max_len" if value.as_u64().is_none() => {
+                    let message = format!("expected a non-negative integer, found {value}");
+                    self.violation(pointer, message);
+                }
+                "max_len" => {}
+                _ => self.violation(
+                    pointer,
+                    format!("unknown field, expected one of {FIELDS:?}"),
+                ),
+            }
+        }
+    }
+
+    /// Expects an object whose values are all strings.
+    fn strings(&mut self, pointer: String, value: &Value) {
+        let Some(object) = value.as_object() else {
+            let message = format!("expected an object, found {}", kind(value));
+            return self.violation(pointer, message);
+        };
+        for (name, value) in object {
+            self.expect_string(format!("{pointer}/{}", escape(name)), value);
+        }
+    }
+
+    fn namespace(&mut self, pointer: String, namespace: &Value) {
+        let Some(entries) = namespace.as_object() else {
+            let message = format!("expected a namespace object, found {}", kind(namespace));
+            return self.violation(pointer, message);
+        };
+        for (key, value) in entries {
+            self.value(format!("{pointer}/{}", escape(key)), value);
+        }
+    }
+}
+
+impl Source {
+    /// Checks `value` against [`source_schema`], returning every violation found.
+    pub fn validate_json(value: &Value) -> Vec<SchemaViolation> {
+        let mut validator = Validator { violations: vec![] };
+        match value.as_object() {
+            None => {
+                let message = format!("expected an object, found {}", kind(value));
+                validator.violation(String::new(), message);
+            }
+            Some(root) => {
+                for (name, value) in root {
+                    let pointer = format!("/{}", escape(name));
+                    if name == "_meta" {
+                        validator.meta(pointer, value);
+                    } else {
+                        validator.namespace(pointer, value);
+                    }
+                }
+            }
+        }
+        validator.violations
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_document() {
+        let doc = json!({
+            "_meta": { "display_name": "English", "direction": "ltr", "custom": [1] },
+            "common": {
+                "这是一个测试": "This is a test",
+                "继续": { "value": "Continue", "platforms": { "web": "Click to continue" } },
+                "保存": { "value": "Save", "comment": "button label", "max_len": 12 },
+                "%1条消息": { "one": "%1 message", "other": "%1 messages" }
+            },
+            "namespace1": {}
+        });
+        assert!(Source::validate_json(&doc).is_empty());
+    }
+
+    #[test]
+    fn malformed_document() {
+        let doc = json!({
+            "_meta": { "display_name": 3, "direction": "up" },
+            "common": { "ok": "fine", "a/b": ["x"], "n~": 12 },
+            "broken": "not a namespace",
+            "variants": {
+                "a": { "platforms": { "web": 1 } },
+                "b": { "value": "B", "note": "?" },
+                "c": { "value": "C", "max_len": -2 },
+                "d": { "one": "D", "few": 3 }
+            }
+        });
+        let violations = Source::validate_json(&doc);
+        let pointers: Vec<&str> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/_meta/direction",
+                "/_meta/display_name",
+                "/broken",
+                "/common/a~1b",
+                "/common/n~0",
+                "/variants/a",
+                "/variants/a/platforms/web",
+                "/variants/b/note",
+                "/variants/c/max_len",
+                "/variants/d",
+                "/variants/d/few"
+            ]
+        );
+        assert_eq!(
+            violations[3].to_string(),
+            "/common/a~1b: expected a string or an object, found an array"
+        );
+        assert_eq!(
+            violations[5].to_string(),
+            r#"/variants/a: missing field "value""#
+        );
+
+        let violations = Source::validate_json(&json!([]));
+        assert_eq!(
+            violations[0].to_string(),
+            "/: expected an object, found an array"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Snapshots of a whole translator, e.g. to hand catalogs to worker processes. The bytes of
+//! [`I18n::to_bytes`] are behind the `snapshot` feature.
+use crate::{sorted, I18n, Source};
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::{Serialize, Serializer};
+use std::collections::HashMap;
+
+/// The layout of snapshots, bumped whenever it changes.
+const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(serde::Serialize)]
+struct SnapshotRef<'a> {
+    version: u32,
+    lang: &'a str,
+    #[serde(serialize_with = "sorted_sources")]
+    sources: &'a HashMap<String, Source>,
+    #[serde(serialize_with = "sorted_sources")]
+    overrides: &'a HashMap<String, Source>,
+}
+
+fn sorted_sources<S: Serializer>(
+    sources: &&HashMap<String, Source>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    sorted(sources, serializer)
+}
+
+#[derive(serde::Deserialize)]
+struct Snapshot {
+    version: u32,
+    lang: String,
+    sources: HashMap<String, Source>,
+    overrides: HashMap<String, Source>,
+}
+
+/// The active language, the loaded catalogs and the overrides, together with a layout version.
+/// Languages registered by [`I18n::set_source_lazy`] that were never looked up, other source
+/// sets and the settings are left out.
+impl Serialize for I18n {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SnapshotRef {
+            version: SNAPSHOT_VERSION,
+            lang: &self.lang,
+            sources: &self.source,
+            overrides: &self.overrides,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// A translator for the language and catalogs of a snapshot, with default settings. Fails on a
+/// snapshot of another layout version.
+impl<'de> Deserialize<'de> for I18n {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let snapshot = Snapshot::deserialize(deserializer)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(D::Error::custom(format!(
+                "unsupported snapshot version {}, expected {SNAPSHOT_VERSION}",
+                snapshot.version
+            )));
+        }
+        let mut i18n = I18n::new(&snapshot.lang);
+        i18n.source = snapshot.sources;
+        i18n.overrides = snapshot.overrides;
+        Ok(i18n)
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl I18n {
+    /// The snapshot of this translator in CBOR, see its `Serialize` impl. Catalogs are written in
+    /// key order, so unchanged catalogs give identical bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        // writing into a Vec never fails
+        let _ = ciborium::into_writer(self, &mut bytes);
+        bytes
+    }
+
+    /// Reads back a snapshot written by [`I18n::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<I18n> {
+        ciborium::from_reader(bytes)
+            .map_err(|err| anyhow::anyhow!("[snapshot error]: can't read the snapshot, {err}."))
+    }
+}
+
+#[cfg(all(test, feature = "snapshot"))]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    #[test]
+    fn round_trip() {
+        let mut i18n = I18n::new("cn");
+        i18n.set_source(Path::new("./source"));
+        i18n.set_lang("de");
+        i18n.set_override("fr", "菜单", "打开文件…", "Ouvrir un fichier…");
+        i18n.set_override("de", "common", "这是一个测试", "Das ist ein Test ✓");
+        let bytes = i18n.to_bytes();
+        let restored = I18n::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.to_bytes(), bytes);
+        assert_eq!(restored.current_lang(), "DE");
+        let sorted = |mut langs: Vec<String>| {
+            langs.sort();
+            langs
+        };
+        assert_eq!(sorted(restored.languages()), sorted(i18n.languages()));
+        for (restored, original) in [
+            (&restored.source, &i18n.source),
+            (&restored.overrides, &i18n.overrides),
+        ] {
+            assert_eq!(
+                serde_json::to_string(&restored.iter().collect::<BTreeMap<_, _>>()).unwrap(),
+                serde_json::to_string(&original.iter().collect::<BTreeMap<_, _>>()).unwrap()
+            );
+        }
+        let mut restored = restored;
+        assert_eq!(
+            restored.translate("这是一个测试", None),
+            "Das ist ein Test ✓"
+        );
+        restored.set_lang("fr");
+        assert_eq!(
+            restored.translate("打开文件…", Some("菜单".to_string())),
+            "Ouvrir un fichier…"
+        );
+    }
+
+    #[test]
+    fn unreadable_snapshots() {
+        let mut bytes = vec![];
+        let snapshot = SnapshotRef {
+            version: SNAPSHOT_VERSION + 1,
+            lang: "EN",
+            sources: &HashMap::new(),
+            overrides: &HashMap::new(),
+        };
+        ciborium::into_writer(&snapshot, &mut bytes).unwrap();
+        let err = I18n::from_bytes(&bytes).unwrap_err().to_string();
+        assert!(err.contains("unsupported snapshot version 2"), "{err}");
+        assert!(I18n::from_bytes(b"{}").is_err());
+    }
+}
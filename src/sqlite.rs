@@ -0,0 +1,211 @@
+//! Catalogs stored in SQLite, behind the `sqlite` feature.
+//!
+//! Translations live in one table:
+//! ```sql
+//! CREATE TABLE translations (
+//!     lang      TEXT NOT NULL,
+//!     namespace TEXT NOT NULL,
+//!     key       TEXT NOT NULL,
+//!     value     TEXT NOT NULL,
+//!     PRIMARY KEY (lang, namespace, key)
+//! );
+//! ```
+//! Only the `value` of an [`Entry`] is stored; platform variants, comments and `_meta` are not.
+use crate::{normalize_key, Entry, I18n, LoadWarning, Source};
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Creates the `translations` table, see the [module documentation](self).
+pub const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS translations (
+    lang      TEXT NOT NULL,
+    namespace TEXT NOT NULL,
+    key       TEXT NOT NULL,
+    value     TEXT NOT NULL,
+    PRIMARY KEY (lang, namespace, key)
+)";
+
+/// Reads every translation of `conn` in one query, keyed by uppercased language.
+pub fn load(conn: &Connection) -> Result<HashMap<String, Source>> {
+    let tables: i64 = conn.query_row(
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'translations'",
+        [],
+        |row| row.get(0),
+    )?;
+    if tables == 0 {
+        bail!("[sqlite error]: the database has no `translations` table.");
+    }
+    let mut stmt = conn
+        .prepare("SELECT lang, namespace, key, value FROM translations")
+        .context(
+            "[sqlite error]: `translations` must have the columns lang, namespace, key and value.",
+        )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+    let mut sources: HashMap<String, Source> = HashMap::new();
+    for row in rows {
+        let (lang, ns, key, value) =
+            row.context("[sqlite error]: lang, namespace, key and value must be non-null text.")?;
+        sources
+            .entry(lang.to_uppercase())
+            .or_default()
+            .namespaces
+            .entry(ns)
+            .or_default()
+            .insert(normalize_key(&key).into_owned(), Entry::from(value));
+    }
+    Ok(sources)
+}
+
+/// Writes `sources` into `conn` in one transaction, creating the table if needed and replacing
+/// the rows of keys that already exist. Rows of other keys are kept.
+pub fn save(conn: &mut Connection, sources: &HashMap<String, Source>) -> Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute(SCHEMA, [])?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT OR REPLACE INTO translations (lang, namespace, key, value) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for (lang, source) in sources {
+            for (ns, entries) in &source.namespaces {
+                for (key, entry) in entries {
+                    insert.execute((lang, ns, key, &entry.value))?;
+                }
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+impl I18n {
+    /// Replaces the loaded sources with the catalogs of the SQLite database at `path`, see
+    /// [`sqlite`](crate::sqlite) for the expected schema. The current sources are kept on error.
+    pub fn set_source_sqlite(&mut self, path: &Path) -> Result<()> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("[sqlite error]: can't open {}.", path.display()))?;
+        let (sources, warnings) = self.load_sqlite(&conn)?;
+        self.replace_sources(Some(path), sources, warnings);
+        Ok(())
+    }
+
+    /// Like [`I18n::set_source_sqlite`] with an open connection, e.g. to a `:memory:` database.
+    /// [`I18n::source_path`] becomes `None`.
+    pub fn set_source_sqlite_conn(&mut self, conn: &Connection) -> Result<()> {
+        let (sources, warnings) = self.load_sqlite(conn)?;
+        self.replace_sources(None, sources, warnings);
+        Ok(())
+    }
+
+    fn load_sqlite(
+        &self,
+        conn: &Connection,
+    ) -> Result<(HashMap<String, Source>, Vec<LoadWarning>)> {
+        let mut sources = load(conn)?;
+        let db = conn.path().map(Path::new).unwrap_or(Path::new(":memory:"));
+        let mut warnings = vec![];
+        for (lang, source) in sources.iter_mut() {
+            warnings.extend(source.file_warnings(lang, db, &self.load_options));
+        }
+        Ok((sources, warnings))
+    }
+
+    /// Writes every loaded language into the SQLite database at `path`, see [`save`].
+    pub fn save_sqlite(&self, path: &Path) -> Result<()> {
+        let mut conn = Connection::open(path)
+            .with_context(|| format!("[sqlite error]: can't open {}.", path.display()))?;
+        self.save_sqlite_conn(&mut conn)
+    }
+
+    /// Like [`I18n::save_sqlite`] with an open connection.
+    pub fn save_sqlite_conn(&self, conn: &mut Connection) -> Result<()> {
+        let mut sources = HashMap::new();
+        for lang in self.languages() {
+            if let Some(source) = self.lang_source(&lang) {
+                sources.insert(lang, source.clone());
+            }
+        }
+        save(conn, &sources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(SCHEMA, []).unwrap();
+        conn.execute_batch(
+            "INSERT INTO translations VALUES ('en', 'common', '这是一个测试', 'This is a test');
+             INSERT INTO translations VALUES ('en', 'dialog', '取消', 'Dismiss');
+             INSERT INTO translations VALUES ('de', 'common', '这是一个测试', 'Das ist ein Test');",
+        )
+        .unwrap();
+        let mut i18n = I18n::new("en");
+        i18n.set_source_sqlite_conn(&conn).unwrap();
+        assert_eq!(i18n.translate("这是一个测试", None), "This is a test");
+        assert_eq!(
+            i18n.translate("取消", Some("dialog".to_string())),
+            "Dismiss"
+        );
+        i18n.set_lang("de");
+        assert_eq!(i18n.translate("这是一个测试", None), "Das ist ein Test");
+
+        let mut copy = Connection::open_in_memory().unwrap();
+        i18n.merge_source(HashMap::from([(
+            "DE".to_string(),
+            serde_json::from_str(r#"{"common": {"保存": "Speichern"}}"#).unwrap(),
+        )]));
+        i18n.save_sqlite_conn(&mut copy).unwrap();
+        let mut again = I18n::new("de");
+        again.set_source_sqlite_conn(&copy).unwrap();
+        assert_eq!(again.translate("保存", None), "Speichern");
+        assert_eq!(again.languages_with_key("这是一个测试", None), ["DE", "EN"]);
+
+        let file = crate::tests::temp_path("easy_i18n_catalogs.sqlite");
+        let _ = std::fs::remove_file(&file);
+        again.save_sqlite(&file).unwrap();
+        let mut from_file = I18n::new("de");
+        from_file.set_source_sqlite(&file).unwrap();
+        assert_eq!(from_file.translate("保存", None), "Speichern");
+        assert_eq!(from_file.source_path(), Some(file.as_path()));
+        from_file.set_source_sqlite_conn(&copy).unwrap();
+        assert_eq!(from_file.source_path(), None);
+    }
+
+    #[test]
+    fn schema_errors() {
+        let mut i18n = I18n::new("en");
+        let conn = Connection::open_in_memory().unwrap();
+        let err = i18n.set_source_sqlite_conn(&conn).unwrap_err();
+        assert!(err.to_string().contains("no `translations` table"), "{err}");
+
+        conn.execute("CREATE TABLE translations (lang TEXT, text TEXT)", [])
+            .unwrap();
+        let err = i18n.set_source_sqlite_conn(&conn).unwrap_err();
+        assert!(err.to_string().contains("must have the columns"), "{err}");
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE translations (lang, namespace, key, value)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO translations VALUES ('en', 'common', 'a', NULL)",
+            [],
+        )
+        .unwrap();
+        let err = i18n.set_source_sqlite_conn(&conn).unwrap_err();
+        assert!(err.to_string().contains("non-null text"), "{err}");
+    }
+}
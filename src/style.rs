@@ -0,0 +1,333 @@
+//! Placeholder conventions, chosen per language.
+use crate::prelude::*;
+use crate::{Found, I18n, Source};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+static PERCENT_REG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"%%|%\{(\d+)(?::(\w+))?\}|%(\d+)|%\{\*\}|%\*").unwrap());
+
+static BRACE_REG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{|\}\}|\{(\d+)(?::(\w+))?\}|\{\*\}").unwrap());
+
+/// How the placeholders of a template are written, set for a language by
+/// [`I18n::set_placeholder_style_for`] or by the `placeholder_style` of its `_meta`.
+///
+/// A doubled delimiter stands for itself once the template is interpolated: `%%` in the
+/// `Percent` style, `{{` and `}}` in the `Brace` one, so `"100%% of %1"` gives `100% of ...` and
+/// `"{{0}} is {0}"` gives `{0} is ...`. Like placeholders, escapes are kept as written by a call
+/// without any value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderStyle {
+    /// `%1`, `%{1:upper}`, counted from 1, with `%0` for all the values and `%*` or `%{*}` for
+    /// the rest.
+    #[default]
+    Percent,
+    /// `{0}`, `{0:upper}`, counted from 0 as in .NET and Java catalogs. Inline plurals
+    /// (`{file|files}`) still work, there is no placeholder for all the values; `{*}` is the
+    /// rest.
+    Brace,
+}
+
+/// What a match of [`PlaceholderStyle::regex`] stands for.
+pub(crate) enum Token {
+    /// A doubled delimiter.
+    Escape,
+    /// A placeholder for a value, numbered `%N` style: 1 for the first value, 0 for all of them,
+    /// `None` when the index doesn't fit.
+    Placeholder(Option<usize>),
+    /// The rest placeholder: the values no numbered placeholder uses, see
+    /// [`I18n::trans_with_inter`].
+    Rest,
+}
+
+impl PlaceholderStyle {
+    /// The pattern of a placeholder or an escape: the index is group 1 or 3, the modifier
+    /// group 2, and an escape or the rest placeholder has neither.
+    pub(crate) fn regex(&self) -> &'static Regex {
+        match self {
+            PlaceholderStyle::Percent => &PERCENT_REG,
+            PlaceholderStyle::Brace => &BRACE_REG,
+        }
+    }
+
+    pub(crate) fn token(&self, caps: &Captures) -> Token {
+        let Some(index) = caps.get(1).or_else(|| caps.get(3)) else {
+            return match caps[0].contains('*') {
+                true => Token::Rest,
+                false => Token::Escape,
+            };
+        };
+        let index = index.as_str().parse::<usize>().ok();
+        Token::Placeholder(match self {
+            PlaceholderStyle::Percent => index,
+            PlaceholderStyle::Brace => index.and_then(|index| index.checked_add(1)),
+        })
+    }
+}
+
+impl Source {
+    /// Compiles the templates of this source for `style`, see [`Template`](crate::template).
+    pub(crate) fn compile_templates(&mut self, style: PlaceholderStyle) {
+        for entry in self.namespaces.values_mut().flat_map(|e| e.values_mut()) {
+            if entry.template.style().is_some_and(|s| s != style) {
+                entry.template = crate::template::Template::compile(&entry.value, style);
+            }
+        }
+    }
+}
+
+impl I18n {
+    /// Sets the placeholder style of the languages without one of their own,
+    /// [`PlaceholderStyle::Percent`] by default. Keys are always read in this style when they are
+    /// shown untranslated.
+    pub fn set_placeholder_style(&mut self, style: PlaceholderStyle) {
+        self.placeholder_style = style;
+        self.compile_templates(None);
+    }
+
+    /// Sets the placeholder style of the templates of `lang`, e.g. for a catalog imported from a
+    /// system writing `{0}`, so that locales can be migrated one at a time. Wins over the
+    /// `placeholder_style` of the `_meta` of the language file.
+    pub fn set_placeholder_style_for(&mut self, lang: &str, style: PlaceholderStyle) {
+        let lang = lang.to_uppercase();
+        self.placeholder_styles.insert(lang.clone(), style);
+        self.compile_templates(Some(&lang));
+    }
+
+    /// Makes `lang` use the style of its `_meta`, or the one set by
+    /// [`I18n::set_placeholder_style`], again.
+    pub fn clear_placeholder_style_for(&mut self, lang: &str) {
+        let lang = lang.to_uppercase();
+        self.placeholder_styles.remove(&lang);
+        self.compile_templates(Some(&lang));
+    }
+
+    /// The placeholder style of the templates of `lang`.
+    pub fn placeholder_style(&self, lang: &str) -> PlaceholderStyle {
+        self.stored_style(&lang.to_uppercase())
+    }
+
+    /// [`I18n::placeholder_style`] of a language named as stored, uppercased.
+    pub(crate) fn stored_style(&self, lang: &str) -> PlaceholderStyle {
+        self.placeholder_styles
+            .get(lang)
+            .copied()
+            .or_else(|| {
+                let meta = self.lang_source(lang)?.meta.as_ref()?;
+                meta.placeholder_style
+            })
+            .unwrap_or(self.placeholder_style)
+    }
+
+    /// Compiles the templates of `lang`, or of every language, catalogs and overrides, for the
+    /// style of their language. Lazily loaded languages are compiled for the style of their
+    /// `_meta` and rendered from the raw value when it isn't theirs.
+    pub(crate) fn compile_templates(&mut self, lang: Option<&str>) {
+        let langs: Vec<String> = match lang {
+            Some(lang) => vec![lang.to_string()],
+            None => self
+                .source
+                .keys()
+                .chain(self.overrides.keys())
+                .cloned()
+                .collect(),
+        };
+        for lang in langs {
+            let style = self.stored_style(&lang);
+            if let Some(source) = self.source.get_mut(&lang) {
+                source.compile_templates(style);
+            }
+            if let Some(overrides) = self.overrides.get_mut(&lang) {
+                overrides.compile_templates(style);
+            }
+        }
+        self.translate_cache.clear();
+    }
+
+    /// The style of the template of a lookup: the one of the language the entry was found in, or
+    /// the default style for a key shown untranslated.
+    pub(crate) fn template_style(&self, found: Option<&Found>) -> PlaceholderStyle {
+        match found {
+            None => self.placeholder_style,
+            Some(found) if found.from_base => {
+                self.stored_style(self.base_lang.as_deref().unwrap_or_default())
+            }
+            Some(_) => self.stored_style(&self.active_lang()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::tests::source;
+
+    #[test]
+    fn style_per_language() {
+        let mut i18n = I18n::new("en");
+        i18n.insert_source("en", source("common", &[("你好%1，%2", "Hello %1, %2")]));
+        i18n.insert_source(
+            "de",
+            source("common", &[("你好%1，%2", "Hallo {1}, {0:upper}")]),
+        );
+        i18n.set_placeholder_style_for("de", PlaceholderStyle::Brace);
+        let vals = || vec!["Ada".to_string(), "welcome".to_string()];
+        assert_eq!(
+            i18n.trans_with_inter("你好%1，%2", vals(), None),
+            "Hello Ada, welcome"
+        );
+        i18n.set_lang("de");
+        assert_eq!(
+            i18n.trans_with_inter("你好%1，%2", vals(), None),
+            "Hallo welcome, ADA"
+        );
+        // a missing key is read in the default style
+        assert_eq!(i18n.trans_with_inter("%1 {0}", vals(), None), "Ada {0}");
+        // so is a template from the base language
+        i18n.insert_source("de", source("common", &[("已保存%1", "{0} gespeichert")]));
+        i18n.insert_source("en", source("common", &[("未读%1", "%1 unread")]));
+        i18n.set_base_lang("en");
+        assert_eq!(
+            i18n.trans_with_inter("已保存%1", vals(), None),
+            "Ada gespeichert"
+        );
+        assert_eq!(i18n.trans_with_inter("未读%1", vals(), None), "Ada unread");
+        let checked = i18n.trans_with_inter_checked("已保存%1", vec!["1".to_string()], None);
+        assert_eq!(checked.unwrap(), "1 gespeichert");
+        let mut out = String::new();
+        i18n.trans_with_inter_into(&mut out, "已保存%1", &[&7], None)
+            .unwrap();
+        assert_eq!(out, "7 gespeichert");
+
+        i18n.clear_placeholder_style_for("DE");
+        assert_eq!(i18n.placeholder_style("de"), PlaceholderStyle::Percent);
+        i18n.set_placeholder_style(PlaceholderStyle::Brace);
+        assert_eq!(i18n.placeholder_style("de"), PlaceholderStyle::Brace);
+        assert_eq!(i18n.trans_with_inter("%1 {0}", vals(), None), "%1 Ada");
+    }
+
+    #[test]
+    fn rest_placeholder() {
+        let mut i18n = I18n::new("cn");
+        i18n.insert_source(
+            "en",
+            source("common", &[("%1 失败，原因：%*", "%1 failed: %*")]),
+        );
+        i18n.insert_source("cn", Source::default());
+        let vals = || {
+            ["a.txt", "磁盘已满", "权限不足"]
+                .map(str::to_string)
+                .to_vec()
+        };
+        assert_eq!(
+            i18n.trans_with_inter("%1 失败，原因：%*", vals(), None),
+            "a.txt 失败，原因：磁盘已满、权限不足"
+        );
+        assert_eq!(
+            i18n.trans_with_inter("%2 %{*}", vals(), None),
+            "磁盘已满 a.txt、权限不足"
+        );
+        // without values left, the rest is empty; without any value, kept as written
+        assert_eq!(
+            i18n.trans_with_inter("%1%2%3%*", vals(), None),
+            "a.txt磁盘已满权限不足"
+        );
+        assert_eq!(i18n.trans_with_inter("%1 %*", vec![], None), "%1 %*");
+
+        i18n.set_lang("en");
+        assert_eq!(
+            i18n.trans_with_inter("%1 失败，原因：%*", vals(), None),
+            "a.txt failed: 磁盘已满, 权限不足"
+        );
+        assert_eq!(
+            i18n.trans_with_inter_html("%*", vec!["<b>".to_string()], None),
+            "&lt;b&gt;"
+        );
+        i18n.set_placeholder_style(PlaceholderStyle::Brace);
+        assert_eq!(
+            i18n.trans_with_inter("{1}: {*}", vals(), None),
+            "磁盘已满: a.txt, 权限不足"
+        );
+        assert!(i18n
+            .trans_with_inter_checked("{0} {*}", vals(), None)
+            .is_ok());
+    }
+
+    fn compiled_style(i18n: &I18n, lang: &str, key: &str) -> Option<PlaceholderStyle> {
+        let source = i18n.lang_source(lang).unwrap();
+        source.get_entry(key, "common").unwrap().template.style()
+    }
+
+    #[test]
+    fn style_from_meta() {
+        let mut i18n = I18n::new("de");
+        let de: Source = serde_json::from_str(
+            r#"{"_meta": {"placeholder_style": "brace"}, "common": {"你好%1": "Hallo {0}"}}"#,
+        )
+        .unwrap();
+        i18n.insert_source("de", de);
+        i18n.insert_source("en", source("common", &[("你好%1", "Hello %1")]));
+        let vals = || vec!["Ada".to_string()];
+        assert_eq!(
+            compiled_style(&i18n, "DE", "你好%1"),
+            Some(PlaceholderStyle::Brace)
+        );
+        assert_eq!(i18n.trans_with_inter("你好%1", vals(), None), "Hallo Ada");
+        i18n.set_override("de", "common", "再见%1", "Tschüss {0}");
+        assert_eq!(i18n.trans_with_inter("再见%1", vals(), None), "Tschüss Ada");
+        i18n.set_lang("en");
+        assert_eq!(i18n.trans_with_inter("你好%1", vals(), None), "Hello Ada");
+
+        // the setting wins over `_meta`, and templates are recompiled for it
+        i18n.set_placeholder_style_for("de", PlaceholderStyle::Percent);
+        assert_eq!(
+            compiled_style(&i18n, "DE", "你好%1"),
+            Some(PlaceholderStyle::Percent)
+        );
+        i18n.set_lang("de");
+        assert_eq!(i18n.trans_with_inter("你好%1", vals(), None), "Hallo {0}");
+        i18n.clear_placeholder_style_for("de");
+        assert_eq!(i18n.trans_with_inter("你好%1", vals(), None), "Hallo Ada");
+        assert_eq!(i18n.trans_with_inter("再见%1", vals(), None), "Tschüss Ada");
+    }
+
+    #[test]
+    fn escaped_delimiters() {
+        let mut i18n = I18n::new("en");
+        i18n.insert_source(
+            "en",
+            source(
+                "common",
+                &[
+                    ("进度", "%1: 100%% done, %%1 is literal"),
+                    ("格式", "{{0}} is {0}"),
+                ],
+            ),
+        );
+        i18n.insert_source("de", source("common", &[("格式", "{{{0}}} and }} {{")]));
+        i18n.set_placeholder_style_for("de", PlaceholderStyle::Brace);
+        let vals = || vec!["x".to_string()];
+        assert_eq!(
+            i18n.trans_with_inter("进度", vals(), None),
+            "x: 100% done, %1 is literal"
+        );
+        // `{` means nothing special in the percent style
+        assert_eq!(i18n.trans_with_inter("格式", vals(), None), "{{0}} is {0}");
+        // without values the template is kept as written
+        assert_eq!(
+            i18n.trans_with_inter("进度", vec![], None),
+            "%1: 100%% done, %%1 is literal"
+        );
+        let checked = i18n.trans_with_inter_checked("进度", vals(), None);
+        assert_eq!(checked.unwrap(), "x: 100% done, %1 is literal");
+        i18n.set_lang("de");
+        assert_eq!(i18n.trans_with_inter("格式", vals(), None), "{x} and } {");
+        // the regex path gives the same result as the compiled template
+        i18n.set_pseudo(true);
+        i18n.set_pseudo_expansion(1.0);
+        let pseudo = i18n.trans_with_inter("格式", vals(), None);
+        assert!(pseudo.contains("{x}") && pseudo.contains("} {"), "{pseudo}");
+    }
+}
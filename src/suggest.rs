@@ -0,0 +1,93 @@
+//! "Did you mean" suggestions for keys that aren't found.
+//!
+//! Keys are compared by normalized Levenshtein similarity after folding full-width ASCII forms
+//! (`？`, `！`, `：`, `Ａ`, the ideographic space, ...) to their ASCII counterparts, so the most
+//! common typos, a full-width question mark for an ASCII one, score just below an exact match.
+use crate::prelude::*;
+use crate::{I18n, Namespace, DEFAULT_NS};
+
+/// Suggestions scoring below this are dropped.
+pub(crate) const THRESHOLD: f32 = 0.6;
+
+fn fold(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Similarity of two keys in `[0, 1]`: 1 when equal, 0.99 when equal up to character width.
+pub(crate) fn score(a: &str, b: &str) -> f32 {
+    if a == b {
+        return 1.0;
+    }
+    let a: Vec<char> = a.chars().map(fold).collect();
+    let b: Vec<char> = b.chars().map(fold).collect();
+    if a == b {
+        return 0.99;
+    }
+    let len = a.len().max(b.len());
+    1.0 - levenshtein(&a, &b) as f32 / len as f32
+}
+
+impl I18n {
+    /// The keys of namespace `ns` (`common` when `None`) in `lang` closest to `key`, best first,
+    /// with their similarity in `[0, 1]`. At most `limit` keys scoring at least 0.6 are returned.
+    ///
+    /// Every key of the namespace is scored, so keep this out of hot paths.
+    pub fn suggest(
+        &self,
+        key: &str,
+        ns: Option<Namespace>,
+        lang: &str,
+        limit: usize,
+    ) -> Vec<(String, f32)> {
+        let ns = ns.unwrap_or(DEFAULT_NS.to_string());
+        let Some(entries) = self
+            .lang_source(&lang.to_uppercase())
+            .and_then(|source| source.namespaces.get(&ns))
+        else {
+            return vec![];
+        };
+        let mut scored: Vec<(String, f32)> = entries
+            .keys()
+            .map(|candidate| (candidate.clone(), score(key, candidate)))
+            .filter(|(candidate, score)| *score >= THRESHOLD && candidate != key)
+            .collect();
+        scored.sort_by(|(a, x), (b, y)| y.total_cmp(x).then_with(|| a.cmp(b)));
+        scored.truncate(limit);
+        scored
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores() {
+        assert_eq!(score("确认删除？", "确认删除?"), 0.99);
+        assert_eq!(score("Ｓａｖｅ", "Save"), 0.99);
+        assert_eq!(score("abc", "abc"), 1.0);
+        assert_eq!(score("保存文件", "保存文档"), 0.75);
+        assert_eq!(score("", "abc"), 0.0);
+        assert!(score("这是一个测试", "Save") < THRESHOLD);
+    }
+}
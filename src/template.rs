@@ -0,0 +1,174 @@
+//! Placeholder positions of a translation, parsed once when its [`Entry`](crate::Entry) is built.
+//!
+//! Interpolating a compiled template walks its segments and writes the values in between, no
+//! regex runs per call. Values with inline plurals (`{message|messages}`) depend on the values
+//! themselves and are still rendered from the raw string, and so are conditional sections
+//! (`%{2?（%2）}`).
+//!
+//! A template is compiled for the [`PlaceholderStyle`] of its language, and recompiled when that
+//! style changes.
+use crate::prelude::*;
+use crate::style::Token;
+use crate::{PlaceholderStyle, PLURAL_REG, SECTION_REG};
+use core::hash::{Hash, Hasher};
+use core::ops::Range;
+
+/// A part of a compiled template, as byte ranges of the value it was compiled from.
+#[derive(Debug, Clone)]
+pub(crate) enum Segment {
+    Literal(Range<usize>),
+    /// A doubled delimiter, `%%` or `{{`, written once.
+    Escape(Range<usize>),
+    Placeholder {
+        /// `None` when the index doesn't fit, the placeholder is then treated as missing.
+        index: Option<usize>,
+        modifier: Option<Range<usize>>,
+        raw: Range<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) enum Template {
+    /// Rendered from the raw value: inline plurals, conditional sections, or an entry built
+    /// without a template.
+    #[default]
+    Dynamic,
+    /// No placeholder at all, the value is written as is.
+    Plain(PlaceholderStyle),
+    Segments(PlaceholderStyle, Box<[Segment]>),
+}
+
+impl Template {
+    pub(crate) fn compile(value: &str, style: PlaceholderStyle) -> Template {
+        if (value.contains('|') && PLURAL_REG.is_match(value))
+            || (value.contains('?') && SECTION_REG.is_match(value))
+        {
+            return Template::Dynamic;
+        }
+        let mut segments = vec![];
+        let mut last = 0;
+        for caps in style.regex().captures_iter(value) {
+            let whole = caps.get(0).unwrap();
+            if last < whole.start() {
+                segments.push(Segment::Literal(last..whole.start()));
+            }
+            last = whole.end();
+            segments.push(match style.token(&caps) {
+                Token::Escape => Segment::Escape(whole.range()),
+                // which values it writes depends on the other placeholders
+                Token::Rest => return Template::Dynamic,
+                Token::Placeholder(index) => Segment::Placeholder {
+                    index,
+                    modifier: caps.get(2).map(|m| m.range()),
+                    raw: whole.range(),
+                },
+            });
+        }
+        if segments.is_empty() {
+            return Template::Plain(style);
+        }
+        if last < value.len() {
+            segments.push(Segment::Literal(last..value.len()));
+        }
+        Template::Segments(style, segments.into_boxed_slice())
+    }
+
+    /// The style this template was compiled for, `None` when it is rendered from the raw value.
+    pub(crate) fn style(&self) -> Option<PlaceholderStyle> {
+        match self {
+            Template::Dynamic => None,
+            Template::Plain(style) | Template::Segments(style, _) => Some(*style),
+        }
+    }
+}
+
+// a template is derived from the value of its entry, which is compared and hashed instead
+impl PartialEq for Template {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Template {}
+
+impl Hash for Template {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn parts(value: &str, style: PlaceholderStyle) -> Vec<String> {
+        let Template::Segments(_, segments) = Template::compile(value, style) else {
+            panic!("not compiled");
+        };
+        segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(range) => format!("'{}'", &value[range.clone()]),
+                Segment::Escape(range) => format!("<{}>", &value[range.clone()]),
+                Segment::Placeholder {
+                    index, modifier, ..
+                } => format!("{index:?}{:?}", modifier.clone().map(|m| &value[m])),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn segments() {
+        assert!(matches!(
+            Template::compile("Save", PlaceholderStyle::Percent),
+            Template::Plain(PlaceholderStyle::Percent)
+        ));
+        assert!(matches!(
+            Template::compile("%1 {file|files}", PlaceholderStyle::Percent),
+            Template::Dynamic
+        ));
+        let value = "Hi %{1:upper}, %2 of %300!";
+        let parts = parts(value, PlaceholderStyle::Percent);
+        assert_eq!(
+            parts,
+            [
+                "'Hi '",
+                "Some(1)Some(\"upper\")",
+                "', '",
+                "Some(2)None",
+                "' of '",
+                "Some(300)None",
+                "'!'"
+            ]
+        );
+    }
+
+    #[test]
+    fn styles_and_escapes() {
+        assert!(matches!(
+            Template::compile("Save {0}", PlaceholderStyle::Percent),
+            Template::Plain(PlaceholderStyle::Percent)
+        ));
+        assert_eq!(
+            parts("100%% of %1, %%2", PlaceholderStyle::Percent),
+            [
+                "'100'",
+                "<%%>",
+                "' of '",
+                "Some(1)None",
+                "', '",
+                "<%%>",
+                "'2'"
+            ]
+        );
+        assert_eq!(
+            parts("{{{0}}} {1:upper} %1", PlaceholderStyle::Brace),
+            [
+                "<{{>",
+                "Some(1)None",
+                "<}}>",
+                "' '",
+                "Some(2)Some(\"upper\")",
+                "' %1'"
+            ]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Import of translation memories in TMX format.
+//!
+//! Only the common subset is read: every `<tu>` has one `<tuv>` in the source language, whose
+//! segment becomes the key, and one or more `<tuv>` in other languages, whose segments become the
+//! values of those languages in the `common` namespace. The source language is the `srclang` of
+//! the `<tu>` or, failing that, of the `<header>`. Inline codes (`<ph>`, `<bpt>`, ...) are dropped,
+//! the text of `<hi>` is kept.
+use crate::{normalize_key, Entry, Source, DEFAULT_NS};
+use anyhow::{bail, Context, Result};
+use quick_xml::encoding::Decoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+/// Reads the TMX file at `path` into one [`Source`] per target language, keyed by the uppercased
+/// language code like the sources read by `set_source`.
+pub fn import(path: &Path) -> Result<HashMap<String, Source>> {
+    let reader =
+        Reader::from_file(path).with_context(|| format!("can't open {}", path.display()))?;
+    parse(reader).with_context(|| format!("[tmx error]: can't import {}.", path.display()))
+}
+
+/// Like [`import`], for a document already in memory.
+pub fn import_str(xml: &str) -> Result<HashMap<String, Source>> {
+    parse(Reader::from_str(xml)).context("[tmx error]: can't import the document.")
+}
+
+/// Inline elements holding native codes rather than text.
+const CODES: &[&[u8]] = &[b"bpt", b"ept", b"ph", b"it", b"ut"];
+
+/// The value of the first of `names` present on `e`.
+fn attr(e: &BytesStart, names: &[&str], decoder: Decoder) -> Result<Option<String>> {
+    for name in names {
+        if let Some(attr) = e.try_get_attribute(name)? {
+            return Ok(Some(attr.decode_and_unescape_value(decoder)?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn parse<R: BufRead>(mut reader: Reader<R>) -> Result<HashMap<String, Source>> {
+    let mut sources: HashMap<String, Source> = HashMap::new();
+    let mut buf = vec![];
+    let mut header_lang: Option<String> = None;
+    // source language of the current unit and its variants as (language, segment)
+    let mut unit_lang: Option<String> = None;
+    let mut variants: Vec<(String, String)> = vec![];
+    let mut variant_lang: Option<String> = None;
+    let mut segment: Option<String> = None;
+    let mut codes = 0;
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.name().as_ref() {
+                    b"header" => {
+                        header_lang = attr(e, &["srclang"], reader.decoder())?;
+                    }
+                    b"tu" => {
+                        unit_lang = attr(e, &["srclang"], reader.decoder())?;
+                        variants.clear();
+                    }
+                    b"tuv" => variant_lang = attr(e, &["xml:lang", "lang"], reader.decoder())?,
+                    b"seg" if !empty => segment = Some(String::new()),
+                    name if CODES.contains(&name) && !empty && segment.is_some() => codes += 1,
+                    _ => {}
+                }
+            }
+            Event::Text(text) if codes == 0 => {
+                if let Some(segment) = segment.as_mut() {
+                    segment.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(data) if codes == 0 => {
+                if let Some(segment) = segment.as_mut() {
+                    segment.push_str(std::str::from_utf8(&data)?);
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"seg" => {
+                    if let (Some(lang), Some(segment)) = (variant_lang.clone(), segment.take()) {
+                        variants.push((lang, segment));
+                    }
+                }
+                b"tu" => {
+                    let Some(src) = unit_lang.take().or_else(|| header_lang.clone()) else {
+                        bail!("translation unit without a source language");
+                    };
+                    add_unit(&mut sources, &src, &variants);
+                }
+                name if CODES.contains(&name) && segment.is_some() => codes -= 1,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(sources)
+}
+
+fn add_unit(sources: &mut HashMap<String, Source>, src: &str, variants: &[(String, String)]) {
+    let Some((_, key)) = variants
+        .iter()
+        .find(|(lang, _)| lang.eq_ignore_ascii_case(src))
+    else {
+        return;
+    };
+    let key = normalize_key(key).into_owned();
+    for (lang, value) in variants {
+        if lang.eq_ignore_ascii_case(src) {
+            continue;
+        }
+        sources
+            .entry(lang.to_uppercase())
+            .or_default()
+            .namespaces
+            .entry(DEFAULT_NS.to_string())
+            .or_default()
+            .insert(key.clone(), Entry::from(value.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tmx version="1.4">
+  <header srclang="zh-CN" datatype="plaintext" segtype="sentence" adminlang="en" o-tmf="x" creationtool="x" creationtoolversion="1"/>
+  <body>
+    <tu tuid="1">
+      <tuv xml:lang="zh-CN"><seg>这是一个测试</seg></tuv>
+      <tuv xml:lang="en"><seg>This is a test</seg></tuv>
+      <tuv xml:lang="de"><seg>Das ist ein Test</seg></tuv>
+    </tu>
+    <tu tuid="2">
+      <tuv xml:lang="zh-CN"><seg>点击<bpt i="1">&lt;b&gt;</bpt>保存<ept i="1">&lt;/b&gt;</ept></seg></tuv>
+      <tuv xml:lang="en"><seg>Click <bpt i="1">&lt;b&gt;</bpt>Save &amp; exit<ept i="1">&lt;/b&gt;</ept></seg></tuv>
+    </tu>
+    <tu tuid="3" srclang="en">
+      <tuv xml:lang="en"><seg><![CDATA[Tom & Jerry]]></seg></tuv>
+      <tuv xml:lang="de"><seg>Tom <hi>und</hi> Jerry</seg></tuv>
+    </tu>
+    <tu tuid="4">
+      <tuv xml:lang="en"><seg>no source segment</seg></tuv>
+    </tu>
+  </body>
+</tmx>"#;
+
+    #[test]
+    fn import_units() {
+        let sources = import_str(TMX).unwrap();
+        let mut langs: Vec<&String> = sources.keys().collect();
+        langs.sort();
+        assert_eq!(langs, ["DE", "EN"]);
+        let (en, de) = (&sources["EN"], &sources["DE"]);
+        assert_eq!(en.get("这是一个测试", "common"), Some("This is a test"));
+        assert_eq!(de.get("这是一个测试", "common"), Some("Das ist ein Test"));
+        assert_eq!(en.get("点击保存", "common"), Some("Click Save & exit"));
+        assert_eq!(de.get("Tom & Jerry", "common"), Some("Tom und Jerry"));
+        assert_eq!(en.namespaces["common"].len(), 2);
+    }
+
+    #[test]
+    fn source_language_required() {
+        let xml =
+            r#"<tmx><header/><body><tu><tuv xml:lang="en"><seg>a</seg></tuv></tu></body></tmx>"#;
+        assert!(import_str(xml).is_err());
+        assert!(import(Path::new("./missing.tmx")).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+//! Catalogs exchanged with translators as Excel workbooks, behind the `xlsx` feature.
+//!
+//! A workbook has one sheet per namespace, named after it. The first row holds the headers:
+//! `key`, `reference`, then one language code per column, and every other row one key:
+//!
+//! | key          | reference    | EN             | FR             |
+//! |--------------|--------------|----------------|----------------|
+//! | 这是一个测试 | 这是一个测试 | This is a test | C'est un test  |
+//!
+//! Only the `value` of an [`Entry`] is exchanged; platform variants, comments and `_meta` are
+//! not. Styling is ignored when reading, and so are rows without a key and the columns whose
+//! header is no language, like `notes`.
+use crate::{normalize_key, Entry, I18n, Source};
+use anyhow::{bail, Context, Result};
+use calamine::{open_workbook, Data, Reader, Xlsx};
+use rust_xlsxwriter::Workbook;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Reads the workbook at `path`, laid out as described in the [module documentation](self),
+/// into one [`Source`] per language column, keyed by uppercased language code. Empty cells are
+/// left out rather than read as empty translations, and formulas are read as their cached
+/// values; a formula without one is an error.
+///
+/// A column is a language when its header is a language tag, `fr`, `pt-BR` or `zh_Hans`; the
+/// others are skipped with a warning. See [`import_xlsx_langs`] to name the languages instead.
+pub fn import_xlsx(path: &Path) -> Result<HashMap<String, Source>> {
+    import(path, is_lang_tag)
+}
+
+/// Like [`import_xlsx`], reading the columns of `langs` only, compared case-insensitively.
+pub fn import_xlsx_langs(path: &Path, langs: &[&str]) -> Result<HashMap<String, Source>> {
+    import(path, |header| {
+        langs.iter().any(|lang| lang.eq_ignore_ascii_case(header))
+    })
+}
+
+/// A primary subtag of 2 or 3 letters, then subtags of 1 to 8 letters or digits.
+fn is_lang_tag(header: &str) -> bool {
+    let mut subtags = header.split(['-', '_']);
+    let primary = subtags.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|tag| {
+            (1..=8).contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+fn import(path: &Path, is_lang: impl Fn(&str) -> bool) -> Result<HashMap<String, Source>> {
+    let mut workbook: Xlsx<_> = open_workbook(path)
+        .with_context(|| format!("[xlsx error]: can't open {}.", path.display()))?;
+    let mut sources: HashMap<String, Source> = HashMap::new();
+    for ns in workbook.sheet_names() {
+        let range = workbook.worksheet_range(&ns)?;
+        let formulas = workbook.worksheet_formula(&ns)?;
+        if let Some((row, col)) = formulas.start() {
+            for (r, c, _) in formulas.used_cells() {
+                let at = (row + r as u32, col + c as u32);
+                if range
+                    .get_value(at)
+                    .is_none_or(|value| *value == Data::Empty)
+                {
+                    bail!(
+                        "[xlsx error]: cell {} of sheet {ns} is a formula without a cached value.",
+                        cell_name(at)
+                    );
+                }
+            }
+        }
+        let (top, left) = range.start().unwrap_or_default();
+        let mut rows = range.rows().enumerate();
+        let Some((_, header)) = rows.next() else {
+            continue;
+        };
+        let headers: Vec<String> = header.iter().map(|cell| cell.to_string()).collect();
+        let Some(key_col) = headers.iter().position(|h| h.trim() == "key") else {
+            bail!("[xlsx error]: sheet {ns} has no `key` column.");
+        };
+        let langs: Vec<bool> = headers
+            .iter()
+            .enumerate()
+            .map(|(c, header)| {
+                let header = header.trim();
+                if c == key_col || header.is_empty() || header == "reference" {
+                    return false;
+                }
+                let lang = is_lang(header);
+                if !lang {
+                    #[cfg(feature = "log")]
+                    log::warn!("skipped the column {header} of sheet {ns}, which is no language.");
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(sheet = %ns, column = header, "skipped a column that is no language");
+                }
+                lang
+            })
+            .collect();
+        for (r, row) in rows {
+            let key = row
+                .get(key_col)
+                .map(|cell| cell.to_string())
+                .unwrap_or_default();
+            if key.is_empty() {
+                continue;
+            }
+            for (c, (header, cell)) in headers.iter().zip(row).enumerate() {
+                if !langs[c] {
+                    continue;
+                }
+                let lang = header.trim();
+                let value = match cell {
+                    Data::Empty => continue,
+                    Data::Error(err) => {
+                        let at = (top + r as u32, left + c as u32);
+                        bail!(
+                            "[xlsx error]: cell {} of sheet {ns} is {err}.",
+                            cell_name(at)
+                        )
+                    }
+                    Data::String(value) => value.clone(),
+                    value => value.to_string(),
+                };
+                sources
+                    .entry(lang.to_uppercase())
+                    .or_default()
+                    .namespaces
+                    .entry(ns.clone())
+                    .or_default()
+                    .insert(normalize_key(&key).into_owned(), Entry::from(value));
+            }
+        }
+    }
+    Ok(sources)
+}
+
+/// `A1` for `(0, 0)`.
+fn cell_name((row, col): (u32, u32)) -> String {
+    let mut letters = vec![];
+    let mut col = col + 1;
+    while col > 0 {
+        letters.push(b'A' + ((col - 1) % 26) as u8);
+        col = (col - 1) / 26;
+    }
+    letters.reverse();
+    format!("{}{}", String::from_utf8_lossy(&letters), row + 1)
+}
+
+impl I18n {
+    /// Writes the catalogs of `langs` to the workbook at `path`, laid out as described in
+    /// [`xlsx`](crate::xlsx) and read back by [`import_xlsx`]. Every key of those languages, and
+    /// of the base language when one is set, gets a row, with keys and namespaces sorted. The
+    /// `reference` column holds the text to translate from: the value in the
+    /// [base language](I18n::set_base_lang) if set, the key otherwise. A language without a
+    /// catalog gets an empty column to fill.
+    pub fn export_xlsx(&self, path: &Path, langs: &[&str]) -> Result<()> {
+        let langs: Vec<String> = langs.iter().map(|lang| lang.to_uppercase()).collect();
+        let base = self
+            .base_lang
+            .as_deref()
+            .and_then(|lang| self.lang_source(lang));
+        let sources: Vec<Option<&Source>> =
+            langs.iter().map(|lang| self.lang_source(lang)).collect();
+        let mut keys: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for source in sources.iter().chain([&base]).flatten() {
+            for (ns, entries) in &source.namespaces {
+                keys.entry(ns)
+                    .or_default()
+                    .extend(entries.keys().map(String::as_str));
+            }
+        }
+
+        let mut workbook = Workbook::new();
+        for (ns, keys) in keys {
+            let sheet = workbook.add_worksheet();
+            sheet
+                .set_name(ns)
+                .with_context(|| format!("[xlsx error]: namespace {ns} can't name a sheet."))?;
+            sheet.set_freeze_panes(1, 0)?;
+            let headers = ["key", "reference"].into_iter();
+            for (c, header) in headers.chain(langs.iter().map(String::as_str)).enumerate() {
+                sheet.write_string(0, c as u16, header)?;
+            }
+            for (r, key) in keys.into_iter().enumerate() {
+                let row = r as u32 + 1;
+                let reference = base.and_then(|base| base.get(key, ns)).unwrap_or(key);
+                sheet.write_string(row, 0, key)?;
+                sheet.write_string(row, 1, reference)?;
+                for (c, source) in sources.iter().enumerate() {
+                    if let Some(value) = source.and_then(|source| source.get(key, ns)) {
+                        sheet.write_string(row, c as u16 + 2, value)?;
+                    }
+                }
+            }
+        }
+        workbook
+            .save(path)
+            .with_context(|| format!("[xlsx error]: can't write {}.", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::source;
+    use rust_xlsxwriter::Formula;
+
+    #[test]
+    fn round_trip() {
+        let path = crate::tests::temp_path("easy_i18n_round_trip.xlsx");
+        let mut i18n = I18n::new("en");
+        let mut en = source("common", &[("保存", "Save 💾"), ("说明", "Line 1\nLine 2")]);
+        en.merge(source("checkout", &[("确认订单", "Confirm \"order\"")]));
+        i18n.insert_source("en", en);
+        i18n.insert_source("ja", source("common", &[("保存", "保存する 🎉")]));
+        i18n.export_xlsx(&path, &["en", "ja", "fr"]).unwrap();
+
+        let sources = import_xlsx(&path).unwrap();
+        let mut langs: Vec<&String> = sources.keys().collect();
+        langs.sort();
+        assert_eq!(langs, ["EN", "JA"]);
+        let (en, ja) = (&sources["EN"], &sources["JA"]);
+        assert_eq!(en.get("保存", "common"), Some("Save 💾"));
+        assert_eq!(en.get("说明", "common"), Some("Line 1\nLine 2"));
+        assert_eq!(en.get("确认订单", "checkout"), Some("Confirm \"order\""));
+        assert_eq!(ja.get("保存", "common"), Some("保存する 🎉"));
+        assert_eq!(ja.get("说明", "common"), None);
+    }
+
+    #[test]
+    fn hand_made_workbook() {
+        let path = crate::tests::temp_path("easy_i18n_hand_made.xlsx");
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet().set_name("common").unwrap();
+        sheet.write_row(0, 0, ["fr", "key", "notes"]).unwrap();
+        sheet
+            .write_row(1, 0, ["Enregistrer", "保存", "a verb"])
+            .unwrap();
+        sheet.write_number(3, 0, 42).unwrap();
+        sheet.write_string(3, 1, "答案").unwrap();
+        let formula = Formula::new(r#"="Annu"&"ler""#).set_result("Annuler");
+        sheet.write_formula(4, 0, formula).unwrap();
+        sheet.write_string(4, 1, "取消").unwrap();
+        workbook.save(&path).unwrap();
+
+        let sources = import_xlsx(&path).unwrap();
+        let fr = &sources["FR"];
+        assert_eq!(fr.get("保存", "common"), Some("Enregistrer"));
+        assert_eq!(fr.get("答案", "common"), Some("42"));
+        assert_eq!(fr.get("取消", "common"), Some("Annuler"));
+        // headers that are no language tag are skipped
+        assert!(!sources.contains_key("NOTES"));
+        assert_eq!(sources.len(), 1);
+        let sources = import_xlsx_langs(&path, &["FR", "notes"]).unwrap();
+        assert_eq!(sources["NOTES"].get("保存", "common"), Some("a verb"));
+        assert!(import_xlsx_langs(&path, &["de"]).unwrap().is_empty());
+        for (header, lang) in [
+            ("pt-BR", true),
+            ("zh_Hans", true),
+            ("CN", true),
+            ("notes", false),
+        ] {
+            assert_eq!(is_lang_tag(header), lang, "{header}");
+        }
+        assert_eq!(cell_name((0, 27)), "AB1");
+    }
+
+    #[test]
+    fn uncached_formula() {
+        // saved by a tool that doesn't compute formulas: B2 has no `<v>` element
+        let err = import_xlsx(Path::new("./fixtures/xlsx/uncached_formula.xlsx")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[xlsx error]: cell B2 of sheet common is a formula without a cached value."
+        );
+    }
+}